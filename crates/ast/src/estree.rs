@@ -0,0 +1,82 @@
+//! `ESTree` serialization of the nodes with fields acorn prints but the AST does not store,
+//! the `value` of a literal and the `kind` of a property
+
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use crate::{BooleanLiteral, NullLiteral, NumberLiteral, Property, Span, StringLiteral};
+
+/// `Number.MAX_SAFE_INTEGER + 1`, below it an integer `f64` is printed without a fraction
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+/// The fields of a `Literal`, the map is left open for a `bigint`
+fn literal<S: Serializer, V: Serialize + ?Sized>(
+    serializer: S,
+    span: Span,
+    value: &V,
+    raw: &str,
+) -> Result<S::SerializeMap, S::Error> {
+    let mut map = serializer.serialize_map(None)?;
+    map.serialize_entry("type", "Literal")?;
+    map.serialize_entry("start", &span.start)?;
+    map.serialize_entry("end", &span.end)?;
+    map.serialize_entry("value", value)?;
+    map.serialize_entry("raw", raw)?;
+    Ok(map)
+}
+
+impl Serialize for BooleanLiteral {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let raw = if self.value { "true" } else { "false" };
+        literal(serializer, self.span, &self.value, raw)?.end()
+    }
+}
+
+impl Serialize for NullLiteral {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        literal(serializer, self.span, &(), "null")?.end()
+    }
+}
+
+impl Serialize for NumberLiteral {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let map = match self.value() {
+            // `1` rather than `1.0`, as JavaScript prints it
+            Some(value) if value.fract() == 0.0 && (0.0..MAX_SAFE_INTEGER).contains(&value) => {
+                literal(serializer, self.span, &(value as u64), &self.raw)?
+            }
+            Some(value) => literal(serializer, self.span, &value, &self.raw)?,
+            // JSON has no `BigInt`, acorn leaves its digits in `bigint`
+            None => {
+                let mut map = literal(serializer, self.span, &(), &self.raw)?;
+                let digits = self.raw.trim_end_matches('n').replace('_', "");
+                map.serialize_entry("bigint", &digits)?;
+                map
+            }
+        };
+        map.end()
+    }
+}
+
+impl Serialize for StringLiteral {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        literal(serializer, self.span, &self.value(), &self.raw)?.end()
+    }
+}
+
+impl Serialize for Property {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("type", "Property")?;
+        map.serialize_entry("start", &self.span.start)?;
+        map.serialize_entry("end", &self.span.end)?;
+        // methods and accessors are not parsed
+        map.serialize_entry("method", &false)?;
+        map.serialize_entry("shorthand", &self.shorthand)?;
+        map.serialize_entry("computed", &self.computed)?;
+        map.serialize_entry("key", &self.key)?;
+        map.serialize_entry("value", &self.value)?;
+        map.serialize_entry("kind", "init")?;
+        map.end()
+    }
+}
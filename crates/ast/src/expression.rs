@@ -0,0 +1,294 @@
+//! Expressions, named and shaped after `ESTree`
+
+// the ESTree names
+#![allow(clippy::module_name_repetitions)]
+
+use std::fmt;
+
+use lexer::Span;
+
+use crate::literal::{
+    BooleanLiteral, NullLiteral, NumberLiteral, RegexLiteral, StringLiteral, TemplateLiteral,
+};
+use crate::operator::{
+    AssignmentOperator, BinaryOperator, LogicalOperator, UnaryOperator, UpdateOperator,
+};
+
+macro_rules! expression {
+    ($($variant:ident),* $(,)?) => {
+        /// Each variant boxes the node of its name
+        #[derive(Clone, PartialEq)]
+        #[cfg_attr(feature = "estree", derive(serde::Serialize), serde(untagged))]
+        pub enum Expression {
+            $($variant(Box<$variant>),)*
+        }
+
+        impl Expression {
+            #[must_use]
+            pub const fn span(&self) -> Span {
+                match self {
+                    $(Self::$variant(node) => node.span,)*
+                }
+            }
+        }
+
+        /// The node without the variant, `Identifier { span: 0..1, name: "a" }`
+        impl fmt::Debug for Expression {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    $(Self::$variant(node) => node.fmt(f),)*
+                }
+            }
+        }
+
+        $(
+            impl From<$variant> for Expression {
+                fn from(node: $variant) -> Self {
+                    Self::$variant(Box::new(node))
+                }
+            }
+        )*
+    };
+}
+
+expression!(
+    Identifier,
+    ThisExpression,
+    BooleanLiteral,
+    NullLiteral,
+    NumberLiteral,
+    StringLiteral,
+    RegexLiteral,
+    TemplateLiteral,
+    ArrayExpression,
+    ObjectExpression,
+    ParenthesizedExpression,
+    MemberExpression,
+    CallExpression,
+    NewExpression,
+    TaggedTemplateExpression,
+    UnaryExpression,
+    UpdateExpression,
+    BinaryExpression,
+    LogicalExpression,
+    ConditionalExpression,
+    AssignmentExpression,
+    SequenceExpression,
+    InvalidExpression,
+);
+
+/// An identifier reference, or the name of a property which can also be a keyword
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "estree", derive(serde::Serialize), serde(tag = "type"))]
+pub struct Identifier {
+    #[cfg_attr(feature = "estree", serde(flatten))]
+    pub span: Span,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "estree", derive(serde::Serialize), serde(tag = "type"))]
+pub struct ThisExpression {
+    #[cfg_attr(feature = "estree", serde(flatten))]
+    pub span: Span,
+}
+
+/// An element of an array or an argument of a call
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "estree", derive(serde::Serialize), serde(untagged))]
+pub enum Argument {
+    Expression(Expression),
+    SpreadElement(SpreadElement),
+}
+
+impl Argument {
+    #[must_use]
+    pub const fn span(&self) -> Span {
+        match self {
+            Self::Expression(expression) => expression.span(),
+            Self::SpreadElement(spread) => spread.span,
+        }
+    }
+}
+
+/// `...a` in an array, an object or arguments
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "estree", derive(serde::Serialize), serde(tag = "type"))]
+pub struct SpreadElement {
+    #[cfg_attr(feature = "estree", serde(flatten))]
+    pub span: Span,
+    pub argument: Expression,
+}
+
+/// `[a, , ...b]`, `None` for a hole
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "estree", derive(serde::Serialize), serde(tag = "type"))]
+pub struct ArrayExpression {
+    #[cfg_attr(feature = "estree", serde(flatten))]
+    pub span: Span,
+    pub elements: Vec<Option<Argument>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "estree", derive(serde::Serialize), serde(tag = "type"))]
+pub struct ObjectExpression {
+    #[cfg_attr(feature = "estree", serde(flatten))]
+    pub span: Span,
+    pub properties: Vec<ObjectProperty>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "estree", derive(serde::Serialize), serde(untagged))]
+pub enum ObjectProperty {
+    Property(Property),
+    SpreadElement(SpreadElement),
+}
+
+impl ObjectProperty {
+    #[must_use]
+    pub const fn span(&self) -> Span {
+        match self {
+            Self::Property(property) => property.span,
+            Self::SpreadElement(spread) => spread.span,
+        }
+    }
+}
+
+/// `a: b`, `[a]: b` when `computed`, `{ a }` when `shorthand` with the key as the value too
+#[derive(Debug, Clone, PartialEq)]
+pub struct Property {
+    pub span: Span,
+    pub key: Expression,
+    pub value: Expression,
+    pub computed: bool,
+    pub shorthand: bool,
+}
+
+/// Kept as a node, as acorn does with `preserveParens`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "estree", derive(serde::Serialize), serde(tag = "type"))]
+pub struct ParenthesizedExpression {
+    #[cfg_attr(feature = "estree", serde(flatten))]
+    pub span: Span,
+    pub expression: Expression,
+}
+
+/// `a.b`, `a[b]` when `computed`, `a?.b` when `optional`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "estree", derive(serde::Serialize), serde(tag = "type"))]
+pub struct MemberExpression {
+    #[cfg_attr(feature = "estree", serde(flatten))]
+    pub span: Span,
+    pub object: Expression,
+    pub property: Expression,
+    pub computed: bool,
+    pub optional: bool,
+}
+
+/// `a(b)`, `a?.(b)` when `optional`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "estree", derive(serde::Serialize), serde(tag = "type"))]
+pub struct CallExpression {
+    #[cfg_attr(feature = "estree", serde(flatten))]
+    pub span: Span,
+    pub callee: Expression,
+    pub arguments: Vec<Argument>,
+    pub optional: bool,
+}
+
+/// `new a(b)`, `new a` without arguments
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "estree", derive(serde::Serialize), serde(tag = "type"))]
+pub struct NewExpression {
+    #[cfg_attr(feature = "estree", serde(flatten))]
+    pub span: Span,
+    pub callee: Expression,
+    pub arguments: Vec<Argument>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "estree", derive(serde::Serialize), serde(tag = "type"))]
+pub struct TaggedTemplateExpression {
+    #[cfg_attr(feature = "estree", serde(flatten))]
+    pub span: Span,
+    pub tag: Expression,
+    pub quasi: TemplateLiteral,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "estree", derive(serde::Serialize), serde(tag = "type"))]
+pub struct UnaryExpression {
+    #[cfg_attr(feature = "estree", serde(flatten))]
+    pub span: Span,
+    pub operator: UnaryOperator,
+    pub argument: Expression,
+}
+
+/// `++a` when `prefix`, `a--` otherwise
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "estree", derive(serde::Serialize), serde(tag = "type"))]
+pub struct UpdateExpression {
+    #[cfg_attr(feature = "estree", serde(flatten))]
+    pub span: Span,
+    pub operator: UpdateOperator,
+    pub prefix: bool,
+    pub argument: Expression,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "estree", derive(serde::Serialize), serde(tag = "type"))]
+pub struct BinaryExpression {
+    #[cfg_attr(feature = "estree", serde(flatten))]
+    pub span: Span,
+    pub left: Expression,
+    pub operator: BinaryOperator,
+    pub right: Expression,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "estree", derive(serde::Serialize), serde(tag = "type"))]
+pub struct LogicalExpression {
+    #[cfg_attr(feature = "estree", serde(flatten))]
+    pub span: Span,
+    pub left: Expression,
+    pub operator: LogicalOperator,
+    pub right: Expression,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "estree", derive(serde::Serialize), serde(tag = "type"))]
+pub struct ConditionalExpression {
+    #[cfg_attr(feature = "estree", serde(flatten))]
+    pub span: Span,
+    pub test: Expression,
+    pub consequent: Expression,
+    pub alternate: Expression,
+}
+
+/// The left is an identifier or a member expression, also an array or an object for `=`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "estree", derive(serde::Serialize), serde(tag = "type"))]
+pub struct AssignmentExpression {
+    #[cfg_attr(feature = "estree", serde(flatten))]
+    pub span: Span,
+    pub left: Expression,
+    pub operator: AssignmentOperator,
+    pub right: Expression,
+}
+
+/// `a, b`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "estree", derive(serde::Serialize), serde(tag = "type"))]
+pub struct SequenceExpression {
+    #[cfg_attr(feature = "estree", serde(flatten))]
+    pub span: Span,
+    pub expressions: Vec<Expression>,
+}
+
+/// An error token, which the parser reports as a diagnostic, not in `ESTree`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "estree", derive(serde::Serialize), serde(tag = "type"))]
+pub struct InvalidExpression {
+    #[cfg_attr(feature = "estree", serde(flatten))]
+    pub span: Span,
+}
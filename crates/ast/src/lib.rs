@@ -0,0 +1,16 @@
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+#[cfg(feature = "estree")]
+mod estree;
+mod expression;
+mod literal;
+mod operator;
+mod visit;
+mod visit_mut;
+
+pub use crate::expression::*;
+pub use crate::literal::*;
+pub use crate::operator::*;
+pub use crate::visit::*;
+pub use crate::visit_mut::*;
+pub use lexer::Span;
@@ -0,0 +1,101 @@
+//! Literals, the `ESTree` `Literal` except for templates
+
+// the ESTree names
+#![allow(clippy::module_name_repetitions)]
+
+use lexer::{Number, Span};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BooleanLiteral {
+    pub span: Span,
+    pub value: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NullLiteral {
+    pub span: Span,
+}
+
+/// A number of any kind of the lexer, `BigInt` included
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberLiteral {
+    pub span: Span,
+    pub raw: String,
+    pub kind: Number,
+}
+
+impl NumberLiteral {
+    /// The value of the number, `None` for a `BigInt`
+    #[must_use]
+    pub fn value(&self) -> Option<f64> {
+        let raw = self.raw.replace('_', "");
+        let (digits, radix) = match self.kind {
+            Number::Decimal | Number::Float => return raw.parse().ok(),
+            Number::BigInt => return None,
+            Number::Binary => (raw.get(2..)?, 2),
+            Number::Hex => (raw.get(2..)?, 16),
+            // `0o17`, or the legacy `017`
+            Number::Octal => (raw.get(1..)?.trim_start_matches(['o', 'O']), 8),
+        };
+        digits.chars().try_fold(0.0, |value: f64, c| {
+            Some(value.mul_add(f64::from(radix), f64::from(c.to_digit(radix)?)))
+        })
+    }
+}
+
+/// A string with its quotes, escapes are not decoded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StringLiteral {
+    pub span: Span,
+    pub raw: String,
+}
+
+impl StringLiteral {
+    /// The value of the string with its escapes decoded, `None` for an invalid escape
+    #[must_use]
+    pub fn value(&self) -> Option<String> {
+        lexer::cook_string(&self.raw)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "estree",
+    derive(serde::Serialize),
+    serde(tag = "type", rename = "Literal")
+)]
+pub struct RegexLiteral {
+    #[cfg_attr(feature = "estree", serde(flatten))]
+    pub span: Span,
+    pub raw: String,
+    pub regex: RegExp,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "estree", derive(serde::Serialize))]
+pub struct RegExp {
+    pub pattern: String,
+    pub flags: String,
+}
+
+impl RegExp {
+    /// Pattern and flags of a regex token, `/a/g`
+    #[must_use]
+    pub fn from_raw(raw: &str) -> Self {
+        let raw = raw.strip_prefix('/').unwrap_or(raw);
+        let (pattern, flags) = raw.rsplit_once('/').unwrap_or((raw, ""));
+        Self {
+            pattern: pattern.to_string(),
+            flags: flags.to_string(),
+        }
+    }
+}
+
+/// A template is a single token of the lexer, its substitutions are not parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "estree", derive(serde::Serialize), serde(tag = "type"))]
+pub struct TemplateLiteral {
+    #[cfg_attr(feature = "estree", serde(flatten))]
+    pub span: Span,
+    pub raw: String,
+}
@@ -0,0 +1,277 @@
+//! Operators, serialized as their source text like `ESTree`
+
+// the ESTree names
+#![allow(clippy::module_name_repetitions)]
+
+use lexer::Kind;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinaryOperator {
+    Equality,
+    Inequality,
+    StrictEquality,
+    StrictInequality,
+    LessThan,
+    LessEqualThan,
+    GreaterThan,
+    GreaterEqualThan,
+    ShiftLeft,
+    ShiftRight,
+    ShiftRightZeroFill,
+    Addition,
+    Subtraction,
+    Multiplication,
+    Division,
+    Remainder,
+    Exponential,
+    BitwiseOr,
+    BitwiseXor,
+    BitwiseAnd,
+    In,
+    Instanceof,
+}
+
+impl BinaryOperator {
+    #[must_use]
+    pub const fn from_kind(kind: Kind) -> Option<Self> {
+        let operator = match kind {
+            Kind::Eq2 => Self::Equality,
+            Kind::Neq => Self::Inequality,
+            Kind::Eq3 => Self::StrictEquality,
+            Kind::Neq2 => Self::StrictInequality,
+            Kind::LAngle => Self::LessThan,
+            Kind::LtEq => Self::LessEqualThan,
+            Kind::RAngle => Self::GreaterThan,
+            Kind::GtEq => Self::GreaterEqualThan,
+            Kind::ShiftLeft => Self::ShiftLeft,
+            Kind::ShiftRight => Self::ShiftRight,
+            Kind::ShiftRight3 => Self::ShiftRightZeroFill,
+            Kind::Plus => Self::Addition,
+            Kind::Minus => Self::Subtraction,
+            Kind::Star => Self::Multiplication,
+            Kind::Slash => Self::Division,
+            Kind::Percent => Self::Remainder,
+            Kind::Star2 => Self::Exponential,
+            Kind::Pipe => Self::BitwiseOr,
+            Kind::Caret => Self::BitwiseXor,
+            Kind::Amp => Self::BitwiseAnd,
+            Kind::In => Self::In,
+            Kind::Instanceof => Self::Instanceof,
+            _ => return None,
+        };
+        Some(operator)
+    }
+
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Equality => "==",
+            Self::Inequality => "!=",
+            Self::StrictEquality => "===",
+            Self::StrictInequality => "!==",
+            Self::LessThan => "<",
+            Self::LessEqualThan => "<=",
+            Self::GreaterThan => ">",
+            Self::GreaterEqualThan => ">=",
+            Self::ShiftLeft => "<<",
+            Self::ShiftRight => ">>",
+            Self::ShiftRightZeroFill => ">>>",
+            Self::Addition => "+",
+            Self::Subtraction => "-",
+            Self::Multiplication => "*",
+            Self::Division => "/",
+            Self::Remainder => "%",
+            Self::Exponential => "**",
+            Self::BitwiseOr => "|",
+            Self::BitwiseXor => "^",
+            Self::BitwiseAnd => "&",
+            Self::In => "in",
+            Self::Instanceof => "instanceof",
+        }
+    }
+}
+
+/// `||`, `&&` and `??`, a `LogicalExpression` in `ESTree`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LogicalOperator {
+    Or,
+    And,
+    Coalesce,
+}
+
+impl LogicalOperator {
+    #[must_use]
+    pub const fn from_kind(kind: Kind) -> Option<Self> {
+        match kind {
+            Kind::Pipe2 => Some(Self::Or),
+            Kind::Amp2 => Some(Self::And),
+            Kind::Question2 => Some(Self::Coalesce),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Or => "||",
+            Self::And => "&&",
+            Self::Coalesce => "??",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnaryOperator {
+    Minus,
+    Plus,
+    LogicalNot,
+    BitwiseNot,
+    Typeof,
+    Void,
+    Delete,
+}
+
+impl UnaryOperator {
+    #[must_use]
+    pub const fn from_kind(kind: Kind) -> Option<Self> {
+        let operator = match kind {
+            Kind::Minus => Self::Minus,
+            Kind::Plus => Self::Plus,
+            Kind::Bang => Self::LogicalNot,
+            Kind::Tilde => Self::BitwiseNot,
+            Kind::Typeof => Self::Typeof,
+            Kind::Void => Self::Void,
+            Kind::Delete => Self::Delete,
+            _ => return None,
+        };
+        Some(operator)
+    }
+
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Minus => "-",
+            Self::Plus => "+",
+            Self::LogicalNot => "!",
+            Self::BitwiseNot => "~",
+            Self::Typeof => "typeof",
+            Self::Void => "void",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UpdateOperator {
+    Increment,
+    Decrement,
+}
+
+impl UpdateOperator {
+    #[must_use]
+    pub const fn from_kind(kind: Kind) -> Option<Self> {
+        match kind {
+            Kind::Plus2 => Some(Self::Increment),
+            Kind::Minus2 => Some(Self::Decrement),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Increment => "++",
+            Self::Decrement => "--",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssignmentOperator {
+    Assign,
+    Addition,
+    Subtraction,
+    Multiplication,
+    Division,
+    Remainder,
+    Exponential,
+    ShiftLeft,
+    ShiftRight,
+    ShiftRightZeroFill,
+    BitwiseOr,
+    BitwiseXor,
+    BitwiseAnd,
+    LogicalOr,
+    LogicalAnd,
+    LogicalCoalesce,
+}
+
+impl AssignmentOperator {
+    #[must_use]
+    pub const fn from_kind(kind: Kind) -> Option<Self> {
+        let operator = match kind {
+            Kind::Eq => Self::Assign,
+            Kind::PlusEq => Self::Addition,
+            Kind::MinusEq => Self::Subtraction,
+            Kind::StarEq => Self::Multiplication,
+            Kind::SlashEq => Self::Division,
+            Kind::PercentEq => Self::Remainder,
+            Kind::Star2Eq => Self::Exponential,
+            Kind::ShiftLeftEq => Self::ShiftLeft,
+            Kind::ShiftRightEq => Self::ShiftRight,
+            Kind::ShiftRight3Eq => Self::ShiftRightZeroFill,
+            Kind::PipeEq => Self::BitwiseOr,
+            Kind::CaretEq => Self::BitwiseXor,
+            Kind::AmpEq => Self::BitwiseAnd,
+            Kind::Pipe2Eq => Self::LogicalOr,
+            Kind::Amp2Eq => Self::LogicalAnd,
+            Kind::Question2Eq => Self::LogicalCoalesce,
+            _ => return None,
+        };
+        Some(operator)
+    }
+
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Assign => "=",
+            Self::Addition => "+=",
+            Self::Subtraction => "-=",
+            Self::Multiplication => "*=",
+            Self::Division => "/=",
+            Self::Remainder => "%=",
+            Self::Exponential => "**=",
+            Self::ShiftLeft => "<<=",
+            Self::ShiftRight => ">>=",
+            Self::ShiftRightZeroFill => ">>>=",
+            Self::BitwiseOr => "|=",
+            Self::BitwiseXor => "^=",
+            Self::BitwiseAnd => "&=",
+            Self::LogicalOr => "||=",
+            Self::LogicalAnd => "&&=",
+            Self::LogicalCoalesce => "??=",
+        }
+    }
+}
+
+#[cfg(feature = "estree")]
+macro_rules! serialize_as_str {
+    ($($operator:ty),*) => {
+        $(
+            impl serde::Serialize for $operator {
+                fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.serialize_str(self.as_str())
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "estree")]
+serialize_as_str!(
+    BinaryOperator,
+    LogicalOperator,
+    UnaryOperator,
+    UpdateOperator,
+    AssignmentOperator
+);
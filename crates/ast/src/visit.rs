@@ -0,0 +1,336 @@
+//! Traversal of the AST.
+//!
+//! Each method of [`Visit`] defaults to walking the children of its node with the `walk_*`
+//! function of the same name, an override calls it to keep going down.
+//! The walkers match every variant and destructure every node without `..`,
+//! so that a new node or child which is not walked does not compile.
+
+use crate::expression::{
+    Argument, ArrayExpression, AssignmentExpression, BinaryExpression, CallExpression,
+    ConditionalExpression, Expression, Identifier, InvalidExpression, LogicalExpression,
+    MemberExpression, NewExpression, ObjectExpression, ObjectProperty, ParenthesizedExpression,
+    Property, SequenceExpression, SpreadElement, TaggedTemplateExpression, ThisExpression,
+    UnaryExpression, UpdateExpression,
+};
+use crate::literal::{
+    BooleanLiteral, NullLiteral, NumberLiteral, RegexLiteral, StringLiteral, TemplateLiteral,
+};
+
+pub trait Visit {
+    fn visit_expression(&mut self, expression: &Expression) {
+        walk_expression(self, expression);
+    }
+
+    fn visit_identifier(&mut self, _identifier: &Identifier) {}
+
+    fn visit_this_expression(&mut self, _expression: &ThisExpression) {}
+
+    fn visit_boolean_literal(&mut self, _literal: &BooleanLiteral) {}
+
+    fn visit_null_literal(&mut self, _literal: &NullLiteral) {}
+
+    fn visit_number_literal(&mut self, _literal: &NumberLiteral) {}
+
+    fn visit_string_literal(&mut self, _literal: &StringLiteral) {}
+
+    fn visit_regex_literal(&mut self, _literal: &RegexLiteral) {}
+
+    fn visit_template_literal(&mut self, _literal: &TemplateLiteral) {}
+
+    fn visit_array_expression(&mut self, expression: &ArrayExpression) {
+        walk_array_expression(self, expression);
+    }
+
+    fn visit_argument(&mut self, argument: &Argument) {
+        walk_argument(self, argument);
+    }
+
+    fn visit_spread_element(&mut self, spread: &SpreadElement) {
+        walk_spread_element(self, spread);
+    }
+
+    fn visit_object_expression(&mut self, expression: &ObjectExpression) {
+        walk_object_expression(self, expression);
+    }
+
+    fn visit_object_property(&mut self, property: &ObjectProperty) {
+        walk_object_property(self, property);
+    }
+
+    fn visit_property(&mut self, property: &Property) {
+        walk_property(self, property);
+    }
+
+    fn visit_parenthesized_expression(&mut self, expression: &ParenthesizedExpression) {
+        walk_parenthesized_expression(self, expression);
+    }
+
+    fn visit_member_expression(&mut self, expression: &MemberExpression) {
+        walk_member_expression(self, expression);
+    }
+
+    fn visit_call_expression(&mut self, expression: &CallExpression) {
+        walk_call_expression(self, expression);
+    }
+
+    fn visit_new_expression(&mut self, expression: &NewExpression) {
+        walk_new_expression(self, expression);
+    }
+
+    fn visit_tagged_template_expression(&mut self, expression: &TaggedTemplateExpression) {
+        walk_tagged_template_expression(self, expression);
+    }
+
+    fn visit_unary_expression(&mut self, expression: &UnaryExpression) {
+        walk_unary_expression(self, expression);
+    }
+
+    fn visit_update_expression(&mut self, expression: &UpdateExpression) {
+        walk_update_expression(self, expression);
+    }
+
+    fn visit_binary_expression(&mut self, expression: &BinaryExpression) {
+        walk_binary_expression(self, expression);
+    }
+
+    fn visit_logical_expression(&mut self, expression: &LogicalExpression) {
+        walk_logical_expression(self, expression);
+    }
+
+    fn visit_conditional_expression(&mut self, expression: &ConditionalExpression) {
+        walk_conditional_expression(self, expression);
+    }
+
+    fn visit_assignment_expression(&mut self, expression: &AssignmentExpression) {
+        walk_assignment_expression(self, expression);
+    }
+
+    fn visit_sequence_expression(&mut self, expression: &SequenceExpression) {
+        walk_sequence_expression(self, expression);
+    }
+
+    fn visit_invalid_expression(&mut self, _expression: &InvalidExpression) {}
+}
+
+pub fn walk_expression<V: Visit + ?Sized>(visitor: &mut V, expression: &Expression) {
+    match expression {
+        Expression::Identifier(node) => visitor.visit_identifier(node),
+        Expression::ThisExpression(node) => visitor.visit_this_expression(node),
+        Expression::BooleanLiteral(node) => visitor.visit_boolean_literal(node),
+        Expression::NullLiteral(node) => visitor.visit_null_literal(node),
+        Expression::NumberLiteral(node) => visitor.visit_number_literal(node),
+        Expression::StringLiteral(node) => visitor.visit_string_literal(node),
+        Expression::RegexLiteral(node) => visitor.visit_regex_literal(node),
+        Expression::TemplateLiteral(node) => visitor.visit_template_literal(node),
+        Expression::ArrayExpression(node) => visitor.visit_array_expression(node),
+        Expression::ObjectExpression(node) => visitor.visit_object_expression(node),
+        Expression::ParenthesizedExpression(node) => visitor.visit_parenthesized_expression(node),
+        Expression::MemberExpression(node) => visitor.visit_member_expression(node),
+        Expression::CallExpression(node) => visitor.visit_call_expression(node),
+        Expression::NewExpression(node) => visitor.visit_new_expression(node),
+        Expression::TaggedTemplateExpression(node) => {
+            visitor.visit_tagged_template_expression(node);
+        }
+        Expression::UnaryExpression(node) => visitor.visit_unary_expression(node),
+        Expression::UpdateExpression(node) => visitor.visit_update_expression(node),
+        Expression::BinaryExpression(node) => visitor.visit_binary_expression(node),
+        Expression::LogicalExpression(node) => visitor.visit_logical_expression(node),
+        Expression::ConditionalExpression(node) => visitor.visit_conditional_expression(node),
+        Expression::AssignmentExpression(node) => visitor.visit_assignment_expression(node),
+        Expression::SequenceExpression(node) => visitor.visit_sequence_expression(node),
+        Expression::InvalidExpression(node) => visitor.visit_invalid_expression(node),
+    }
+}
+
+pub fn walk_array_expression<V: Visit + ?Sized>(visitor: &mut V, expression: &ArrayExpression) {
+    let ArrayExpression { span: _, elements } = expression;
+    for element in elements.iter().flatten() {
+        visitor.visit_argument(element);
+    }
+}
+
+pub fn walk_argument<V: Visit + ?Sized>(visitor: &mut V, argument: &Argument) {
+    match argument {
+        Argument::Expression(expression) => visitor.visit_expression(expression),
+        Argument::SpreadElement(spread) => visitor.visit_spread_element(spread),
+    }
+}
+
+pub fn walk_spread_element<V: Visit + ?Sized>(visitor: &mut V, spread: &SpreadElement) {
+    let SpreadElement { span: _, argument } = spread;
+    visitor.visit_expression(argument);
+}
+
+pub fn walk_object_expression<V: Visit + ?Sized>(visitor: &mut V, expression: &ObjectExpression) {
+    let ObjectExpression {
+        span: _,
+        properties,
+    } = expression;
+    for property in properties {
+        visitor.visit_object_property(property);
+    }
+}
+
+pub fn walk_object_property<V: Visit + ?Sized>(visitor: &mut V, property: &ObjectProperty) {
+    match property {
+        ObjectProperty::Property(property) => visitor.visit_property(property),
+        ObjectProperty::SpreadElement(spread) => visitor.visit_spread_element(spread),
+    }
+}
+
+/// The key then the value, both the same identifier for a shorthand
+pub fn walk_property<V: Visit + ?Sized>(visitor: &mut V, property: &Property) {
+    let Property {
+        span: _,
+        key,
+        value,
+        computed: _,
+        shorthand: _,
+    } = property;
+    visitor.visit_expression(key);
+    visitor.visit_expression(value);
+}
+
+pub fn walk_parenthesized_expression<V: Visit + ?Sized>(
+    visitor: &mut V,
+    expression: &ParenthesizedExpression,
+) {
+    let ParenthesizedExpression {
+        span: _,
+        expression,
+    } = expression;
+    visitor.visit_expression(expression);
+}
+
+pub fn walk_member_expression<V: Visit + ?Sized>(visitor: &mut V, expression: &MemberExpression) {
+    let MemberExpression {
+        span: _,
+        object,
+        property,
+        computed: _,
+        optional: _,
+    } = expression;
+    visitor.visit_expression(object);
+    visitor.visit_expression(property);
+}
+
+pub fn walk_call_expression<V: Visit + ?Sized>(visitor: &mut V, expression: &CallExpression) {
+    let CallExpression {
+        span: _,
+        callee,
+        arguments,
+        optional: _,
+    } = expression;
+    visitor.visit_expression(callee);
+    for argument in arguments {
+        visitor.visit_argument(argument);
+    }
+}
+
+pub fn walk_new_expression<V: Visit + ?Sized>(visitor: &mut V, expression: &NewExpression) {
+    let NewExpression {
+        span: _,
+        callee,
+        arguments,
+    } = expression;
+    visitor.visit_expression(callee);
+    for argument in arguments {
+        visitor.visit_argument(argument);
+    }
+}
+
+pub fn walk_tagged_template_expression<V: Visit + ?Sized>(
+    visitor: &mut V,
+    expression: &TaggedTemplateExpression,
+) {
+    let TaggedTemplateExpression {
+        span: _,
+        tag,
+        quasi,
+    } = expression;
+    visitor.visit_expression(tag);
+    visitor.visit_template_literal(quasi);
+}
+
+pub fn walk_unary_expression<V: Visit + ?Sized>(visitor: &mut V, expression: &UnaryExpression) {
+    let UnaryExpression {
+        span: _,
+        operator: _,
+        argument,
+    } = expression;
+    visitor.visit_expression(argument);
+}
+
+pub fn walk_update_expression<V: Visit + ?Sized>(visitor: &mut V, expression: &UpdateExpression) {
+    let UpdateExpression {
+        span: _,
+        operator: _,
+        prefix: _,
+        argument,
+    } = expression;
+    visitor.visit_expression(argument);
+}
+
+pub fn walk_binary_expression<V: Visit + ?Sized>(visitor: &mut V, expression: &BinaryExpression) {
+    let BinaryExpression {
+        span: _,
+        left,
+        operator: _,
+        right,
+    } = expression;
+    visitor.visit_expression(left);
+    visitor.visit_expression(right);
+}
+
+pub fn walk_logical_expression<V: Visit + ?Sized>(visitor: &mut V, expression: &LogicalExpression) {
+    let LogicalExpression {
+        span: _,
+        left,
+        operator: _,
+        right,
+    } = expression;
+    visitor.visit_expression(left);
+    visitor.visit_expression(right);
+}
+
+pub fn walk_conditional_expression<V: Visit + ?Sized>(
+    visitor: &mut V,
+    expression: &ConditionalExpression,
+) {
+    let ConditionalExpression {
+        span: _,
+        test,
+        consequent,
+        alternate,
+    } = expression;
+    visitor.visit_expression(test);
+    visitor.visit_expression(consequent);
+    visitor.visit_expression(alternate);
+}
+
+pub fn walk_assignment_expression<V: Visit + ?Sized>(
+    visitor: &mut V,
+    expression: &AssignmentExpression,
+) {
+    let AssignmentExpression {
+        span: _,
+        left,
+        operator: _,
+        right,
+    } = expression;
+    visitor.visit_expression(left);
+    visitor.visit_expression(right);
+}
+
+pub fn walk_sequence_expression<V: Visit + ?Sized>(
+    visitor: &mut V,
+    expression: &SequenceExpression,
+) {
+    let SequenceExpression {
+        span: _,
+        expressions,
+    } = expression;
+    for expression in expressions {
+        visitor.visit_expression(expression);
+    }
+}
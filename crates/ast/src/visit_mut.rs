@@ -0,0 +1,362 @@
+//! Traversal of the AST with mutable access to the nodes, otherwise the same as [`crate::Visit`]
+//! with `walk_*_mut` functions.
+
+use crate::expression::{
+    Argument, ArrayExpression, AssignmentExpression, BinaryExpression, CallExpression,
+    ConditionalExpression, Expression, Identifier, InvalidExpression, LogicalExpression,
+    MemberExpression, NewExpression, ObjectExpression, ObjectProperty, ParenthesizedExpression,
+    Property, SequenceExpression, SpreadElement, TaggedTemplateExpression, ThisExpression,
+    UnaryExpression, UpdateExpression,
+};
+use crate::literal::{
+    BooleanLiteral, NullLiteral, NumberLiteral, RegexLiteral, StringLiteral, TemplateLiteral,
+};
+
+pub trait VisitMut {
+    fn visit_expression(&mut self, expression: &mut Expression) {
+        walk_expression_mut(self, expression);
+    }
+
+    fn visit_identifier(&mut self, _identifier: &mut Identifier) {}
+
+    fn visit_this_expression(&mut self, _expression: &mut ThisExpression) {}
+
+    fn visit_boolean_literal(&mut self, _literal: &mut BooleanLiteral) {}
+
+    fn visit_null_literal(&mut self, _literal: &mut NullLiteral) {}
+
+    fn visit_number_literal(&mut self, _literal: &mut NumberLiteral) {}
+
+    fn visit_string_literal(&mut self, _literal: &mut StringLiteral) {}
+
+    fn visit_regex_literal(&mut self, _literal: &mut RegexLiteral) {}
+
+    fn visit_template_literal(&mut self, _literal: &mut TemplateLiteral) {}
+
+    fn visit_array_expression(&mut self, expression: &mut ArrayExpression) {
+        walk_array_expression_mut(self, expression);
+    }
+
+    fn visit_argument(&mut self, argument: &mut Argument) {
+        walk_argument_mut(self, argument);
+    }
+
+    fn visit_spread_element(&mut self, spread: &mut SpreadElement) {
+        walk_spread_element_mut(self, spread);
+    }
+
+    fn visit_object_expression(&mut self, expression: &mut ObjectExpression) {
+        walk_object_expression_mut(self, expression);
+    }
+
+    fn visit_object_property(&mut self, property: &mut ObjectProperty) {
+        walk_object_property_mut(self, property);
+    }
+
+    fn visit_property(&mut self, property: &mut Property) {
+        walk_property_mut(self, property);
+    }
+
+    fn visit_parenthesized_expression(&mut self, expression: &mut ParenthesizedExpression) {
+        walk_parenthesized_expression_mut(self, expression);
+    }
+
+    fn visit_member_expression(&mut self, expression: &mut MemberExpression) {
+        walk_member_expression_mut(self, expression);
+    }
+
+    fn visit_call_expression(&mut self, expression: &mut CallExpression) {
+        walk_call_expression_mut(self, expression);
+    }
+
+    fn visit_new_expression(&mut self, expression: &mut NewExpression) {
+        walk_new_expression_mut(self, expression);
+    }
+
+    fn visit_tagged_template_expression(&mut self, expression: &mut TaggedTemplateExpression) {
+        walk_tagged_template_expression_mut(self, expression);
+    }
+
+    fn visit_unary_expression(&mut self, expression: &mut UnaryExpression) {
+        walk_unary_expression_mut(self, expression);
+    }
+
+    fn visit_update_expression(&mut self, expression: &mut UpdateExpression) {
+        walk_update_expression_mut(self, expression);
+    }
+
+    fn visit_binary_expression(&mut self, expression: &mut BinaryExpression) {
+        walk_binary_expression_mut(self, expression);
+    }
+
+    fn visit_logical_expression(&mut self, expression: &mut LogicalExpression) {
+        walk_logical_expression_mut(self, expression);
+    }
+
+    fn visit_conditional_expression(&mut self, expression: &mut ConditionalExpression) {
+        walk_conditional_expression_mut(self, expression);
+    }
+
+    fn visit_assignment_expression(&mut self, expression: &mut AssignmentExpression) {
+        walk_assignment_expression_mut(self, expression);
+    }
+
+    fn visit_sequence_expression(&mut self, expression: &mut SequenceExpression) {
+        walk_sequence_expression_mut(self, expression);
+    }
+
+    fn visit_invalid_expression(&mut self, _expression: &mut InvalidExpression) {}
+}
+
+pub fn walk_expression_mut<V: VisitMut + ?Sized>(visitor: &mut V, expression: &mut Expression) {
+    match expression {
+        Expression::Identifier(node) => visitor.visit_identifier(node),
+        Expression::ThisExpression(node) => visitor.visit_this_expression(node),
+        Expression::BooleanLiteral(node) => visitor.visit_boolean_literal(node),
+        Expression::NullLiteral(node) => visitor.visit_null_literal(node),
+        Expression::NumberLiteral(node) => visitor.visit_number_literal(node),
+        Expression::StringLiteral(node) => visitor.visit_string_literal(node),
+        Expression::RegexLiteral(node) => visitor.visit_regex_literal(node),
+        Expression::TemplateLiteral(node) => visitor.visit_template_literal(node),
+        Expression::ArrayExpression(node) => visitor.visit_array_expression(node),
+        Expression::ObjectExpression(node) => visitor.visit_object_expression(node),
+        Expression::ParenthesizedExpression(node) => visitor.visit_parenthesized_expression(node),
+        Expression::MemberExpression(node) => visitor.visit_member_expression(node),
+        Expression::CallExpression(node) => visitor.visit_call_expression(node),
+        Expression::NewExpression(node) => visitor.visit_new_expression(node),
+        Expression::TaggedTemplateExpression(node) => {
+            visitor.visit_tagged_template_expression(node);
+        }
+        Expression::UnaryExpression(node) => visitor.visit_unary_expression(node),
+        Expression::UpdateExpression(node) => visitor.visit_update_expression(node),
+        Expression::BinaryExpression(node) => visitor.visit_binary_expression(node),
+        Expression::LogicalExpression(node) => visitor.visit_logical_expression(node),
+        Expression::ConditionalExpression(node) => visitor.visit_conditional_expression(node),
+        Expression::AssignmentExpression(node) => visitor.visit_assignment_expression(node),
+        Expression::SequenceExpression(node) => visitor.visit_sequence_expression(node),
+        Expression::InvalidExpression(node) => visitor.visit_invalid_expression(node),
+    }
+}
+
+pub fn walk_array_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut ArrayExpression,
+) {
+    let ArrayExpression { span: _, elements } = expression;
+    for element in elements.iter_mut().flatten() {
+        visitor.visit_argument(element);
+    }
+}
+
+pub fn walk_argument_mut<V: VisitMut + ?Sized>(visitor: &mut V, argument: &mut Argument) {
+    match argument {
+        Argument::Expression(expression) => visitor.visit_expression(expression),
+        Argument::SpreadElement(spread) => visitor.visit_spread_element(spread),
+    }
+}
+
+pub fn walk_spread_element_mut<V: VisitMut + ?Sized>(visitor: &mut V, spread: &mut SpreadElement) {
+    let SpreadElement { span: _, argument } = spread;
+    visitor.visit_expression(argument);
+}
+
+pub fn walk_object_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut ObjectExpression,
+) {
+    let ObjectExpression {
+        span: _,
+        properties,
+    } = expression;
+    for property in properties {
+        visitor.visit_object_property(property);
+    }
+}
+
+pub fn walk_object_property_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    property: &mut ObjectProperty,
+) {
+    match property {
+        ObjectProperty::Property(property) => visitor.visit_property(property),
+        ObjectProperty::SpreadElement(spread) => visitor.visit_spread_element(spread),
+    }
+}
+
+/// The key then the value, both the same identifier for a shorthand
+pub fn walk_property_mut<V: VisitMut + ?Sized>(visitor: &mut V, property: &mut Property) {
+    let Property {
+        span: _,
+        key,
+        value,
+        computed: _,
+        shorthand: _,
+    } = property;
+    visitor.visit_expression(key);
+    visitor.visit_expression(value);
+}
+
+pub fn walk_parenthesized_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut ParenthesizedExpression,
+) {
+    let ParenthesizedExpression {
+        span: _,
+        expression,
+    } = expression;
+    visitor.visit_expression(expression);
+}
+
+pub fn walk_member_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut MemberExpression,
+) {
+    let MemberExpression {
+        span: _,
+        object,
+        property,
+        computed: _,
+        optional: _,
+    } = expression;
+    visitor.visit_expression(object);
+    visitor.visit_expression(property);
+}
+
+pub fn walk_call_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut CallExpression,
+) {
+    let CallExpression {
+        span: _,
+        callee,
+        arguments,
+        optional: _,
+    } = expression;
+    visitor.visit_expression(callee);
+    for argument in arguments {
+        visitor.visit_argument(argument);
+    }
+}
+
+pub fn walk_new_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut NewExpression,
+) {
+    let NewExpression {
+        span: _,
+        callee,
+        arguments,
+    } = expression;
+    visitor.visit_expression(callee);
+    for argument in arguments {
+        visitor.visit_argument(argument);
+    }
+}
+
+pub fn walk_tagged_template_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut TaggedTemplateExpression,
+) {
+    let TaggedTemplateExpression {
+        span: _,
+        tag,
+        quasi,
+    } = expression;
+    visitor.visit_expression(tag);
+    visitor.visit_template_literal(quasi);
+}
+
+pub fn walk_unary_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut UnaryExpression,
+) {
+    let UnaryExpression {
+        span: _,
+        operator: _,
+        argument,
+    } = expression;
+    visitor.visit_expression(argument);
+}
+
+pub fn walk_update_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut UpdateExpression,
+) {
+    let UpdateExpression {
+        span: _,
+        operator: _,
+        prefix: _,
+        argument,
+    } = expression;
+    visitor.visit_expression(argument);
+}
+
+pub fn walk_binary_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut BinaryExpression,
+) {
+    let BinaryExpression {
+        span: _,
+        left,
+        operator: _,
+        right,
+    } = expression;
+    visitor.visit_expression(left);
+    visitor.visit_expression(right);
+}
+
+pub fn walk_logical_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut LogicalExpression,
+) {
+    let LogicalExpression {
+        span: _,
+        left,
+        operator: _,
+        right,
+    } = expression;
+    visitor.visit_expression(left);
+    visitor.visit_expression(right);
+}
+
+pub fn walk_conditional_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut ConditionalExpression,
+) {
+    let ConditionalExpression {
+        span: _,
+        test,
+        consequent,
+        alternate,
+    } = expression;
+    visitor.visit_expression(test);
+    visitor.visit_expression(consequent);
+    visitor.visit_expression(alternate);
+}
+
+pub fn walk_assignment_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut AssignmentExpression,
+) {
+    let AssignmentExpression {
+        span: _,
+        left,
+        operator: _,
+        right,
+    } = expression;
+    visitor.visit_expression(left);
+    visitor.visit_expression(right);
+}
+
+pub fn walk_sequence_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut SequenceExpression,
+) {
+    let SequenceExpression {
+        span: _,
+        expressions,
+    } = expression;
+    for expression in expressions {
+        visitor.visit_expression(expression);
+    }
+}
@@ -0,0 +1,253 @@
+use ast::{
+    Argument, AssignmentOperator, BinaryExpression, BinaryOperator, CallExpression, Expression,
+    Identifier, LogicalExpression, LogicalOperator, NumberLiteral, RegExp, Span, StringLiteral,
+    UnaryOperator, UpdateOperator, VisitMut,
+};
+#[cfg(feature = "estree")]
+use ast::{BooleanLiteral, NullLiteral, ObjectExpression, ObjectProperty, Property};
+use lexer::{Kind, Number};
+
+fn ident(name: &str, start: usize) -> Expression {
+    Identifier {
+        span: Span::new(start, start + name.len()),
+        name: name.to_string(),
+    }
+    .into()
+}
+
+/// `a + 1` at `start`
+fn addition(start: usize) -> Expression {
+    BinaryExpression {
+        span: Span::new(start, start + 5),
+        left: ident("a", start),
+        operator: BinaryOperator::Addition,
+        right: NumberLiteral {
+            span: Span::new(start + 4, start + 5),
+            raw: "1".to_string(),
+            kind: Number::Decimal,
+        }
+        .into(),
+    }
+    .into()
+}
+
+#[test]
+fn spans() {
+    let expression = addition(2);
+    assert_eq!(expression.span(), Span::new(2, 7));
+    // `f(a + 1)`
+    let call = CallExpression {
+        span: Span::new(0, 8),
+        callee: ident("f", 0),
+        arguments: vec![Argument::Expression(expression)],
+        optional: false,
+    };
+    assert_eq!(call.arguments[0].span().range(), 2..7);
+    assert_eq!(Span::new(0, 1).to(Span::new(4, 5)), Span::new(0, 5));
+}
+
+#[test]
+fn debug() {
+    let expression = addition(0);
+    assert_eq!(
+        format!("{expression:?}"),
+        "BinaryExpression { span: 0..5, left: Identifier { span: 0..1, name: \"a\" }, \
+         operator: Addition, right: NumberLiteral { span: 4..5, raw: \"1\", kind: Decimal } }"
+    );
+}
+
+#[test]
+fn logical() {
+    let expression: Expression = LogicalExpression {
+        span: Span::new(0, 6),
+        left: ident("a", 0),
+        operator: LogicalOperator::Coalesce,
+        right: ident("b", 5),
+    }
+    .into();
+    assert!(matches!(expression, Expression::LogicalExpression(_)));
+    assert_ne!(expression, addition(0));
+}
+
+#[test]
+fn operators() {
+    for kind in [Kind::Plus, Kind::Star2, Kind::Instanceof, Kind::ShiftRight3] {
+        let operator = BinaryOperator::from_kind(kind).unwrap();
+        assert_eq!(operator.as_str(), kind.to_string());
+    }
+    for kind in [Kind::Pipe2, Kind::Amp2, Kind::Question2] {
+        let operator = LogicalOperator::from_kind(kind).unwrap();
+        assert_eq!(operator.as_str(), kind.to_string());
+        assert_eq!(BinaryOperator::from_kind(kind), None);
+    }
+    for kind in [Kind::Bang, Kind::Typeof, Kind::Delete] {
+        let operator = UnaryOperator::from_kind(kind).unwrap();
+        assert_eq!(operator.as_str(), kind.to_string());
+    }
+    for kind in [Kind::Plus2, Kind::Minus2] {
+        let operator = UpdateOperator::from_kind(kind).unwrap();
+        assert_eq!(operator.as_str(), kind.to_string());
+    }
+    for kind in [Kind::Eq, Kind::Star2Eq, Kind::Question2Eq] {
+        let operator = AssignmentOperator::from_kind(kind).unwrap();
+        assert_eq!(operator.as_str(), kind.to_string());
+    }
+    assert_eq!(AssignmentOperator::from_kind(Kind::Eq2), None);
+}
+
+#[test]
+fn regexp() {
+    let regexp = RegExp::from_raw("/a\\/b/gu");
+    assert_eq!(regexp.pattern, "a\\/b");
+    assert_eq!(regexp.flags, "gu");
+}
+
+#[test]
+fn literal_values() {
+    let number = |raw: &str, kind| {
+        NumberLiteral {
+            span: Span::new(0, raw.len()),
+            raw: raw.to_string(),
+            kind,
+        }
+        .value()
+    };
+    assert_eq!(number("1_000", Number::Decimal), Some(1000.0));
+    assert_eq!(number("0.5e-2", Number::Float), Some(0.005));
+    assert_eq!(number("0x1F", Number::Hex), Some(31.0));
+    assert_eq!(number("0b101", Number::Binary), Some(5.0));
+    assert_eq!(number("0o17", Number::Octal), Some(15.0));
+    assert_eq!(number("017", Number::Octal), Some(15.0));
+    assert_eq!(number("10n", Number::BigInt), None);
+    let string = |raw: &str| {
+        StringLiteral {
+            span: Span::new(0, raw.len()),
+            raw: raw.to_string(),
+        }
+        .value()
+    };
+    assert_eq!(string("'c\\n'").as_deref(), Some("c\n"));
+    assert_eq!(string("'\\x'"), None);
+}
+
+#[cfg(feature = "estree")]
+#[test]
+fn estree() {
+    let json = serde_json::to_value(&addition(0)).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "type": "BinaryExpression",
+            "start": 0,
+            "end": 5,
+            "left": serde_json::json!({ "type": "Identifier", "start": 0, "end": 1, "name": "a" }),
+            "operator": "+",
+            "right": serde_json::json!({
+                "type": "Literal",
+                "start": 4,
+                "end": 5,
+                "value": 1,
+                "raw": "1"
+            }),
+        })
+    );
+}
+
+/// `key: value`
+#[cfg(feature = "estree")]
+fn property(key: Expression, value: Expression) -> ObjectProperty {
+    ObjectProperty::Property(Property {
+        span: key.span().to(value.span()),
+        key,
+        value,
+        computed: false,
+        shorthand: false,
+    })
+}
+
+#[cfg(feature = "estree")]
+#[test]
+fn estree_literals() {
+    let number = |start, raw: &str, kind| -> Expression {
+        NumberLiteral {
+            span: Span::new(start, start + raw.len()),
+            raw: raw.to_string(),
+            kind,
+        }
+        .into()
+    };
+    let string = |start, raw: &str| -> Expression {
+        StringLiteral {
+            span: Span::new(start, start + raw.len()),
+            raw: raw.to_string(),
+        }
+        .into()
+    };
+    // `{a: 0x10, 'b': "c\n", c: true, d: null, e: 10n, f: 2.5}`
+    let expression: Expression = ObjectExpression {
+        span: Span::new(0, 55),
+        properties: vec![
+            property(ident("a", 1), number(4, "0x10", Number::Hex)),
+            property(string(10, "'b'"), string(15, "\"c\\n\"")),
+            property(
+                ident("c", 22),
+                BooleanLiteral {
+                    span: Span::new(25, 29),
+                    value: true,
+                }
+                .into(),
+            ),
+            property(
+                ident("d", 31),
+                NullLiteral {
+                    span: Span::new(34, 38),
+                }
+                .into(),
+            ),
+            property(ident("e", 40), number(43, "10n", Number::BigInt)),
+            property(ident("f", 48), number(51, "2.5", Number::Float)),
+        ],
+    }
+    .into();
+    // as acorn parses it, a `BigInt` value aside which JSON cannot hold
+    let fixture: serde_json::Value =
+        serde_json::from_str(include_str!("fixtures/object.json")).unwrap();
+    assert_eq!(serde_json::to_value(&expression).unwrap(), fixture);
+}
+
+/// Renames every identifier `from` to `to`
+struct Rename {
+    from: &'static str,
+    to: &'static str,
+}
+
+impl VisitMut for Rename {
+    fn visit_identifier(&mut self, identifier: &mut Identifier) {
+        if identifier.name == self.from {
+            identifier.name = self.to.to_string();
+        }
+    }
+}
+
+#[test]
+fn visit_mut() {
+    // `f(a + 1, a)`
+    let mut expression: Expression = CallExpression {
+        span: Span::new(0, 11),
+        callee: ident("f", 0),
+        arguments: vec![
+            Argument::Expression(addition(2)),
+            Argument::Expression(ident("a", 9)),
+        ],
+        optional: false,
+    }
+    .into();
+    Rename { from: "a", to: "b" }.visit_expression(&mut expression);
+    let call = match expression {
+        Expression::CallExpression(call) => call,
+        _ => unreachable!(),
+    };
+    assert_eq!(call.callee, ident("f", 0));
+    assert_eq!(call.arguments[1], Argument::Expression(ident("b", 9)));
+    assert!(format!("{:?}", call.arguments[0]).contains("name: \"b\""));
+}
@@ -1,20 +1,89 @@
+//! `cli <path>` prints the tokens of a file, stdin for `-` or without a path.
+//! `cli <path> --count-only` prints only the number of significant tokens.
+//! `cli <path> --filter Str --filter Number` only prints or counts the tokens of these kinds.
+
 #![warn(clippy::all, clippy::pedantic, clippy::nursery)]
 
 use pico_args::Arguments;
-use std::fs::read_to_string;
+use std::{
+    fs::read_to_string,
+    io::{self, Read},
+    process,
+};
+
+use lexer::{count_significant_tokens, Kind, Lexer, Token};
+
+/// `path`, or stdin for `-`
+fn read_source(path: &str) -> io::Result<String> {
+    if path == "-" {
+        let mut code = String::new();
+        io::stdin().read_to_string(&mut code)?;
+        Ok(code)
+    } else {
+        read_to_string(path)
+    }
+}
 
-use lexer::Lexer;
+/// Name of the variant of `kind`, e.g. `Str`, or `Number` for every kind of number
+fn kind_name(kind: Kind) -> String {
+    let name = format!("{kind:?}");
+    match name.split_once('(') {
+        Some((name, _)) => name.to_string(),
+        None => name,
+    }
+}
+
+/// Check the kind names of `--filter`
+fn check_filters(filters: &[String]) -> Result<(), String> {
+    let mut names = Kind::ALL.iter().copied().map(kind_name).collect::<Vec<_>>();
+    // a `Number` per kind of number
+    names.dedup();
+    match filters.iter().find(|filter| !names.contains(filter)) {
+        Some(filter) => Err(format!(
+            "Unknown token kind `{}` for `--filter`, expected one of {}",
+            filter,
+            names.join(", ")
+        )),
+        None => Ok(()),
+    }
+}
 
 /// # Errors
-/// # Panics
+/// Invalid arguments
 pub fn main() -> Result<(), pico_args::Error> {
     let mut args = Arguments::from_env();
 
-    let path = args.free_from_str::<String>()?;
+    let count_only = args.contains("--count-only");
+    let filters: Vec<String> = args.values_from_str("--filter")?;
+    let path = args
+        .opt_free_from_str::<String>()?
+        .unwrap_or_else(|| "-".to_string());
+
+    if let Err(e) = check_filters(&filters) {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+
+    let code = read_source(&path).unwrap_or_else(|e| {
+        eprintln!("{}: {}", path, e);
+        process::exit(1);
+    });
+
+    if count_only && filters.is_empty() {
+        println!("{}", count_significant_tokens(&code));
+        return Ok(());
+    }
+
+    let tokens = Lexer::new(code.as_str())
+        .into_iter()
+        .filter(|token| filters.is_empty() || filters.contains(&kind_name(*token.kind())));
 
-    let code = read_to_string(&path).unwrap();
+    if count_only {
+        println!("{}", tokens.filter(Token::is_significant).count());
+        return Ok(());
+    }
 
-    let tokens = Lexer::new(code.as_str()).into_iter().collect::<Vec<_>>();
+    let tokens = tokens.collect::<Vec<_>>();
 
     println!("{tokens:?}");
 
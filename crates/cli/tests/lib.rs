@@ -0,0 +1,82 @@
+use std::{
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+/// Run the cli with `args`, feeding `stdin`
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_cli"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn tokens_from_stdin() {
+    for args in [&["-"][..], &[]] {
+        let output = run(args, "a b");
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "[Token { kind: Ident, offset: 0, len: 1 }, \
+             Token { kind: WhiteSpace, offset: 1, len: 1 }, \
+             Token { kind: Ident, offset: 2, len: 1 }, \
+             Token { kind: EOF, offset: 3, len: 1 }]\n"
+        );
+    }
+}
+
+#[test]
+fn read_error() {
+    let output = run(&["missing.js"], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("missing.js: "), "{}", stderr);
+}
+
+#[test]
+fn count_only_from_stdin() {
+    let output = run(&["-", "--count-only"], "let a = 1; // comment\nf(a);\n");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "10\n");
+}
+
+#[test]
+fn filter() {
+    let source = "let a = 'b' + c; // d\n";
+    let output = run(&["--filter", "Ident"], source);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "[Token { kind: Ident, offset: 4, len: 1 }, Token { kind: Ident, offset: 14, len: 1 }]\n"
+    );
+
+    let output = run(
+        &["--filter", "Ident", "--filter", "Str", "--count-only"],
+        source,
+    );
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "3\n");
+
+    let output = run(&["--filter", "Number", "--count-only"], "1 + 0x2 + 3n");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "3\n");
+
+    let output = run(&["--filter", "String"], source);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.starts_with("Unknown token kind `String` for `--filter`, expected one of "),
+        "{}",
+        stderr
+    );
+}
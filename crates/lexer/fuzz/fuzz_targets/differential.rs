@@ -0,0 +1,65 @@
+//! The number and string literals of our lexer and of [ress](https://crates.io/crates/ress),
+//! `cargo +nightly fuzz run differential --features differential`
+
+#![no_main]
+
+use std::ops::Range;
+
+use lexer::{Kind, Lexer};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Debug, PartialEq, Eq)]
+enum Literal {
+    Number,
+    String,
+}
+
+// Known divergences skip the input, the tokens after them may differ:
+//   * regexes, our heuristic and ress disagree on `/` after `)` and `}`
+//   * templates, which ress splits at `${`
+type Literals = Option<Vec<(Literal, Range<usize>)>>;
+
+fn ours(source: &str) -> Literals {
+    let mut literals = vec![];
+    for token in Lexer::new(source) {
+        let literal = match token.kind() {
+            Kind::Regex | Kind::Template => return None,
+            Kind::Number(_) => Literal::Number,
+            Kind::Str(_) => Literal::String,
+            _ => continue,
+        };
+        literals.push((literal, token.range()));
+    }
+    Some(literals)
+}
+
+/// `None` for an input ress rejects as well
+fn theirs(source: &str) -> Literals {
+    let mut literals = vec![];
+    for item in ress::Scanner::new(source) {
+        let item = item.ok()?;
+        let token = &item.token;
+        if token.is_eof() {
+            break;
+        }
+        let literal = if token.is_regex() || token.is_template() {
+            return None;
+        } else if token.is_number() {
+            Literal::Number
+        } else if token.is_string() {
+            Literal::String
+        } else {
+            continue;
+        };
+        literals.push((literal, item.span.start..item.span.end));
+    }
+    Some(literals)
+}
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(s) = std::str::from_utf8(data) {
+        if let (Some(ours), Some(theirs)) = (ours(s), theirs(s)) {
+            assert_eq!(ours, theirs, "literals of {:?}", s);
+        }
+    }
+});
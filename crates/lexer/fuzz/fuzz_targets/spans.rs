@@ -0,0 +1,23 @@
+#![no_main]
+
+use lexer::{Kind, Lexer};
+use libfuzzer_sys::fuzz_target;
+
+// The tokens tile the input: contiguous from 0 to its length, none empty, then `EOF`
+fuzz_target!(|data: &[u8]| {
+    if let Ok(s) = std::str::from_utf8(data) {
+        let tokens = Lexer::new(s).into_iter().collect::<Vec<_>>();
+        let (eof, tokens) = tokens.split_last().expect("no EOF");
+        assert_eq!(eof.kind(), &Kind::EOF);
+        assert_eq!(eof.range().start, s.len());
+        let mut end = 0;
+        for token in tokens {
+            assert_ne!(token.kind(), &Kind::EOF, "EOF before the end: {:?}", token);
+            assert_eq!(token.range().start, end, "gap or overlap: {:?}", token);
+            assert!(!token.is_empty(), "empty token: {:?}", token);
+            end = token.range().end;
+            assert!(end <= s.len(), "past the end: {:?}", token);
+        }
+        assert_eq!(end, s.len());
+    }
+});
@@ -0,0 +1,56 @@
+#![no_main]
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Once,
+    },
+    time::Instant,
+};
+
+use lexer::Lexer;
+use libfuzzer_sys::fuzz_target;
+
+/// Lexing may be this many times slower per byte than the calibration input
+const MAX_SLOWDOWN: f64 = 50.0;
+
+/// Inputs are timed as at least this long, timings of short inputs are noise
+const MIN_LEN: usize = 4096;
+
+const CALIBRATION: &str = "function f(a, b) { return a + b * 2; } // add\n\
+    const s = 'str' + `t${x}`, r = /re[a-z]+/g, n = 0x1F + 1.5e3;\n";
+
+static CALIBRATE: Once = Once::new();
+/// Nanoseconds per byte of lexing `CALIBRATION`, as `f64` bits
+static NANOS_PER_BYTE: AtomicU64 = AtomicU64::new(0);
+
+fn nanos(source: &str) -> f64 {
+    let start = Instant::now();
+    Lexer::new(source).count();
+    start.elapsed().as_secs_f64() * 1e9
+}
+
+/// The fastest of a few runs on 64 KiB of `CALIBRATION`
+fn calibrate() -> f64 {
+    let source = CALIBRATION.repeat(65536 / CALIBRATION.len());
+    let best = (0..5).map(|_| nanos(&source)).fold(f64::MAX, f64::min);
+    best / source.len() as f64
+}
+
+// Lexing time is linear in the input length, so that a quadratic reader is a crash
+fuzz_target!(|data: &[u8]| {
+    CALIBRATE.call_once(|| NANOS_PER_BYTE.store(calibrate().to_bits(), Ordering::Relaxed));
+    if let Ok(s) = std::str::from_utf8(data) {
+        let per_byte = f64::from_bits(NANOS_PER_BYTE.load(Ordering::Relaxed));
+        let budget = s.len().max(MIN_LEN) as f64 * per_byte * MAX_SLOWDOWN;
+        let elapsed = nanos(s);
+        assert!(
+            elapsed <= budget,
+            "lexing {} bytes took {:.0}ns, over {:.0}x the calibrated {:.2}ns per byte",
+            s.len(),
+            elapsed,
+            MAX_SLOWDOWN,
+            per_byte
+        );
+    }
+});
@@ -0,0 +1,27 @@
+#![no_main]
+
+use lexer::Lexer;
+use libfuzzer_sys::{arbitrary::Unstructured, fuzz_target};
+
+#[path = "../../tests/generator/mod.rs"]
+mod generator;
+
+// A token stream generated from the input lexes back to the generated kinds
+fuzz_target!(|data: &[u8]| {
+    let mut u = Unstructured::new(data);
+    let stream = generator::generate(
+        |n| {
+            if u.is_empty() {
+                None
+            } else {
+                u.int_in_range(0..=n - 1).ok()
+            }
+        },
+        data.len(),
+    );
+    let (source, expected) = generator::render(&stream);
+    let kinds = Lexer::new(&source)
+        .map(|token| *token.kind())
+        .collect::<Vec<_>>();
+    assert_eq!(kinds, expected, "{:?}", source);
+});
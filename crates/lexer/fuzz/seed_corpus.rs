@@ -0,0 +1,88 @@
+//! Seed `corpus/lexer` with small test262 files of the literals the lexer reads
+//! `cargo run --bin seed_corpus -- ../../../tasks/coverage/test262`
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    process,
+};
+
+/// Directories of `test262/test` to seed from, each with the number of files it seeds
+const SOURCES: [(&str, usize); 5] = [
+    ("language/literals/string", 80),
+    ("language/literals/regexp", 80),
+    ("language/literals/numeric", 80),
+    ("language/literals/bigint", 40),
+    ("language/expressions/template-literal", 80),
+];
+
+/// Bytes kept of each file, the fuzzer mutates small inputs faster
+const MAX_LEN: usize = 1024;
+
+const CORPUS: &str = "corpus/lexer";
+
+/// FNV-1a, stable across Rust versions unlike `DefaultHasher`, so a seed keeps its name
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// `.js` files under `dir`, sorted so that every run seeds the same files
+fn js_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            js_files(&path, files)?;
+        } else if path.extension().map_or(false, |ext| ext == "js") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// The code after the license header and the frontmatter, up to `MAX_LEN` bytes
+fn seed(code: &str) -> &str {
+    let code = code.find("---*/").map_or(code, |end| &code[end + 5..]).trim();
+    let mut len = code.len().min(MAX_LEN);
+    while !code.is_char_boundary(len) {
+        len -= 1;
+    }
+    &code[..len]
+}
+
+fn main() {
+    let test262 = env::args().nth(1).unwrap_or_else(|| {
+        eprintln!("Usage: seed_corpus <test262 checkout>");
+        process::exit(1);
+    });
+    let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join(CORPUS);
+    let mut seeded = 0;
+    for (dir, count) in SOURCES {
+        let mut files = vec![];
+        let dir = Path::new(&test262).join("test").join(dir);
+        if let Err(e) = js_files(&dir, &mut files) {
+            eprintln!("{}: {}", dir.display(), e);
+            process::exit(1);
+        }
+        // spread over the directory rather than its first files
+        let step = (files.len() / count).max(1);
+        for path in files.iter().step_by(step).take(count) {
+            let code = fs::read_to_string(path).unwrap();
+            let seed = seed(&code);
+            if seed.is_empty() {
+                continue;
+            }
+            // named by content, a duplicate has the name of a seed
+            let path = corpus.join(format!("test262-{:016x}", fnv1a(seed.as_bytes())));
+            if !path.exists() {
+                fs::write(&path, seed).unwrap();
+                seeded += 1;
+            }
+        }
+    }
+    println!("{} new seeds in {}", seeded, corpus.display());
+}
@@ -0,0 +1,124 @@
+//! Virtual semicolons at automatic semicolon insertion positions
+
+use crate::kind::Kind;
+#[allow(clippy::enum_glob_use)]
+use crate::kind::Kind::*;
+use crate::token::Token;
+
+/// Finds the positions where a semicolon is inserted without needing a parser,
+/// see [`crate::LexerOptions::virtual_semicolons`]
+pub struct Asi {
+    /// Last significant token
+    last: Option<Kind>,
+
+    /// Significant token before `last`, `last` is a property name after `.` or `?.`
+    before_last: Option<Kind>,
+
+    /// End of `last`, where a virtual semicolon is inserted
+    end: usize,
+
+    /// Whether there is a line break since `last`
+    line_break: bool,
+
+    /// For each unclosed `{`, whether it opens a block or class body rather than an object literal
+    braces: Vec<bool>,
+}
+
+impl Asi {
+    pub const fn new() -> Self {
+        Self {
+            last: None,
+            before_last: None,
+            end: 0,
+            line_break: false,
+            braces: Vec::new(),
+        }
+    }
+
+    /// The virtual semicolon to insert before `token`, if any.
+    /// It goes right after the last significant token, before the trivia since.
+    pub fn before(&mut self, source: &str, token: &Token) -> Option<Token> {
+        let kind = *token.kind();
+        if kind.is_trivia() {
+            self.line_break |= is_line_break(source, token);
+            return None;
+        }
+        let semicolon = self
+            .inserts_before(kind)
+            .then(|| Token::new(VirtualSemicolon, self.end, 0));
+        match kind {
+            LCurly => self.braces.push(opens_block(self.last)),
+            RCurly => {
+                self.braces.pop();
+            }
+            _ => {}
+        }
+        self.before_last = self.last;
+        self.last = Some(kind);
+        self.end = token.range().end;
+        self.line_break = false;
+        semicolon
+    }
+
+    /// Only the unambiguous cases:
+    ///   * a line break after `return`, `throw`, `break` or `continue` (restricted productions)
+    ///   * a line break before the `}` of a block, after a token which can end a statement
+    ///   * `EOF` after a token which can end a statement
+    ///
+    /// Never before a `;`, and never after a `}` which may end a block as well as an expression.
+    fn inserts_before(&self, kind: Kind) -> bool {
+        let last = match self.last {
+            Some(last) if kind != Semicolon => last,
+            _ => return false,
+        };
+        let property = matches!(self.before_last, Some(Dot | QuestionDot));
+        if self.line_break && !property && matches!(last, Return | Throw | Break | Continue) {
+            return true;
+        }
+        let ends = ends_statement(last) && last != RCurly;
+        match kind {
+            RCurly => ends && self.line_break && self.braces.last() == Some(&true),
+            EOF => ends,
+            _ => false,
+        }
+    }
+}
+
+/// Whether a `{` after `last` opens a block or class body.
+/// After an operator, `(`, `[`, `,`, `:` or `return` it is an object literal.
+const fn opens_block(last: Option<Kind>) -> bool {
+    match last {
+        None | Some(Semicolon | LCurly | RCurly | FatArrow | Do | Else) => true,
+        Some(kind) => !kind.at_expr(),
+    }
+}
+
+/// A line terminator, or a multi-line comment containing one
+pub fn is_line_break(source: &str, token: &Token) -> bool {
+    match token.kind() {
+        LineTerminator => true,
+        MultilineComment => source[token.range()].contains(['\n', '\r', '\u{2028}', '\u{2029}']),
+        _ => false,
+    }
+}
+
+/// Whether a statement can end after `kind`, including the restricted productions
+/// such as `return` which end at a line break
+pub const fn ends_statement(kind: Kind) -> bool {
+    match kind {
+        Return | Break | Continue | Debugger | This | Super | Null | True | False | Plus2
+        | Minus2 | RParen | RBrack | RCurly => true,
+        _ => !kind.at_expr() && !kind.is_always_reserved(),
+    }
+}
+
+/// Whether `kind` at the start of a line continues the statement ending with `last` on the
+/// previous line. `++`, `--`, `!` and `~` start a new statement, `{` only continues after `)`
+/// as in `if (a)` or `function f()`.
+pub const fn continues_statement(last: Option<Kind>, kind: Kind) -> bool {
+    match (last, kind) {
+        (Some(RParen), LCurly) | (_, Template | Catch | FinallY) => true,
+        (_, Plus2 | Minus2 | Bang | Tilde | Dot3 | LCurly) => false,
+        _ => kind.at_expr(),
+    }
+}
@@ -0,0 +1,62 @@
+//! Comment Attachment
+
+use crate::asi::is_line_break;
+use crate::kind::Kind;
+use crate::lexer::Lexer;
+use crate::token::Token;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentPosition {
+    /// Before its token, on a line of its own or earlier on the line of the token
+    Leading,
+    /// After its token, on the same line
+    Trailing,
+}
+
+/// A comment of a token, see [`attach_comments`]
+#[derive(Debug, PartialEq)]
+pub struct Attached {
+    pub comment: Token,
+    pub position: CommentPosition,
+}
+
+/// Each significant token of `source` and `EOF`, with its comments in source order,
+/// for a formatter to print them back.
+///   * a comment after a token on the same line trails that token, `x; // c` trails `;`
+///   * any other comment leads the next token, `// c\nx` leads `x`
+///   * the comments after the last line break of the source lead `EOF`
+///
+/// A multi-line comment ends the line of the token it trails, a comment after it leads.
+#[must_use]
+#[allow(clippy::module_name_repetitions)]
+pub fn attach_comments(source: &str) -> Vec<(Token, Vec<Attached>)> {
+    let mut tokens: Vec<(Token, Vec<Attached>)> = vec![];
+    let mut leading = vec![];
+    // whether the last token is on the current line
+    let mut same_line = false;
+    for token in Lexer::new(source) {
+        match token.kind() {
+            Kind::Comment | Kind::MultilineComment => {
+                let line_break = is_line_break(source, &token);
+                match tokens.last_mut() {
+                    Some((_, comments)) if same_line => comments.push(Attached {
+                        comment: token,
+                        position: CommentPosition::Trailing,
+                    }),
+                    _ => leading.push(Attached {
+                        comment: token,
+                        position: CommentPosition::Leading,
+                    }),
+                }
+                same_line &= !line_break;
+            }
+            Kind::LineTerminator => same_line = false,
+            Kind::WhiteSpace => {}
+            _ => {
+                tokens.push((token, std::mem::take(&mut leading)));
+                same_line = true;
+            }
+        }
+    }
+    tokens
+}
@@ -0,0 +1,140 @@
+//! Token count estimate for pre-sizing allocations
+
+use crate::constants::{ASCII_LINE_TERMINATORS, ASCII_SPACES};
+
+/// Estimate of the number of tokens of `source` including `EOF`,
+/// e.g. for `Vec::with_capacity` of a token or node arena.
+///
+/// This is an estimate, not the exact count: a single pass over the bytes without building tokens,
+/// counting runs of word characters, whitespaces and line terminators, single punctuator bytes,
+/// and whole strings, templates, regexes and comments. It over-counts numbers such as `1.5`
+/// and multi-byte punctuators such as `===`, so it is usually an upper bound.
+/// It under-counts with non-ASCII whitespaces or line terminators between words.
+#[must_use]
+pub fn estimate_token_count(source: &str) -> usize {
+    let bytes = source.as_bytes();
+    let mut estimate = Estimate::default();
+    // EOF
+    let mut count = 1;
+    let mut cur = 0;
+    while cur < bytes.len() {
+        count += 1;
+        cur += estimate.token_len(&bytes[cur..]);
+    }
+    count
+}
+
+#[derive(Default)]
+struct Estimate {
+    /// Whether a `/` is a division rather than the start of a regex
+    after_value: bool,
+}
+
+impl Estimate {
+    /// Length of the token at the start of `bytes`, at least 1
+    fn token_len(&mut self, bytes: &[u8]) -> usize {
+        let b = bytes[0];
+        let mut after_value = true;
+        let len = match b {
+            b'"' | b'\'' => quoted_len(bytes, true),
+            b'`' => quoted_len(bytes, false),
+            b'/' if bytes.get(1) == Some(&b'/') => {
+                return run_len(bytes, |b| !ASCII_LINE_TERMINATORS.contains(&b));
+            }
+            b'/' if bytes.get(1) == Some(&b'*') => {
+                return bytes
+                    .windows(2)
+                    .skip(2)
+                    .position(|w| w == b"*/")
+                    .map_or(bytes.len(), |end| end + 4);
+            }
+            b'/' if !self.after_value => regex_len(bytes),
+            _ if is_word(b) => {
+                let len = run_len(bytes, is_word);
+                after_value = !precedes_expression(&bytes[..len]);
+                len
+            }
+            _ if ASCII_SPACES.contains(&b) => {
+                return run_len(bytes, |b| ASCII_SPACES.contains(&b));
+            }
+            _ if ASCII_LINE_TERMINATORS.contains(&b) => {
+                return run_len(bytes, |b| ASCII_LINE_TERMINATORS.contains(&b));
+            }
+            _ => {
+                after_value = matches!(b, b')' | b']' | b'}');
+                1
+            }
+        };
+        self.after_value = after_value;
+        len
+    }
+}
+
+/// Identifier, keyword and number bytes, any non-ASCII byte included
+const fn is_word(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b == b'$' || !b.is_ascii()
+}
+
+/// Keywords after which a `/` starts a regex
+const fn precedes_expression(word: &[u8]) -> bool {
+    matches!(
+        word,
+        b"return"
+            | b"typeof"
+            | b"instanceof"
+            | b"in"
+            | b"of"
+            | b"new"
+            | b"delete"
+            | b"void"
+            | b"throw"
+            | b"case"
+            | b"do"
+            | b"else"
+            | b"yield"
+            | b"await"
+    )
+}
+
+/// Length of the leading bytes matching `f`
+fn run_len(bytes: &[u8], f: impl Fn(u8) -> bool) -> usize {
+    bytes.iter().take_while(|b| f(**b)).count()
+}
+
+/// Length of the string or template at the start of `bytes`, up to EOF if unterminated.
+/// A string also ends at a line terminator.
+fn quoted_len(bytes: &[u8], string: bool) -> usize {
+    let quote = bytes[0];
+    let mut iter = bytes.iter().enumerate().skip(1);
+    while let Some((len, &b)) = iter.next() {
+        if b == quote {
+            return len + 1;
+        }
+        if b == b'\\' {
+            iter.next();
+        } else if string && ASCII_LINE_TERMINATORS.contains(&b) {
+            return len;
+        }
+    }
+    bytes.len()
+}
+
+/// Length of the regex at the start of `bytes` with its flags,
+/// up to a line terminator or EOF if unterminated
+fn regex_len(bytes: &[u8]) -> usize {
+    let mut class = false;
+    let mut iter = bytes.iter().enumerate().skip(1);
+    while let Some((len, &b)) = iter.next() {
+        match b {
+            b'/' if !class => return len + 1 + run_len(&bytes[len + 1..], is_word),
+            b'[' => class = true,
+            b']' => class = false,
+            b'\\' => {
+                iter.next();
+            }
+            _ if ASCII_LINE_TERMINATORS.contains(&b) => return len,
+            _ => {}
+        }
+    }
+    bytes.len()
+}
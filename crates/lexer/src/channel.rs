@@ -0,0 +1,22 @@
+//! Lex on a background thread
+
+use std::sync::mpsc::{self, Receiver};
+use std::thread::{self, JoinHandle};
+
+use crate::lexer::Lexer;
+use crate::token::Token;
+
+/// Lex `source` on a background thread which owns it, and send the tokens through a channel.
+/// The channel is closed after the EOF token, or when the receiver is dropped.
+#[must_use]
+pub fn spawn_lexer(source: String) -> (JoinHandle<()>, Receiver<Token>) {
+    let (sender, receiver) = mpsc::channel();
+    let handle = thread::spawn(move || {
+        for token in Lexer::new(&source) {
+            if sender.send(token).is_err() {
+                break;
+            }
+        }
+    });
+    (handle, receiver)
+}
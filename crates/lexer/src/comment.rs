@@ -0,0 +1,134 @@
+//! Comment Body and Kind
+
+use unicode_id::UnicodeID;
+
+use crate::constants::{ASCII_LINE_TERMINATORS_CHAR, UNICODE_LINE_TERMINATORS, UNICODE_SPACES};
+use crate::kind::Kind;
+use crate::lexer::Lexer;
+use crate::token::Token;
+
+/// Lex the content of a comment into coarse sub-tokens,
+/// for directive parsers such as `// eslint-disable-next-line rule` or `/* @flow */`.
+///
+/// The comment opener and closer are skipped and offsets are relative to `comment_text`.
+///   * words (identifier parts, `-`, `@` and `.`) are `Kind::Ident`
+///   * whitespaces and line terminators keep their kinds
+///   * any other character is lexed on its own, e.g. `Kind::Comma`
+#[must_use]
+pub fn lex_comment_body(comment_text: &str) -> Vec<Token> {
+    let start = if comment_text.starts_with("//") || comment_text.starts_with("/*") {
+        2
+    } else {
+        0
+    };
+    let end = if comment_text.starts_with("/*") && comment_text.len() >= 4 {
+        comment_text
+            .strip_suffix("*/")
+            .map_or(comment_text.len(), str::len)
+    } else {
+        comment_text.len()
+    };
+
+    let mut tokens = vec![];
+    let mut iter = comment_text[start..end].char_indices().peekable();
+    while let Some((i, c)) = iter.next() {
+        let mut len = c.len_utf8();
+        if let Some(kind) = comment_char_kind(c) {
+            while let Some((_, c)) = iter.next_if(|(_, c)| comment_char_kind(*c) == Some(kind)) {
+                len += c.len_utf8();
+            }
+            tokens.push(Token::new(kind, start + i, len));
+        } else {
+            let kind = Lexer::new(&comment_text[start + i..start + i + len])
+                .next()
+                .map_or(Kind::Unknown, |token| *token.kind());
+            tokens.push(Token::new(kind, start + i, len));
+        }
+    }
+    tokens
+}
+
+/// What a comment is for tools, see [`classify_comment`]
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentKind {
+    /// `/** ... */`, but not `/**/` or `/*** ... */`
+    JsDoc,
+    /// `/*! ... */`, a license banner which minifiers keep
+    Banner,
+    /// `// eslint-disable-next-line`, `/* eslint-env node */` and the other ESLint directives
+    Eslint,
+    /// `// @ts-ignore`, `// @ts-expect-error`, `// @ts-nocheck` and `// @ts-check`
+    TypeScript,
+    /// `//# sourceMappingURL=`, or the legacy `//@ sourceMappingURL=`
+    SourceMappingUrl,
+    /// Any other comment
+    Plain,
+}
+
+/// The first words of the eslint directives of any comment
+const ESLINT_DIRECTIVES: [&str; 4] = [
+    "eslint-disable",
+    "eslint-disable-line",
+    "eslint-disable-next-line",
+    "eslint-enable",
+];
+
+/// The first words of the eslint directives of multi-line comments only
+const ESLINT_BLOCK_DIRECTIVES: [&str; 5] =
+    ["eslint", "eslint-env", "exported", "global", "globals"];
+
+/// The TypeScript pragmas, which start the first word as in `// @ts-ignore: a reason`
+const TYPESCRIPT_DIRECTIVES: [&str; 4] =
+    ["@ts-check", "@ts-expect-error", "@ts-ignore", "@ts-nocheck"];
+
+/// The kind of the comment `token` in `source` from its opener and first word,
+/// `None` for a token which is not a comment
+#[must_use]
+#[allow(clippy::module_name_repetitions)]
+pub fn classify_comment(source: &str, token: &Token) -> Option<CommentKind> {
+    if !matches!(token.kind(), Kind::Comment | Kind::MultilineComment) {
+        return None;
+    }
+    let text = &source[token.range()];
+    let block = *token.kind() == Kind::MultilineComment;
+    if block {
+        if text.starts_with("/*!") {
+            return Some(CommentKind::Banner);
+        }
+        if text.starts_with("/**") && !text.starts_with("/***") && text != "/**/" {
+            return Some(CommentKind::JsDoc);
+        }
+    }
+    let body = text[2..].strip_suffix("*/").unwrap_or(&text[2..]);
+    if body.starts_with("# sourceMappingURL=") || body.starts_with("@ sourceMappingURL=") {
+        return Some(CommentKind::SourceMappingUrl);
+    }
+    let word = body.split_whitespace().next().unwrap_or_default();
+    let kind = if ESLINT_DIRECTIVES.contains(&word)
+        || (block && ESLINT_BLOCK_DIRECTIVES.contains(&word))
+    {
+        CommentKind::Eslint
+    } else if TYPESCRIPT_DIRECTIVES
+        .iter()
+        .any(|pragma| word.starts_with(pragma))
+    {
+        CommentKind::TypeScript
+    } else {
+        CommentKind::Plain
+    };
+    Some(kind)
+}
+
+/// Kind of a run of characters inside a comment, `None` for punctuations
+fn comment_char_kind(c: char) -> Option<Kind> {
+    if UNICODE_SPACES.contains(&c) {
+        Some(Kind::WhiteSpace)
+    } else if ASCII_LINE_TERMINATORS_CHAR.contains(&c) || UNICODE_LINE_TERMINATORS.contains(&c) {
+        Some(Kind::LineTerminator)
+    } else if c.is_id_continue() || matches!(c, '$' | '_' | '-' | '@' | '.') {
+        Some(Kind::Ident)
+    } else {
+        None
+    }
+}
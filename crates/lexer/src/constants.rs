@@ -10,3 +10,31 @@ pub const ASCII_LINE_TERMINATORS: [u8; 2] = [b'\n', b'\r'];
 pub const ASCII_LINE_TERMINATORS_CHAR: [char; 2] = ['\n', '\r'];
 
 pub const UNICODE_LINE_TERMINATORS: [char; 2] = ['\u{2028}', '\u{2029}'];
+
+/// Section 22.2.3.1 `RegExpInitialize` flags, bit `i` of a set of flags is `REGEX_FLAGS[i]`
+pub const REGEX_FLAGS: &str = "dgimsuvy";
+/// The bits of `u` and `v`, which exclude each other
+pub const REGEX_UNICODE_FLAGS: u8 = 0b0110_0000;
+
+/// Bit `b` is set for the ASCII bytes `b` which start an identifier, `[A-Za-z$_]`
+pub const ASCII_ID_START: u128 = ascii_id_bits(false);
+/// Bit `b` is set for the ASCII bytes `b` which continue an identifier, `[A-Za-z0-9$_]`
+pub const ASCII_ID_CONTINUE: u128 = ascii_id_bits(true);
+
+const fn ascii_id_bits(digits: bool) -> u128 {
+    let mut bits = 0;
+    let mut b: u8 = 0;
+    while b < 128 {
+        if b.is_ascii_alphabetic() || b == b'$' || b == b'_' || (digits && b.is_ascii_digit()) {
+            bits |= 1 << b;
+        }
+        b += 1;
+    }
+    bits
+}
+
+/// Whether the bit of `b` is set in `bits`, `false` beyond ASCII
+#[inline]
+pub const fn is_ascii_in(bits: u128, b: u8) -> bool {
+    b < 128 && bits & (1 << b) != 0
+}
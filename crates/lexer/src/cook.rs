@@ -0,0 +1,87 @@
+//! 12.8.4.2 Static Semantics: SV, the value of a string literal
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// `n` hex digits as a number
+fn read_hex(chars: &mut Peekable<Chars>, n: usize) -> Option<u32> {
+    (0..n).try_fold(0, |value, _| Some(value * 16 + chars.next()?.to_digit(16)?))
+}
+
+/// `\u{...}` after the `u`, or the 4 digits of `\uXXXX`
+fn read_unicode_escape(chars: &mut Peekable<Chars>) -> Option<u32> {
+    if chars.next_if_eq(&'{').is_none() {
+        return read_hex(chars, 4);
+    }
+    let mut value = 0_u32;
+    let mut digits = 0;
+    while let Some(digit) = chars.next_if(char::is_ascii_hexdigit) {
+        value = value.checked_mul(16)? + digit.to_digit(16)?;
+        digits += 1;
+    }
+    if digits == 0 || chars.next()? != '}' || value > 0x10_FFFF {
+        return None;
+    }
+    Some(value)
+}
+
+/// A legacy octal escape after its first digit, up to `\377`
+fn read_legacy_octal(chars: &mut Peekable<Chars>, first: u32) -> u32 {
+    let max_digits = if first <= 3 { 2 } else { 1 };
+    let mut value = first;
+    for _ in 0..max_digits {
+        match chars.next_if(|c| matches!(c, '0'..='7')) {
+            Some(digit) => value = value * 8 + digit.to_digit(8).unwrap(),
+            None => break,
+        }
+    }
+    value
+}
+
+/// The code units of an escape after its `\`, empty for a line continuation
+fn cook_escape(chars: &mut Peekable<Chars>, units: &mut Vec<u16>) -> Option<()> {
+    let c = chars.next()?;
+    let value = match c {
+        'b' => 0x08,
+        't' => 0x09,
+        'n' => 0x0A,
+        'v' => 0x0B,
+        'f' => 0x0C,
+        'r' => 0x0D,
+        '0' if !chars.peek().map_or(false, char::is_ascii_digit) => 0,
+        '0'..='7' => read_legacy_octal(chars, c.to_digit(8).unwrap()),
+        'x' => read_hex(chars, 2)?,
+        'u' => read_unicode_escape(chars)?,
+        '\r' => {
+            chars.next_if_eq(&'\n');
+            return Some(());
+        }
+        '\n' | '\u{2028}' | '\u{2029}' => return Some(()),
+        c => u32::from(c),
+    };
+    // a surrogate escape is a code unit, a pair of them is a code point
+    match (char::from_u32(value), u16::try_from(value)) {
+        (_, Ok(unit)) => units.push(unit),
+        (Some(c), Err(_)) => units.extend(c.encode_utf16(&mut [0; 2]).iter()),
+        (None, Err(_)) => return None,
+    }
+    Some(())
+}
+
+/// The value of the string literal `raw`, quotes included, with its escapes decoded.
+/// `None` for an invalid escape, or a lone surrogate which a `String` cannot hold.
+#[must_use]
+#[allow(clippy::module_name_repetitions)]
+pub fn cook_string(raw: &str) -> Option<String> {
+    let body = raw.get(1..raw.len().checked_sub(1)?)?;
+    let mut units = Vec::with_capacity(body.len());
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            cook_escape(&mut chars, &mut units)?;
+        } else {
+            units.extend(c.encode_utf16(&mut [0; 2]).iter());
+        }
+    }
+    String::from_utf16(&units).ok()
+}
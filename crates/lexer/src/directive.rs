@@ -0,0 +1,50 @@
+//! Directive Prologues
+
+use crate::asi::continues_statement;
+use crate::kind::Kind;
+use crate::span::Span;
+use crate::token::{line_break_before, Token};
+
+/// Directives a parser acts upon
+const DIRECTIVES: [&str; 2] = ["use strict", "use asm"];
+
+/// Section 11.2.1 Directive Prologues, the string literal statements at the start of `tokens`,
+/// the tokens of a script or of a function body after its `{`.
+/// The span of the first `"use strict"` or `"use asm"` directive among them, which is
+/// spelled exactly so, without an escape or a line continuation.
+/// A string followed by a `;`, a `}`, the end, or a line break before a token which does not
+/// continue it is a statement of its own, `"use strict" + a` is not a directive.
+#[must_use]
+pub fn is_directive_prologue_candidate(source: &str, tokens: &[Token]) -> Option<Span> {
+    let mut significant = tokens
+        .iter()
+        .enumerate()
+        .filter(|(_, token)| token.is_significant())
+        .peekable();
+    while let Some((_, token)) = significant.next() {
+        if !matches!(token.kind(), Kind::Str(_)) {
+            return None;
+        }
+        let ends_statement = match significant.peek() {
+            None => true,
+            Some((_, next)) if matches!(next.kind(), Kind::Semicolon | Kind::RCurly) => true,
+            Some((index, next)) => {
+                line_break_before(source, tokens, *index)
+                    && !continues_statement(Some(*token.kind()), *next.kind())
+            }
+        };
+        if !ends_statement {
+            return None;
+        }
+        let raw = &source[token.range()];
+        if DIRECTIVES.contains(&&raw[1..raw.len() - 1]) {
+            return Some(token.span());
+        }
+        if let Some((_, next)) = significant.peek() {
+            if *next.kind() == Kind::Semicolon {
+                significant.next();
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,44 @@
+//! Token Dump
+
+use std::fmt::Write;
+
+use crate::kind::Kind;
+use crate::lexer::Lexer;
+use crate::token::Token;
+
+/// Text of a comment dumped with [`DumpOptions::redact_comments`]
+pub const REDACTED_COMMENT: &str = "<comment>";
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DumpOptions {
+    /// Replace the text of `Comment` and `MultilineComment` tokens by [`REDACTED_COMMENT`],
+    /// keeping their kinds and spans, for tools which must not retain comments of third-party code
+    pub redact_comments: bool,
+}
+
+/// Source text of `token`, empty for `EOF` which is past the end
+#[must_use]
+pub fn token_text<'a>(source: &'a str, token: &Token, options: DumpOptions) -> &'a str {
+    match token.kind() {
+        Kind::Comment | Kind::MultilineComment if options.redact_comments => REDACTED_COMMENT,
+        _ => source.get(token.range()).unwrap_or_default(),
+    }
+}
+
+/// A line per token with its kind, range and text, then a line per error
+/// # Panics
+/// Formatting failed
+#[must_use]
+pub fn dump(source: &str, options: DumpOptions) -> String {
+    let mut lexer = Lexer::new(source);
+    let mut s = String::new();
+    for token in lexer.by_ref() {
+        let text = token_text(source, &token, options);
+        writeln!(s, "{:?} {:?} {:?}", token.kind(), token.range(), text).unwrap();
+    }
+    for error in lexer.errors() {
+        writeln!(s, "error {:?} {:?}", error.kind(), error.span()).unwrap();
+    }
+    s
+}
@@ -0,0 +1,68 @@
+//! Lexer Errors
+
+use std::ops::Range;
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ErrorKind {
+    /// A character that does not start any token
+    UnexpectedCharacter,
+    /// A string literal reaching EOF
+    UnterminatedString,
+    /// A template literal reaching EOF
+    UnterminatedTemplate,
+    /// A regex reaching EOF or with an invalid flag
+    InvalidRegex,
+    /// An identifier with an invalid escape or over `max_identifier_length`
+    InvalidIdentifier,
+    /// A numeric literal with a missing exponent or digits
+    InvalidNumber,
+}
+
+impl ErrorKind {
+    /// Kind of the error from the first byte of an `Unknown` token
+    #[must_use]
+    pub const fn from_first_byte(b: u8) -> Self {
+        match b {
+            b'\'' | b'"' => Self::UnterminatedString,
+            b'`' => Self::UnterminatedTemplate,
+            b'/' => Self::InvalidRegex,
+            b'0'..=b'9' => Self::InvalidNumber,
+            b'\\' | b'$' | b'_' | b'a'..=b'z' | b'A'..=b'Z' | 0x80.. => Self::InvalidIdentifier,
+            _ => Self::UnexpectedCharacter,
+        }
+    }
+}
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LexerError {
+    kind: ErrorKind,
+    span: Range<usize>,
+}
+
+impl LexerError {
+    #[must_use]
+    pub const fn new(kind: ErrorKind, span: Range<usize>) -> Self {
+        Self { kind, span }
+    }
+
+    #[must_use]
+    pub const fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    #[must_use]
+    pub const fn span(&self) -> &Range<usize> {
+        &self.span
+    }
+}
+
+/// Errors sorted by span start, without duplicated `(kind, span)` pairs
+#[must_use]
+pub fn sort_errors(errors: &[LexerError]) -> Vec<&LexerError> {
+    let mut sorted = errors.iter().collect::<Vec<_>>();
+    sorted.sort_by_key(|error| (error.span.start, error.span.end, error.kind));
+    sorted.dedup();
+    sorted
+}
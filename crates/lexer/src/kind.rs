@@ -1,6 +1,15 @@
 //! ECMAScript Token Kinds
+//!
+//! `Kind` is a flat enum, a byte for the lexer to pass around and `match` on, with a payload
+//! for the literals which come in several forms, `Number(Number)` and `Str(Quote)`.
+//! Each kind is a row of the `kinds!` table, its group and its text, which defines the
+//! enum along with [`Kind::discriminant`], [`Kind::as_str`], [`Kind::group`] and the lists
+//! [`Kind::ALL`] and [`Kind::fixed_variants`]. Predicates of the grammar, e.g. [`Kind::at_expr`]
+//! and [`Kind::binary_precedence`], are matches over the kinds, by group where they can be.
 
-#[derive(Debug, Eq, PartialEq)]
+use std::{fmt, str::FromStr};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum Number {
     Decimal,
     Float,
@@ -10,161 +19,670 @@ pub enum Number {
     BigInt,
 }
 
-#[derive(Debug, Eq, PartialEq)]
-#[non_exhaustive]
-pub enum Kind {
-    Unknown,
-    EOF,
+const NUMBERS: [Number; 6] = [
+    Number::Decimal,
+    Number::Float,
+    Number::Binary,
+    Number::Octal,
+    Number::Hex,
+    Number::BigInt,
+];
+
+/// The quote of a string literal, see [`Kind::Str`]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Quote {
+    /// `'`
+    Single,
+    /// `"`
+    Double,
+}
+
+const QUOTES: [Quote; 2] = [Quote::Single, Quote::Double];
+
+impl Quote {
+    /// The quote of a string starting with `b`
+    #[must_use]
+    pub const fn from_byte(b: u8) -> Option<Self> {
+        match b {
+            b'\'' => Some(Self::Single),
+            b'"' => Some(Self::Double),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_char(self) -> char {
+        match self {
+            Self::Single => '\'',
+            Self::Double => '"',
+        }
+    }
+}
+
+/// A coarse grouping of the kinds, see [`Kind::group`], for a consumer to `match` on a few groups
+/// instead of every kind. As `u8`, a group indexes a jump table.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[repr(u8)]
+pub enum TokenGroup {
+    /// Whitespaces, line terminators and comments, see [`Kind::is_trivia`]
+    Trivia,
+    Ident,
+    /// Keywords and the reserved words lexed as keywords, but not `null`, `true` and `false`
+    Keyword,
+    /// Punctuators, and `VirtualSemicolon` which stands for a `;`
+    Punctuator,
+    /// `null`, `undefined`, booleans, numbers, strings, regexes and templates
+    Literal,
+    /// `Unknown` and `EOF`, which are not one of the tokens of the grammar
+    Other,
+}
+
+/// Defines `Kind` from a table of `Group Variant "text"` rows, `Group Variant(Payload: PAYLOADS)`
+/// for a variant with a payload, and what is spelled out once per kind: `Kind::discriminant`,
+/// `Kind::as_str`, `Kind::group` and the lists of kinds
+macro_rules! kinds {
+    (@wildcard $payload:ident) => { _ };
+    (@has_payload) => { false };
+    (@has_payload $payload:ident) => { true };
+    (@kinds $variant:ident) => { &[Kind::$variant] };
+    (@kinds $variant:ident $payloads:ident) => {
+        &{
+            let mut kinds = [Kind::Unknown; $payloads.len()];
+            let mut i = 0;
+            while i < kinds.len() {
+                kinds[i] = Kind::$variant($payloads[i]);
+                i += 1;
+            }
+            kinds
+        }
+    };
+    ($($group:ident $variant:ident $(($payload:ident: $payloads:ident))? $text:literal,)*) => {
+        #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+        #[non_exhaustive]
+        pub enum Kind {
+            $($variant $(($payload))?,)*
+        }
+
+        /// The variants without their payloads, numbered in declaration order
+        #[allow(clippy::upper_case_acronyms)]
+        #[repr(u8)]
+        enum Discriminant {
+            $($variant,)*
+        }
+
+        /// The kinds of each variant, a kind for each payload
+        const VARIANT_KINDS: &[&[Kind]] = &[$(kinds!(@kinds $variant $($payloads)?),)*];
+
+        impl Kind {
+            /// Stable ordering of the variants, in declaration order
+            #[must_use]
+            pub const fn discriminant(&self) -> u8 {
+                match self {
+                    $(Self::$variant $((kinds!(@wildcard $payload)))? => Discriminant::$variant as u8,)*
+                }
+            }
+
+            /// Source text of keywords and punctuators, a description for the other kinds
+            #[must_use]
+            pub const fn as_str(&self) -> &'static str {
+                match self {
+                    $(Self::$variant $((kinds!(@wildcard $payload)))? => $text,)*
+                }
+            }
+
+            #[must_use]
+            pub const fn group(&self) -> TokenGroup {
+                match self {
+                    $(Self::$variant $((kinds!(@wildcard $payload)))? => TokenGroup::$group,)*
+                }
+            }
+
+            const fn has_payload(&self) -> bool {
+                match self {
+                    $(Self::$variant $((kinds!(@wildcard $payload)))? => kinds!(@has_payload $($payload)?),)*
+                }
+            }
+        }
+    };
+}
+
+kinds! {
+    Other      Unknown "unknown",
+    Other      EOF "end of file",
     // 12.2 whitespace
-    WhiteSpace,
+    Trivia     WhiteSpace "whitespace",
     // 12.3 line terminator
-    LineTerminator,
+    Trivia     LineTerminator "line terminator",
     // 12.4 comment
-    Comment,
-    MultilineComment,
+    Trivia     Comment "comment",
+    Trivia     MultilineComment "multi line comment",
     // 12.6 identifier
-    Ident,
+    Ident      Ident "identifier",
     // 12.6.2 keyword
-    Await,
-    Break,
-    Case,
-    Catch,
-    Class,
-    Const,
-    Continue,
-    Debugger,
-    DefaulT,
-    Delete,
-    Do,
-    Else,
-    Enum,
-    Export,
-    Extends,
-    FinallY,
-    For,
-    Function,
-    If,
-    Import,
-    In,
-    Instanceof,
-    New,
-    Return,
-    Super,
-    Switch,
-    This,
-    Throw,
-    Try,
-    Typeof,
-    Var,
-    Void,
-    While,
-    With,
-    Yield,
+    Keyword    Await "await",
+    Keyword    Break "break",
+    Keyword    Case "case",
+    Keyword    Catch "catch",
+    Keyword    Class "class",
+    Keyword    Const "const",
+    Keyword    Continue "continue",
+    Keyword    Debugger "debugger",
+    Keyword    DefaulT "default",
+    Keyword    Delete "delete",
+    Keyword    Do "do",
+    Keyword    Else "else",
+    Keyword    Enum "enum",
+    Keyword    Export "export",
+    Keyword    Extends "extends",
+    Keyword    FinallY "finally",
+    Keyword    For "for",
+    Keyword    Function "function",
+    Keyword    If "if",
+    Keyword    Import "import",
+    Keyword    In "in",
+    Keyword    Instanceof "instanceof",
+    Keyword    New "new",
+    Keyword    Return "return",
+    Keyword    Super "super",
+    Keyword    Switch "switch",
+    Keyword    This "this",
+    Keyword    Throw "throw",
+    Keyword    Try "try",
+    Keyword    Typeof "typeof",
+    Keyword    Var "var",
+    Keyword    Void "void",
+    Keyword    While "while",
+    Keyword    With "with",
+    Keyword    Yield "yield",
+    // 12.6.2 reserved in strict mode code
+    Keyword    Implements "implements",
+    Keyword    Interface "interface",
+    Keyword    Let "let",
+    Keyword    Package "package",
+    Keyword    Private "private",
+    Keyword    Protected "protected",
+    Keyword    Public "public",
+    Keyword    Static "static",
     // 12.7 punctuators
-    Amp, // &
-    Amp2,
-    Amp2Eq,
-    AmpEq,
-    Bang,
-    Caret,
-    CaretEq,
-    Colon,
-    Comma,
-    Dot,
-    Dot3, // ...
-    Eq,
-    Eq2,
-    Eq3,
-    FatArrow,
-    GtEq, // >=
-    LAngle,
-    LBrack,
-    LCurly,
-    LParen,
-    LtEq, // <=
-    Minus,
-    Minus2,
-    MinusEq,
-    Neq,
-    Neq2,
-    Percent,
-    PercentEq,
-    Pipe,
-    Pipe2,
-    Pipe2Eq,
-    PipeEq,
-    Plus,
-    Plus2,
-    PlusEq,
-    Question,
-    Question2,
-    Question2Eq,
-    QuestionDot,
-    RAngle,
-    RBrack,
-    RCurly,
-    RParen,
-    Semicolon,
-    ShiftLeft,     // <<
-    ShiftLeftEq,   // <<=
-    ShiftRight,    // >>
-    ShiftRight3,   // >>>
-    ShiftRight3Eq, // >>>=
-    ShiftRightEq,  // >>=
-    Slash,
-    SlashEq,
-    Star,
-    Star2,
-    Star2Eq,
-    StarEq,
-    Tilde,
+    Punctuator Amp "&",
+    Punctuator Amp2 "&&",
+    Punctuator Amp2Eq "&&=",
+    Punctuator AmpEq "&=",
+    Punctuator Bang "!",
+    Punctuator Caret "^",
+    Punctuator CaretEq "^=",
+    Punctuator Colon ":",
+    Punctuator Comma ",",
+    Punctuator Dot ".",
+    Punctuator Dot3 "...",
+    Punctuator Eq "=",
+    Punctuator Eq2 "==",
+    Punctuator Eq3 "===",
+    Punctuator FatArrow "=>",
+    Punctuator GtEq ">=",
+    Punctuator LAngle "<",
+    Punctuator LBrack "[",
+    Punctuator LCurly "{",
+    Punctuator LParen "(",
+    Punctuator LtEq "<=",
+    Punctuator Minus "-",
+    Punctuator Minus2 "--",
+    Punctuator MinusEq "-=",
+    Punctuator Neq "!=",
+    Punctuator Neq2 "!==",
+    Punctuator Percent "%",
+    Punctuator PercentEq "%=",
+    Punctuator Pipe "|",
+    Punctuator Pipe2 "||",
+    Punctuator Pipe2Eq "||=",
+    Punctuator PipeEq "|=",
+    Punctuator PipeGt "|>",
+    Punctuator Plus "+",
+    Punctuator Plus2 "++",
+    Punctuator PlusEq "+=",
+    Punctuator Question "?",
+    Punctuator Question2 "??",
+    Punctuator Question2Eq "??=",
+    Punctuator QuestionDot "?.",
+    Punctuator RAngle ">",
+    Punctuator RBrack "]",
+    Punctuator RCurly "}",
+    Punctuator RParen ")",
+    Punctuator Semicolon ";",
+    Punctuator ShiftLeft "<<",
+    Punctuator ShiftLeftEq "<<=",
+    Punctuator ShiftRight ">>",
+    Punctuator ShiftRight3 ">>>",
+    Punctuator ShiftRight3Eq ">>>=",
+    Punctuator ShiftRightEq ">>=",
+    Punctuator Slash "/",
+    Punctuator SlashEq "/=",
+    Punctuator Star "*",
+    Punctuator Star2 "**",
+    Punctuator Star2Eq "**=",
+    Punctuator StarEq "*=",
+    Punctuator Tilde "~",
     // 12.8.1 Null Literals
-    Null,
-    Undefined,
+    Literal    Null "null",
+    Literal    Undefined "undefined",
     // 12.8.2 Boolean Literals
-    True,
-    False,
+    Literal    True "true",
+    Literal    False "false",
     // 12.8.3 Numeric Literals
-    Number(Number),
+    Literal    Number(Number: NUMBERS) "number",
     // 12.8.4 String Literals
-    Str,
+    Literal    Str(Quote: QUOTES) "string",
     // 12.8.5 Regular Expression Literals
-    Regex,
+    Literal    Regex "regex",
     // 12.8.6 Template Literal Lexical Components
-    Template,
+    Literal    Template "template",
     // TODO section
-    Hash,
+    Punctuator Hash "#",
+    // 12.10 Automatic Semicolon Insertion, zero length, see `LexerOptions::virtual_semicolons`
+    Punctuator VirtualSemicolon "virtual semicolon",
 }
+
 #[allow(clippy::enum_glob_use)]
 use self::Kind::*;
+
+/// The number of kinds, and of those without a payload
+const COUNTS: (usize, usize) = {
+    let (mut all, mut fixed) = (0, 0);
+    let mut i = 0;
+    while i < VARIANT_KINDS.len() {
+        all += VARIANT_KINDS[i].len();
+        if !VARIANT_KINDS[i][0].has_payload() {
+            fixed += 1;
+        }
+        i += 1;
+    }
+    (all, fixed)
+};
+
+/// `VARIANT_KINDS` flattened
+const ALL_KINDS: [Kind; COUNTS.0] = {
+    let mut all = [Unknown; COUNTS.0];
+    let (mut len, mut i) = (0, 0);
+    while i < VARIANT_KINDS.len() {
+        let mut j = 0;
+        while j < VARIANT_KINDS[i].len() {
+            all[len] = VARIANT_KINDS[i][j];
+            len += 1;
+            j += 1;
+        }
+        i += 1;
+    }
+    all
+};
+
+const FIXED_VARIANTS: [Kind; COUNTS.1] = {
+    let mut fixed = [Unknown; COUNTS.1];
+    let (mut len, mut i) = (0, 0);
+    while i < ALL_KINDS.len() {
+        if !ALL_KINDS[i].has_payload() {
+            fixed[len] = ALL_KINDS[i];
+            len += 1;
+        }
+        i += 1;
+    }
+    fixed
+};
+
 impl Kind {
+    /// Every kind in declaration order, a kind for each payload of `Number` and `Str`,
+    /// for tools building tables over the kinds
+    pub const ALL: &'static [Self] = &ALL_KINDS;
+
     #[must_use]
     pub const fn is_whitespace(&self) -> bool {
         matches!(self, WhiteSpace)
     }
 
+    /// Whitespaces, line terminators and comments, which carry no meaning for a parser.
+    /// `EOF` is not trivia.
+    #[must_use]
+    pub const fn is_trivia(&self) -> bool {
+        matches!(self.group(), TokenGroup::Trivia)
+    }
+
+    /// See [`TokenGroup::Keyword`]
+    #[must_use]
+    pub const fn is_keyword(&self) -> bool {
+        matches!(self.group(), TokenGroup::Keyword)
+    }
+
+    /// `=>` of an arrow function
+    #[must_use]
+    pub const fn is_arrow(&self) -> bool {
+        matches!(self, FatArrow)
+    }
+
+    /// All variants without a payload, in declaration order
+    #[must_use]
+    pub const fn fixed_variants() -> &'static [Self] {
+        &FIXED_VARIANTS
+    }
+
+    /// Keyword or literal keyword (`null`, `true`, `false`) spelled by `s`
+    #[must_use]
+    pub const fn keyword_from_str(s: &str) -> Option<Self> {
+        match Self::match_keyword(s.as_bytes()) {
+            Ident => None,
+            kind => Some(kind),
+        }
+    }
+
+    /// Keyword spelled by `bytes`, `Ident` otherwise
+    pub(crate) const fn match_keyword(bytes: &[u8]) -> Self {
+        match bytes.len() {
+            2 => match bytes {
+                b"do" => Do,
+                b"if" => If,
+                b"in" => In,
+                _ => Ident,
+            },
+            3 => match bytes {
+                b"new" => New,
+                b"try" => Try,
+                b"var" => Var,
+                b"for" => For,
+                b"let" => Let,
+                _ => Ident,
+            },
+            4 => match bytes {
+                b"case" => Case,
+                b"else" => Else,
+                b"enum" => Enum,
+                b"this" => This,
+                b"true" => True,
+                b"void" => Void,
+                b"with" => With,
+                b"null" => Null,
+                _ => Ident,
+            },
+            5 => match bytes {
+                b"await" => Await,
+                b"break" => Break,
+                b"catch" => Catch,
+                b"class" => Class,
+                b"const" => Const,
+                b"false" => False,
+                b"while" => While,
+                b"super" => Super,
+                b"throw" => Throw,
+                b"yield" => Yield,
+                _ => Ident,
+            },
+            6 => match bytes {
+                b"delete" => Delete,
+                b"export" => Export,
+                b"import" => Import,
+                b"return" => Return,
+                b"switch" => Switch,
+                b"typeof" => Typeof,
+                b"public" => Public,
+                b"static" => Static,
+                _ => Ident,
+            },
+            7 => match bytes {
+                b"default" => DefaulT,
+                b"extends" => Extends,
+                b"finally" => FinallY,
+                b"package" => Package,
+                b"private" => Private,
+                _ => Ident,
+            },
+            8 => match bytes {
+                b"continue" => Continue,
+                b"debugger" => Debugger,
+                b"function" => Function,
+                _ => Ident,
+            },
+            9 => match bytes {
+                b"interface" => Interface,
+                b"protected" => Protected,
+                _ => Ident,
+            },
+            10 => match bytes {
+                b"instanceof" => Instanceof,
+                b"implements" => Implements,
+                _ => Ident,
+            },
+            _ => Ident,
+        }
+    }
+
+    /// Reserved words that are identifiers in sloppy mode code, e.g. `let`, `static`, `yield`
+    #[must_use]
+    pub const fn is_reserved_in_strict_only(&self) -> bool {
+        matches!(
+            self,
+            Implements | Interface | Let | Package | Private | Protected | Public | Static | Yield
+        )
+    }
+
+    /// Future reserved words of strict mode code since ES5, which are only lexed as keywords
+    /// with [`crate::LexerOptions::strict`]. `let`, `static` and `yield` are always lexed as
+    /// keywords, a parser tells their uses as identifiers apart.
+    #[must_use]
+    pub const fn is_future_reserved(&self) -> bool {
+        matches!(
+            self,
+            Implements | Interface | Package | Private | Protected | Public
+        )
+    }
+
+    /// Reserved words that are never identifiers.
+    /// `await` is not, it is an identifier outside of modules and async functions.
+    #[must_use]
+    pub const fn is_always_reserved(&self) -> bool {
+        matches!(
+            self,
+            Break
+                | Case
+                | Catch
+                | Class
+                | Const
+                | Continue
+                | Debugger
+                | DefaulT
+                | Delete
+                | Do
+                | Else
+                | Enum
+                | Export
+                | Extends
+                | FinallY
+                | For
+                | Function
+                | If
+                | Import
+                | In
+                | Instanceof
+                | New
+                | Return
+                | Super
+                | Switch
+                | This
+                | Throw
+                | Try
+                | Typeof
+                | Var
+                | Void
+                | While
+                | With
+                | Null
+                | True
+                | False
+        )
+    }
+
+    /// Reserved words which are identifiers in sloppy scripts but not in modules, `await`, which
+    /// is an operator at the top level of a module, and those of strict mode code.
+    /// `import` and `export` are always reserved, a module gives them their statement meaning.
+    #[must_use]
+    pub const fn is_reserved_in_module(&self) -> bool {
+        matches!(self, Await) || self.is_reserved_in_strict_only()
+    }
+
+    /// Restricted productions, with no line terminator allowed after the keyword
+    /// (`return`, `throw`, `break`, `continue`, `yield`) or before the postfix `++` and `--`,
+    /// see [`crate::line_break_before`]
+    #[must_use]
+    pub const fn starts_restricted_production(&self) -> bool {
+        matches!(
+            self,
+            Return | Throw | Break | Continue | Yield | Plus2 | Minus2
+        )
+    }
+
+    /// Precedence of a binary operator, higher binds tighter, `None` for other kinds.
+    /// `**` is the only right associative one. `??` is lowest, mixing it with `||` or `&&`
+    /// without parentheses is a syntax error for a parser to report.
+    #[must_use]
+    pub const fn binary_precedence(&self) -> Option<u8> {
+        let precedence = match self {
+            Question2 => 1,
+            Pipe2 => 2,
+            Amp2 => 3,
+            Pipe => 4,
+            Caret => 5,
+            Amp => 6,
+            Eq2 | Neq | Eq3 | Neq2 => 7,
+            LAngle | RAngle | LtEq | GtEq | Instanceof | In => 8,
+            ShiftLeft | ShiftRight | ShiftRight3 => 9,
+            Plus | Minus => 10,
+            Star | Slash | Percent => 11,
+            Star2 => 12,
+            _ => return None,
+        };
+        Some(precedence)
+    }
+
+    /// `=` and the compound assignment operators such as `+=` and `??=`
+    #[must_use]
+    pub const fn is_assignment_operator(&self) -> bool {
+        matches!(
+            self,
+            Eq | PlusEq
+                | MinusEq
+                | StarEq
+                | Star2Eq
+                | SlashEq
+                | PercentEq
+                | ShiftLeftEq
+                | ShiftRightEq
+                | ShiftRight3Eq
+                | AmpEq
+                | PipeEq
+                | CaretEq
+                | Amp2Eq
+                | Pipe2Eq
+                | Question2Eq
+        )
+    }
+
+    /// Whether an expression may begin with this token, e.g. to resynchronize after a syntax
+    /// error. Unlike [`Kind::at_expr`], which is about the token before an expression:
+    /// `=` and `return` precede an expression without starting one, `-` and `(` do both.
+    /// `/` and `/=` start a regex the lexer took for a division, `#` an `#x in a` check.
+    #[must_use]
+    pub const fn can_start_expression(&self) -> bool {
+        matches!(
+            self,
+            Ident
+                | Await
+                | Yield
+                | Let
+                | Static
+                | This
+                | Super
+                | Function
+                | Class
+                | New
+                | Import
+                | Delete
+                | Void
+                | Typeof
+                | Null
+                | Undefined
+                | True
+                | False
+                | Number(_)
+                | Str(_)
+                | Regex
+                | Template
+                | LParen
+                | LBrack
+                | LCurly
+                | Plus
+                | Minus
+                | Plus2
+                | Minus2
+                | Bang
+                | Tilde
+                | Slash
+                | SlashEq
+                | Hash
+        )
+    }
+
+    /// Whether an expression may follow, so that a `/` after this token starts a regex,
+    /// see [`Kind::can_start_expression`] for whether it begins one itself.
+    /// `Colon` is overloaded but always is: after a ternary `a ? b : /re/`, a label `l: /re/`
+    /// or a property key `{ a: /re/ }`. A TypeScript annotation `let a: T` is not told apart,
+    /// which is harmless as a type never starts with `/`.
     // https://stackoverflow.com/questions/5519596/when-parsing-javascript-what-determines-the-meaning-of-a-slash
     // https://www-archive.mozilla.org/js/language/js20-2002-04/rationale/syntax.html#regular-expressions
     #[must_use]
     pub const fn at_expr(&self) -> bool {
-        // punctuators
-        match self {
-            LCurly | LParen | LBrack | Dot | Dot3 | Semicolon | Comma | LAngle | RAngle | LtEq
-            | GtEq | Eq | Eq2 | Eq3 | Neq | Neq2 | Plus | Plus2 | PlusEq | Minus | Minus2
-            | MinusEq | Star | Star2 | StarEq | Star2Eq | ShiftLeft | ShiftLeftEq | ShiftRight
-            | ShiftRightEq | ShiftRight3 | ShiftRight3Eq | Amp | AmpEq | Amp2 | Amp2Eq | Pipe
-            | PipeEq | Pipe2 | Pipe2Eq | Bang | Tilde | Question | Question2 | Question2Eq
-            | QuestionDot | Caret | CaretEq | Slash | SlashEq | FatArrow | Percent | PercentEq
-            | Colon => return true,
-            _ => {}
-        };
-        // keywords
-        match self {
-            New | Delete | Void | Typeof | Instanceof | In | Do | Return | Case | Throw | Else => {
-                return true
-            }
-            _ => {}
-        };
-        false
+        match self.group() {
+            // every punctuator but the closing ones
+            TokenGroup::Punctuator => !matches!(self, RParen | RBrack | RCurly | Hash),
+            TokenGroup::Keyword => matches!(
+                self,
+                New | Delete | Void | Typeof | Instanceof | In | Do | Return | Case | Throw | Else
+            ),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// `Name::Payload` or `Name(Payload)` as `Payload`, `s` otherwise
+fn strip_payload<'a>(s: &'a str, name: &str) -> &'a str {
+    s.strip_prefix(name)
+        .and_then(|rest| {
+            rest.strip_prefix("::")
+                .or_else(|| rest.strip_prefix('(')?.strip_suffix(')'))
+        })
+        .unwrap_or(s)
+}
+
+/// The variant name, the inverse of `Debug`.
+/// A number is `Number::Hex`, `Number(Hex)` or the bare `Hex`, and likewise a string.
+impl FromStr for Kind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = strip_payload(s, "Number");
+        if let Some(number) = NUMBERS.iter().find(|n| format!("{:?}", n) == number) {
+            return Ok(Number(*number));
+        }
+        let quote = strip_payload(s, "Str");
+        if let Some(quote) = QUOTES.iter().find(|q| format!("{:?}", q) == quote) {
+            return Ok(Str(*quote));
+        }
+        FIXED_VARIANTS
+            .iter()
+            .find(|kind| format!("{:?}", kind) == s)
+            .copied()
+            .ok_or_else(|| format!("invalid token kind `{}`", s))
     }
 }
@@ -1,11 +1,17 @@
 //! Lexer
+use std::{cell::Cell, ops::ControlFlow};
+
 use unicode_id::UnicodeID;
 
+use crate::asi::Asi;
 use crate::constants::{
-    ASCII_LINE_TERMINATORS, ASCII_LINE_TERMINATORS_CHAR, ASCII_SPACES, UNICODE_LINE_TERMINATORS,
-    UNICODE_SPACES,
+    is_ascii_in, ASCII_ID_CONTINUE, ASCII_ID_START, ASCII_LINE_TERMINATORS,
+    ASCII_LINE_TERMINATORS_CHAR, ASCII_SPACES, REGEX_FLAGS, REGEX_UNICODE_FLAGS,
+    UNICODE_LINE_TERMINATORS, UNICODE_SPACES,
 };
-use crate::kind::{Kind, Number};
+use crate::error::{sort_errors, ErrorKind, LexerError};
+use crate::kind::{Kind, Number, Quote};
+use crate::options::{EcmaVersion, LexerOptions};
 use crate::state::State;
 use crate::token::Token;
 
@@ -13,6 +19,9 @@ type LexerReturn = Option<(Kind, usize)>;
 
 pub struct Lexer<'a> {
     /// The input string
+    source: &'a str,
+
+    /// `source` as bytes, sliced by the readers and converted back with `Lexer::source_from`
     bytes: &'a [u8],
 
     /// The cursor position
@@ -23,12 +32,79 @@ pub struct Lexer<'a> {
 
     /// Lexer State
     state: State,
+
+    options: LexerOptions,
+
+    /// An error for each `Unknown` token, in source order
+    errors: Vec<LexerError>,
+
+    /// With `virtual_semicolons`
+    asi: Asi,
+
+    /// Tokens read ahead with `virtual_semicolons`, in reverse order
+    pending: Vec<Token>,
+
+    /// Within an identifier over `max_identifier_length`, whose next chunk is `Unknown` too.
+    /// A `Cell` as the readers borrow the lexer immutably.
+    capped_identifier: Cell<bool>,
 }
 
 impl Iterator for Lexer<'_> {
     type Item = Token;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.options.virtual_semicolons {
+            return self.read_with_virtual_semicolons();
+        }
+        self.read_token()
+    }
+}
+
+#[allow(clippy::unused_self)]
+impl<'a> Lexer<'a> {
+    #[must_use]
+    pub const fn new(source: &'a str) -> Self {
+        Self::with_options(source, LexerOptions::new())
+    }
+
+    #[must_use]
+    pub const fn with_options(source: &'a str, options: LexerOptions) -> Self {
+        Self {
+            source,
+            bytes: source.as_bytes(),
+            cur: 0,
+            eof: false,
+            state: State::new(),
+            options,
+            errors: Vec::new(),
+            asi: Asi::new(),
+            pending: Vec::new(),
+            capped_identifier: Cell::new(false),
+        }
+    }
+
+    /// Errors of the tokens lexed so far, in the order they were found
+    #[must_use]
+    pub fn errors(&self) -> &[LexerError] {
+        &self.errors
+    }
+
+    /// Errors of the tokens lexed so far, sorted by span start without duplicates
+    #[must_use]
+    pub fn errors_sorted(&self) -> Vec<&LexerError> {
+        sort_errors(&self.errors)
+    }
+
+    /// Lex the source and call `f` with each token including `EOF`, without collecting them.
+    /// Stops at the first `ControlFlow::Break`, which is returned.
+    pub fn for_each_token<B>(self, mut f: impl FnMut(&Token) -> ControlFlow<B>) -> ControlFlow<B> {
+        let mut lexer = self;
+        lexer.try_for_each(|token| f(&token))
+    }
+
+    /// The next token of the source, `EOF` once and then `None`
+    #[inline]
+    fn read_token(&mut self) -> Option<Token> {
         // return EOF if the current index is out of bounds
         // uses unlikely for branch prediction for improving performance
         if self.cur >= self.bytes.len() {
@@ -40,30 +116,64 @@ impl Iterator for Lexer<'_> {
         }
 
         // find the next token by examining from the current position
-        let result = self.dispatch_read(&self.bytes[self.cur..]);
-        let token = if let Some((kind, len)) = result {
-            self.state.update(&kind);
+        let chunk = self.capped_identifier.get();
+        let result = if chunk {
+            self.read_identifier_parts(&self.bytes[self.cur..], 0, 0)
+        } else {
+            self.dispatch_read(&self.bytes[self.cur..])
+        };
+        let mut token = if let Some((kind, len)) = result {
+            self.state.update(kind);
             Token::new(kind, self.cur, len)
         } else {
-            Token::new(Kind::Unknown, self.cur, 1)
+            self.capped_identifier.set(false);
+            // a whole char, so that the cursor stays on a char boundary
+            let len = self.source[self.cur..]
+                .chars()
+                .next()
+                .map_or(1, char::len_utf8);
+            Token::new(Kind::Unknown, self.cur, len)
         };
+        if token.is_unknown() {
+            let first = self.bytes[self.cur];
+            // a chunk may start with a digit
+            let kind = if chunk {
+                ErrorKind::InvalidIdentifier
+            } else {
+                ErrorKind::from_first_byte(first)
+            };
+            self.errors.push(LexerError::new(kind, token.range()));
+            // an unterminated string
+            if let Some(quote) = Quote::from_byte(first) {
+                token = token.with_unterminated_quote(quote);
+            }
+        }
 
         // move the cursor
         self.cur += token.len();
         Some(token)
     }
-}
 
-#[allow(clippy::unused_self)]
-impl<'a> Lexer<'a> {
-    #[must_use]
-    pub const fn new(source: &'a str) -> Self {
-        Self {
-            bytes: source.as_bytes(),
-            cur: 0,
-            eof: false,
-            state: State::new(),
+    /// With `virtual_semicolons`, read ahead the trivia after a token up to the next significant
+    /// token, so that a virtual semicolon comes right after the significant token it follows.
+    /// Cold to keep `next` small, inlining this into it halves the throughput without the option.
+    #[cold]
+    fn read_with_virtual_semicolons(&mut self) -> Option<Token> {
+        if let Some(token) = self.pending.pop() {
+            return Some(token);
         }
+        let mut token = self.read_token()?;
+        let source = self.source;
+        while token.is_trivia() {
+            self.asi.before(source, &token);
+            self.pending.push(token);
+            // `EOF` follows the trivia at the latest
+            token = self.read_token()?;
+        }
+        let semicolon = self.asi.before(source, &token);
+        self.pending.push(token);
+        self.pending.reverse();
+        semicolon.or_else(|| self.pending.pop())
     }
 
     #[inline]
@@ -101,9 +211,12 @@ impl<'a> Lexer<'a> {
             b'&' => Some(self.read_ampersand(bytes)),
             b'|' => Some(self.read_pipe(bytes)),
             b'?' => Some(self.read_question(bytes)),
-            n if n.is_ascii_alphabetic() => self
-                .read_identifier(bytes)
-                .map(|(_, len)| (self.read_keyword(&bytes[..len]), len)),
+            n if n.is_ascii_alphabetic() => {
+                self.read_identifier(bytes).map(|(kind, len)| match kind {
+                    Kind::Ident => (self.read_keyword(&bytes[..len]), len),
+                    _ => (kind, len),
+                })
+            }
             _ => self
                 .read_unicode_whitespaces(bytes)
                 .or_else(|| self.read_unicode_line_terminators(bytes))
@@ -126,7 +239,8 @@ impl<'a> Lexer<'a> {
 
     #[inline]
     fn read_unicode_whitespaces(&self, bytes: &[u8]) -> LexerReturn {
-        let len = Lexer::from_utf8_unchecked(bytes)
+        let len = self
+            .source_from(bytes)
             .chars()
             .take_while(|c| UNICODE_SPACES.contains(c))
             .map(char::len_utf8)
@@ -152,7 +266,8 @@ impl<'a> Lexer<'a> {
 
     #[inline]
     fn read_unicode_line_terminators(&self, bytes: &[u8]) -> LexerReturn {
-        let len = Lexer::from_utf8_unchecked(bytes)
+        let len = self
+            .source_from(bytes)
             .chars()
             .take_while(|c| UNICODE_LINE_TERMINATORS.contains(c))
             .map(char::len_utf8)
@@ -168,7 +283,8 @@ impl<'a> Lexer<'a> {
     fn read_single_comment(&self, bytes: &[u8]) -> (Kind, usize) {
         assert_eq!(bytes[0], b'/');
         assert_eq!(bytes[1], b'/');
-        let len = Lexer::from_utf8_unchecked(bytes)
+        let len = self
+            .source_from(bytes)
             .chars()
             .skip(2)
             .take_while(|c| {
@@ -185,7 +301,8 @@ impl<'a> Lexer<'a> {
         assert_eq!(bytes[0], b'/');
         assert_eq!(bytes[1], b'*');
         let mut cur = 2;
-        while let Some(bytes) = bytes.get(cur..) {
+        // unterminated, the comment ends at EOF
+        while let Some(bytes) = bytes.get(cur..).filter(|bytes| !bytes.is_empty()) {
             if bytes.starts_with(&[b'*', b'/']) {
                 cur += 2;
                 break;
@@ -195,116 +312,126 @@ impl<'a> Lexer<'a> {
         (Kind::MultilineComment, cur)
     }
 
-    /// Section 12.6.1 Identifier Names
+    /// Section 12.6.1 Identifier Names.
+    /// Runs of ASCII identifier bytes are scanned with a bitmask, the chars beyond ASCII
+    /// and the escapes one by one.
     #[inline]
     fn read_identifier(&self, bytes: &[u8]) -> LexerReturn {
-        let mut iter = Lexer::from_utf8_unchecked(bytes).chars().peekable();
-        let mut len = 0;
-        if let Some(c) = iter.next() {
-            if self.is_identifier_start(c) {
-                len += c.len_utf8();
-            } else if c == '\\' && iter.peek() == Some(&'u') {
-                if let Some(count) = self.read_unicode_escape_sequence(bytes) {
-                    len += count;
-                    for _ in 0..count - 1 {
-                        iter.next();
-                    }
-                } else {
+        let (len, name_len) = match bytes.first() {
+            Some(&b) if is_ascii_in(ASCII_ID_START, b) => (1, 1),
+            Some(b'\\') if bytes.get(1) == Some(&b'u') => {
+                match self.read_identifier_escape(bytes, true) {
+                    Some((c, len)) => (len, c.len_utf8()),
+                    None => return Some((Kind::Unknown, self.invalid_escape_len(bytes))),
+                }
+            }
+            Some(&b) if !b.is_ascii() => {
+                let c = self.source_from(bytes).chars().next()?;
+                if !self.is_identifier_start(c) {
                     return None;
                 }
-            } else {
-                return None;
+                (c.len_utf8(), c.len_utf8())
             }
-        }
-        if len == 0 {
-            return None;
-        }
-        while let Some(c) = iter.next() {
-            if self.is_identifier_part(c) {
-                len += c.len_utf8();
-            } else if c == '\\' && iter.peek() == Some(&'u') {
-                if let Some(count) = self.read_unicode_escape_sequence(&bytes[len..]) {
-                    len += count;
-                    for _ in 0..count - 1 {
-                        iter.next();
+            _ => return None,
+        };
+        self.read_identifier_parts(bytes, len, name_len)
+    }
+
+    /// The identifier parts after the first `len` bytes of an identifier, `name_len` bytes
+    /// once its escapes are decoded. Over `max_identifier_length`, the identifier is cut into
+    /// `Unknown` chunks, each read from `len` 0 after the first.
+    #[inline]
+    fn read_identifier_parts(
+        &self,
+        bytes: &[u8],
+        mut len: usize,
+        mut name_len: usize,
+    ) -> LexerReturn {
+        let max = self.options.max_identifier_length.unwrap_or(usize::MAX);
+        loop {
+            let run = bytes[len..]
+                .iter()
+                .take_while(|b| is_ascii_in(ASCII_ID_CONTINUE, **b))
+                .count();
+            if name_len + run > max {
+                // a chunk holds a char at least
+                let fit = max.saturating_sub(name_len).max(usize::from(len == 0));
+                self.capped_identifier.set(true);
+                return Some((Kind::Unknown, len + fit));
+            }
+            len += run;
+            name_len += run;
+            let rest = &bytes[len..];
+            let (count, name_count) = match rest.first() {
+                Some(b'\\') if rest.get(1) == Some(&b'u') => {
+                    if let Some((c, count)) = self.read_identifier_escape(rest, false) {
+                        (count, c.len_utf8())
+                    } else {
+                        // the identifier through the escape is the error
+                        self.capped_identifier.set(false);
+                        return Some((Kind::Unknown, len + self.invalid_escape_len(rest)));
                     }
-                } else {
-                    return None;
                 }
-            } else {
-                break;
+                Some(&b) if !b.is_ascii() => {
+                    let c = self.source_from(rest).chars().next()?;
+                    if !self.is_identifier_part(c) {
+                        break;
+                    }
+                    (c.len_utf8(), c.len_utf8())
+                }
+                _ => break,
+            };
+            if name_len + name_count > max && len > 0 {
+                self.capped_identifier.set(true);
+                return Some((Kind::Unknown, len));
             }
+            len += count;
+            name_len += name_count;
+        }
+        // the last chunk of a capped identifier
+        let kind = if self.capped_identifier.replace(false) {
+            Kind::Unknown
+        } else {
+            Kind::Ident
+        };
+        Some((kind, len))
+    }
+
+    /// The code point and the length of a `UnicodeEscapeSequence` in an identifier, `None` if
+    /// it is malformed or decodes to a code point that is not an identifier start (or part when
+    /// not `start`). A surrogate is never an identifier character, neither alone nor as a pair
+    /// with a following low surrogate escape, as an identifier escape is a code point.
+    /// Strings accept both.
+    #[inline]
+    fn read_identifier_escape(&self, bytes: &[u8], start: bool) -> Option<(char, usize)> {
+        let (c, len) = self.decode_unicode_escape_sequence(bytes)?;
+        if (0xD800..=0xDFFF).contains(&c) {
+            return None;
         }
-        Some((Kind::Ident, len))
+        let c = char::from_u32(c)?;
+        let valid = if start {
+            self.is_identifier_start(c)
+        } else {
+            self.is_identifier_part(c)
+        };
+        valid.then(|| (c, len))
+    }
+
+    /// Length of an invalid escape in an identifier, the whole sequence when well-formed
+    /// e.g. a surrogate, else `\u`
+    #[inline]
+    fn invalid_escape_len(&self, bytes: &[u8]) -> usize {
+        self.read_unicode_escape_sequence(bytes).unwrap_or(2)
     }
 
     /// Section 12.6.2 Keywords and Reserved Words
     #[inline]
     const fn read_keyword(&self, bytes: &[u8]) -> Kind {
-        match bytes.len() {
-            2 => match bytes {
-                b"do" => Kind::Do,
-                b"if" => Kind::If,
-                b"in" => Kind::In,
-                _ => Kind::Ident,
-            },
-            3 => match bytes {
-                b"new" => Kind::New,
-                b"try" => Kind::Try,
-                b"var" => Kind::Var,
-                b"for" => Kind::For,
-                _ => Kind::Ident,
-            },
-            4 => match bytes {
-                b"case" => Kind::Case,
-                b"else" => Kind::Else,
-                b"enum" => Kind::Enum,
-                b"this" => Kind::This,
-                b"true" => Kind::True,
-                b"void" => Kind::Void,
-                b"with" => Kind::With,
-                b"null" => Kind::Null,
-                _ => Kind::Ident,
-            },
-            5 => match bytes {
-                b"await" => Kind::Await,
-                b"break" => Kind::Break,
-                b"catch" => Kind::Catch,
-                b"class" => Kind::Class,
-                b"const" => Kind::Const,
-                b"false" => Kind::False,
-                b"while" => Kind::While,
-                b"super" => Kind::Super,
-                b"throw" => Kind::Throw,
-                b"yield" => Kind::Yield,
-                _ => Kind::Ident,
-            },
-            6 => match bytes {
-                b"delete" => Kind::Delete,
-                b"export" => Kind::Export,
-                b"import" => Kind::Import,
-                b"return" => Kind::Return,
-                b"switch" => Kind::Switch,
-                b"typeof" => Kind::Typeof,
-                _ => Kind::Ident,
-            },
-            7 => match bytes {
-                b"default" => Kind::DefaulT,
-                b"extends" => Kind::Extends,
-                b"finally" => Kind::FinallY,
-                _ => Kind::Ident,
-            },
-            8 => match bytes {
-                b"continue" => Kind::Continue,
-                b"debugger" => Kind::Debugger,
-                b"function" => Kind::Function,
-                _ => Kind::Ident,
-            },
-            10 => match bytes {
-                b"instanceof" => Kind::Instanceof,
-                _ => Kind::Ident,
-            },
-            _ => Kind::Ident,
+        match Kind::match_keyword(bytes) {
+            kind if kind.is_future_reserved() && !(self.options.strict || self.options.module) => {
+                Kind::Ident
+            }
+            kind => kind,
         }
     }
 
@@ -318,12 +445,9 @@ impl<'a> Lexer<'a> {
         if bytes.get(2) == Some(&b'{') {
             let mut len = 0;
             while len < 6 {
-                if let Some(b) = bytes.get(len + 3) {
-                    if b.is_ascii_hexdigit() {
-                        len += 1;
-                    } else {
-                        break;
-                    }
+                match bytes.get(len + 3) {
+                    Some(b) if b.is_ascii_hexdigit() => len += 1,
+                    _ => break,
                 }
             }
             return if bytes.get(len + 3) == Some(&b'}') {
@@ -339,6 +463,21 @@ impl<'a> Lexer<'a> {
         None
     }
 
+    /// Decode a `UnicodeEscapeSequence` into its code point, with the length of the sequence
+    #[inline]
+    fn decode_unicode_escape_sequence(&self, bytes: &[u8]) -> Option<(u32, usize)> {
+        let len = self.read_unicode_escape_sequence(bytes)?;
+        let digits = if bytes[2] == b'{' {
+            &bytes[3..len - 1]
+        } else {
+            &bytes[2..len]
+        };
+        u32::from_str_radix(std::str::from_utf8(digits).ok()?, 16)
+            .ok()
+            .filter(|c| *c <= 0x0010_FFFF)
+            .map(|c| (c, len))
+    }
+
     /// Section 12.7 Punctuators
     #[inline]
     fn read_dot(&self, bytes: &[u8]) -> (Kind, usize) {
@@ -487,7 +626,7 @@ impl<'a> Lexer<'a> {
             return (Kind::AmpEq, 2); // &=
         }
         if iter.next_if_eq(&&b'&').is_some() {
-            if iter.peek() == Some(&&b'=') {
+            if self.has_logical_assignment() && iter.peek() == Some(&&b'=') {
                 return (Kind::Amp2Eq, 3); // &&=
             }
             return (Kind::Amp2, 2); // &&
@@ -502,8 +641,11 @@ impl<'a> Lexer<'a> {
         if iter.peek() == Some(&&b'=') {
             return (Kind::PipeEq, 2); // |=
         }
+        if self.options.experimental && iter.peek() == Some(&&b'>') {
+            return (Kind::PipeGt, 2); // |>
+        }
         if iter.next_if_eq(&&b'|').is_some() {
-            if iter.peek() == Some(&&b'=') {
+            if self.has_logical_assignment() && iter.peek() == Some(&&b'=') {
                 return (Kind::Pipe2Eq, 3); // ||=
             }
             return (Kind::Pipe2, 2); // ||
@@ -515,11 +657,12 @@ impl<'a> Lexer<'a> {
     fn read_question(&self, bytes: &[u8]) -> (Kind, usize) {
         assert_eq!(bytes[0], b'?');
         let mut iter = bytes.iter().skip(1).peekable();
-        if iter.peek() == Some(&&b'.') {
+        // `?.5` is a conditional, `?.` is never followed by a digit
+        if iter.peek() == Some(&&b'.') && !bytes.get(2).map_or(false, u8::is_ascii_digit) {
             return (Kind::QuestionDot, 2); // ?.
         }
         if iter.next_if_eq(&&b'?').is_some() {
-            if iter.peek() == Some(&&b'=') {
+            if self.has_logical_assignment() && iter.peek() == Some(&&b'=') {
                 return (Kind::Question2Eq, 3); // ??=
             }
             return (Kind::Question2, 2); // ??
@@ -580,7 +723,12 @@ impl<'a> Lexer<'a> {
                         .read_number_exponent_part(&bytes[len..])
                         .map(|count| (Kind::Number(kind), len + count));
                 }
+                // a separator goes between two digits, never next to `.`, `_` or at the end
                 b'_' => {
+                    let digit = |i: usize| bytes.get(i).map_or(false, u8::is_ascii_digit);
+                    if !digit(len - 1) || !digit(len + 1) {
+                        return None;
+                    }
                     len += 1;
                 }
                 n if n.is_ascii_digit() => {
@@ -595,16 +743,26 @@ impl<'a> Lexer<'a> {
     #[inline]
     fn read_number_exponent_part(&self, bytes: &[u8]) -> Option<usize> {
         assert!(matches!(bytes[0], b'e' | b'E'));
-        let mut iter = bytes.iter().skip(1).peekable();
         let mut len = 1;
-        if iter.next_if(|b| b == &&b'-' || b == &&b'+').is_some() {
+        if matches!(bytes.get(1), Some(b'-' | b'+')) {
             len += 1;
         }
-        let digits = iter.take_while(|c| c.is_ascii_digit()).count();
-        if digits == 0 {
-            return None;
+        let start = len;
+        while let Some(b) = bytes.get(len) {
+            match b {
+                // a separator goes between two digits, as in `read_number`
+                b'_' => {
+                    let digit = |i: usize| bytes.get(i).map_or(false, u8::is_ascii_digit);
+                    if len == start || !digit(len - 1) || !digit(len + 1) {
+                        return None;
+                    }
+                    len += 1;
+                }
+                n if n.is_ascii_digit() => len += 1,
+                _ => break,
+            }
         }
-        Some(len + digits)
+        (len > start).then(|| len)
     }
 
     #[inline]
@@ -676,8 +834,8 @@ impl<'a> Lexer<'a> {
     /// 12.8.4 String Literals
     #[inline]
     fn read_string_literal(&self, bytes: &[u8]) -> LexerReturn {
-        assert!(matches!(bytes[0], b'\'' | b'"'));
-        let mut iter = Lexer::from_utf8_unchecked(bytes).chars().peekable();
+        let kind = Kind::Str(Quote::from_byte(bytes[0]).unwrap());
+        let mut iter = self.source_from(bytes).chars().peekable();
         let quote = iter.next().unwrap();
         let mut len = 1;
         while let Some(b) = iter.next() {
@@ -686,10 +844,10 @@ impl<'a> Lexer<'a> {
                 len += 1;
                 iter.next();
             } else if b == quote {
-                return Some((Kind::Str, len));
+                return Some((kind, len));
             }
         }
-        None
+        self.read_unterminated(bytes)
     }
 
     /// 12.8.5 Regular Expression Literals
@@ -713,7 +871,10 @@ impl<'a> Lexer<'a> {
                         cur += 1;
                         continue;
                     }
-                    return Some((Kind::Regex, cur + 1));
+                    let (len, valid) = self.read_regex_flags(&bytes[cur + 1..]);
+                    // a single error token, the body is not code
+                    let kind = if valid { Kind::Regex } else { Kind::Unknown };
+                    return Some((kind, cur + 1 + len));
                 }
                 b'\\' => {
                     if iter.peek().map_or(false, |q| q == &&b'/' || q == &&b'\\') {
@@ -725,24 +886,64 @@ impl<'a> Lexer<'a> {
             }
             cur += 1;
         }
-        None
+        self.read_unterminated(bytes)
+    }
+
+    /// 12.8.5 `RegularExpressionFlags`, returns the length of the flags and whether they are valid.
+    /// Flags are identifier parts, each must be a flag letter, also when escaped.
+    /// A repeated flag or both `u` and `v` is an error, also when spelled with escapes.
+    #[inline]
+    fn read_regex_flags(&self, bytes: &[u8]) -> (usize, bool) {
+        let mut len = 0;
+        let mut flags = Some(0);
+        while let Some(rest) = bytes.get(len..) {
+            let (flag, count) = match rest {
+                [b'\\', b'u', ..] => match self.decode_unicode_escape_sequence(rest) {
+                    Some((c, count)) => (self.regex_flag(c), count),
+                    None => (None, 2),
+                },
+                [_, ..] => match self.source_from(rest).chars().next() {
+                    Some(c) if self.is_identifier_part(c) => {
+                        (self.regex_flag(u32::from(c)), c.len_utf8())
+                    }
+                    _ => break,
+                },
+                [] => break,
+            };
+            flags = flags
+                .zip(flag)
+                .and_then(|(flags, flag)| self.add_regex_flag(flags, flag));
+            len += count;
+        }
+        (len, flags.is_some())
     }
 
-    /// 12.8.6 Template Literal Lexical Components
+    /// 12.8.6 Template Literal Lexical Components.
+    /// A backslash escapes whatever follows, e.g. `` \` ``, `\${` which does not start a substitution,
+    /// or a line terminator which is a line continuation
     #[inline]
     fn read_template_literal(&self, bytes: &[u8]) -> LexerReturn {
         assert_eq!(bytes[0], b'`');
-        let mut iter = bytes.iter().enumerate().skip(1).peekable();
+        let mut iter = bytes.iter().enumerate().skip(1);
         while let Some((len, b)) = iter.next() {
             match &b {
                 b'\\' => {
-                    iter.next_if(|t| t.1 == &b'`' || t.1 == &b'\\');
+                    // the rest of a multi-byte character or a `\n` after `\r` is never a backtick
+                    iter.next();
                 }
                 b'`' => return Some((Kind::Template, len + 1)),
                 _ => {}
             }
         }
-        None
+        self.read_unterminated(bytes)
+    }
+
+    /// A string, template or regex literal reaching EOF,
+    /// e.g. `"a\` with a dangling backslash, is a single `Unknown` token up to EOF
+    #[inline]
+    #[allow(clippy::unnecessary_wraps)]
+    const fn read_unterminated(&self, bytes: &[u8]) -> LexerReturn {
+        Some((Kind::Unknown, bytes.len()))
     }
 
     /// Read Slash `/`:
@@ -777,11 +978,36 @@ impl<'a> Lexer<'a> {
             || c == '\u{200c}' || c == '\u{200d}'
     }
 
-    /// `std::str::from_utf8_unchecked`
-    /// Safefy: we assumed byte string is utf8
+    /// `&&=`, `||=` and `??=` are ES2021
     #[inline]
-    #[must_use]
-    const fn from_utf8_unchecked(bytes: &[u8]) -> &str {
-        unsafe { std::str::from_utf8_unchecked(bytes) }
+    fn has_logical_assignment(&self) -> bool {
+        self.options.ecma_version >= EcmaVersion::ES2021
+    }
+
+    /// Section 22.2.3.1 `RegExpInitialize` flags, a bit for each flag letter
+    #[inline]
+    fn regex_flag(&self, c: u32) -> Option<u8> {
+        let i = char::from_u32(c).and_then(|c| REGEX_FLAGS.find(c))?;
+        Some(1 << i)
+    }
+
+    /// `flags` with `flag`, `None` if it is repeated or makes both `u` and `v`
+    #[inline]
+    fn add_regex_flag(&self, flags: u8, flag: u8) -> Option<u8> {
+        let flags = (flags & flag == 0).then(|| flags | flag)?;
+        (flags & REGEX_UNICODE_FLAGS != REGEX_UNICODE_FLAGS).then(|| flags)
+    }
+
+    /// The source from the start of `bytes`, a suffix of the source sliced by a reader.
+    /// The cursor only ever sits on a char boundary, as every token is whole chars, an
+    /// `Unknown` token included, and the readers slice after whole chars. Slicing checks it.
+    #[inline]
+    fn source_from(&self, bytes: &[u8]) -> &'a str {
+        debug_assert_eq!(
+            bytes.as_ptr_range().end,
+            self.bytes.as_ptr_range().end,
+            "not a suffix of the source"
+        );
+        &self.source[self.bytes.len() - bytes.len()..]
     }
 }
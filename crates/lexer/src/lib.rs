@@ -1,11 +1,41 @@
 #![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+#![forbid(unsafe_code)]
 
+mod asi;
+mod attach;
+mod capacity;
+mod channel;
+mod comment;
 mod constants;
+mod cook;
+mod directive;
+mod dump;
+mod error;
 mod kind;
 mod lexer;
+mod lossy;
+mod options;
+mod repl;
+mod soa;
+mod span;
 mod state;
+mod statistics;
 mod token;
 
+pub use crate::attach::*;
+pub use crate::capacity::*;
+pub use crate::channel::*;
+pub use crate::comment::*;
+pub use crate::cook::*;
+pub use crate::directive::*;
+pub use crate::dump::*;
+pub use crate::error::*;
 pub use crate::kind::*;
 pub use crate::lexer::*;
+pub use crate::lossy::*;
+pub use crate::options::*;
+pub use crate::repl::*;
+pub use crate::soa::*;
+pub use crate::span::*;
+pub use crate::statistics::*;
 pub use crate::token::*;
@@ -0,0 +1,40 @@
+//! Lexing of bytes which are mostly but not entirely UTF-8
+
+use crate::kind::Kind;
+use crate::lexer::Lexer;
+use crate::token::Token;
+
+/// Lex `bytes` like [`Lexer`], with an `Unknown` token for each invalid UTF-8 sequence,
+/// where `String::from_utf8_lossy` would substitute U+FFFD. Offsets are in `bytes`.
+///
+/// Each valid run between invalid sequences is lexed on its own,
+/// so a string or comment containing an invalid sequence is split at it.
+#[must_use]
+#[allow(clippy::module_name_repetitions)]
+pub fn tokenize_lossy(bytes: &[u8]) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    while start < bytes.len() {
+        let rest = &bytes[start..];
+        let (valid, invalid) = match std::str::from_utf8(rest) {
+            Ok(source) => (source, 0),
+            Err(e) => {
+                let valid = std::str::from_utf8(&rest[..e.valid_up_to()]).unwrap_or_default();
+                // a sequence truncated by the end of the input
+                let invalid = e.error_len().unwrap_or(rest.len() - valid.len());
+                (valid, invalid)
+            }
+        };
+        tokens.extend(
+            Lexer::new(valid)
+                .filter(|token| token.kind() != &Kind::EOF)
+                .map(|token| Token::new(*token.kind(), start + token.range().start, token.len())),
+        );
+        if invalid > 0 {
+            tokens.push(Token::new(Kind::Unknown, start + valid.len(), invalid));
+        }
+        start += valid.len() + invalid;
+    }
+    tokens.push(Token::new(Kind::EOF, bytes.len(), 1));
+    tokens
+}
@@ -0,0 +1,85 @@
+//! Lexer Options
+
+/// ECMAScript version to lex, syntax from later versions is lexed as in this version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EcmaVersion {
+    ES2015,
+    ES2016,
+    ES2017,
+    ES2018,
+    ES2019,
+    ES2020,
+    ES2021,
+    ES2022,
+}
+
+impl EcmaVersion {
+    pub const LATEST: Self = Self::ES2022;
+
+    /// The version of a year, like the `ecmaVersion` of acorn, `None` before 2015 or after 2022
+    #[must_use]
+    pub const fn from_year(year: u32) -> Option<Self> {
+        let version = match year {
+            2015 => Self::ES2015,
+            2016 => Self::ES2016,
+            2017 => Self::ES2017,
+            2018 => Self::ES2018,
+            2019 => Self::ES2019,
+            2020 => Self::ES2020,
+            2021 => Self::ES2021,
+            2022 => Self::ES2022,
+            _ => return None,
+        };
+        Some(version)
+    }
+}
+
+impl Default for EcmaVersion {
+    fn default() -> Self {
+        Self::LATEST
+    }
+}
+
+#[allow(clippy::module_name_repetitions)]
+// independent switches, as those of other JavaScript tools
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LexerOptions {
+    /// Lex syntax from proposals, e.g. the pipeline operator `|>`
+    pub experimental: bool,
+
+    pub ecma_version: EcmaVersion,
+
+    /// Identifiers longer than this many bytes, escapes decoded, are split into `Unknown` tokens
+    /// of at most this length, bounding the work per token on untrusted input
+    pub max_identifier_length: Option<usize>,
+
+    /// Insert zero length `VirtualSemicolon` tokens where a semicolon is automatically inserted,
+    /// for tools which make ASI explicit. True ASI needs a parser, so this only covers the
+    /// unambiguous cases: a line break after `return`, `throw`, `break` or `continue`,
+    /// a line break before the `}` of a block, and `EOF`.
+    pub virtual_semicolons: bool,
+
+    /// Lex strict mode code, where the future reserved words such as `public` are keywords,
+    /// see [`crate::Kind::is_future_reserved`]. They are `Ident` in sloppy scripts.
+    pub strict: bool,
+
+    /// Lex module code, which is strict mode code, see [`crate::Kind::is_reserved_in_module`]
+    /// for the words a parser reports as identifiers
+    pub module: bool,
+}
+
+impl LexerOptions {
+    /// Same as `default`, usable in const contexts
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            experimental: false,
+            ecma_version: EcmaVersion::LATEST,
+            max_identifier_length: None,
+            virtual_semicolons: false,
+            strict: false,
+            module: false,
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! Incremental lexing for REPLs
+
+use crate::asi::{continues_statement, ends_statement, is_line_break};
+#[allow(clippy::enum_glob_use)]
+use crate::kind::Kind::*;
+use crate::lexer::Lexer;
+use crate::token::Token;
+
+/// Lex up to the end of the first statement, for REPL-style incremental evaluation.
+/// Returns the tokens (without `EOF`) and the offset where lexing stopped.
+///
+/// This is a heuristic, not a parser. Outside of `()`, `[]` and `{}`:
+///   * a `;` ends the statement and is included
+///   * a line break ends the statement when the token before it can end an expression
+///     (e.g. an identifier, a literal, `)` or `return`) and the token after it cannot continue one
+///     (e.g. a binary operator, `.`, `(` or a template), the line break is not included
+///
+/// A `}` does not end the statement by itself, `if (a) {} b` is lexed as one statement.
+/// Trailing trivia at the end of `source` is included unless it starts with such a line break.
+#[must_use]
+pub fn lex_first_statement(source: &str) -> (Vec<Token>, usize) {
+    let mut tokens = vec![];
+    // trivia since a line break which ends the statement, unless the next token continues it
+    let mut pending: Vec<Token> = vec![];
+    let mut depth = 0_usize;
+    let mut last = None;
+    for token in Lexer::new(source) {
+        let kind = *token.kind();
+        if kind == EOF {
+            break;
+        }
+        if !pending.is_empty() {
+            if kind.is_trivia() {
+                pending.push(token);
+                continue;
+            }
+            if !continues_statement(last, kind) {
+                return (tokens, pending[0].range().start);
+            }
+            tokens.append(&mut pending);
+        } else if depth == 0 && is_line_break(source, &token) && last.map_or(false, ends_statement)
+        {
+            pending.push(token);
+            continue;
+        }
+        match kind {
+            LParen | LBrack | LCurly => depth += 1,
+            RParen | RBrack | RCurly => depth = depth.saturating_sub(1),
+            Semicolon if depth == 0 => {
+                let end = token.range().end;
+                tokens.push(token);
+                return (tokens, end);
+            }
+            _ => {}
+        }
+        if !kind.is_trivia() {
+            last = Some(kind);
+        }
+        tokens.push(token);
+    }
+    let end = pending
+        .first()
+        .map_or(source.len(), |token| token.range().start);
+    (tokens, end)
+}
@@ -0,0 +1,135 @@
+//! Struct of arrays token buffer
+
+use std::convert::TryFrom;
+
+use crate::capacity::estimate_token_count;
+use crate::kind::Kind;
+use crate::lexer::Lexer;
+use crate::token::Token;
+
+/// Largest source with `u32` offsets, of [`TokenBuffer`] and the bindings, one byte less than
+/// `u32::MAX` as the `EOF` token ends one byte past the source.
+/// The `usize` offsets of [`Lexer`] and [`Token`] have no limit.
+pub const MAX_U32_SOURCE_LEN: usize = u32::MAX as usize - 1;
+
+/// Check that the offsets of a source of `len` bytes fit in `u32`, rather than truncating them
+/// # Errors
+/// `len` is larger than [`MAX_U32_SOURCE_LEN`]
+pub fn check_u32_offsets(len: usize) -> Result<(), String> {
+    if len > MAX_U32_SOURCE_LEN {
+        return Err(format!(
+            "Source of {len} bytes is larger than {MAX_U32_SOURCE_LEN} bytes, the limit of u32 offsets"
+        ));
+    }
+    Ok(())
+}
+
+/// Tokens stored as parallel arrays of kinds, starts and lengths,
+/// so a pass scanning only the kinds touches a fraction of the memory.
+///
+/// Offsets are `u32`, the source is limited to [`MAX_U32_SOURCE_LEN`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TokenBuffer {
+    kinds: Vec<Kind>,
+    starts: Vec<u32>,
+    lens: Vec<u32>,
+}
+
+impl TokenBuffer {
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            kinds: Vec::with_capacity(capacity),
+            starts: Vec::with_capacity(capacity),
+            lens: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// # Panics
+    /// The token ends past 4 GiB
+    pub fn push(&mut self, token: &Token) {
+        let range = token.range();
+        let offset = |n: usize| u32::try_from(n).expect("source larger than 4 GiB");
+        let (start, end) = (offset(range.start), offset(range.end));
+        self.kinds.push(*token.kind());
+        self.starts.push(start);
+        self.lens.push(end - start);
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.kinds.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.kinds.is_empty()
+    }
+
+    #[must_use]
+    pub fn kinds(&self) -> &[Kind] {
+        &self.kinds
+    }
+
+    /// Offset of each token in source
+    #[must_use]
+    pub fn starts(&self) -> &[u32] {
+        &self.starts
+    }
+
+    /// Length of each token
+    #[must_use]
+    pub fn lens(&self) -> &[u32] {
+        &self.lens
+    }
+
+    /// The token at `index`, reconstructed from the arrays
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<Token> {
+        Some(Token::new(
+            *self.kinds.get(index)?,
+            self.starts[index] as usize,
+            self.lens[index] as usize,
+        ))
+    }
+
+    /// The tokens in source order, reconstructed on demand
+    #[must_use]
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Token> + '_ {
+        (0..self.len()).map(move |index| {
+            Token::new(
+                self.kinds[index],
+                self.starts[index] as usize,
+                self.lens[index] as usize,
+            )
+        })
+    }
+}
+
+impl Extend<Token> for TokenBuffer {
+    fn extend<I: IntoIterator<Item = Token>>(&mut self, tokens: I) {
+        for token in tokens {
+            self.push(&token);
+        }
+    }
+}
+
+/// Lex `source` into a [`TokenBuffer`], `EOF` included like [`Lexer`]
+/// # Panics
+/// `source` is larger than [`MAX_U32_SOURCE_LEN`], see [`try_tokenize_soa`]
+#[must_use]
+#[allow(clippy::module_name_repetitions)]
+pub fn tokenize_soa(source: &str) -> TokenBuffer {
+    let mut buffer = TokenBuffer::with_capacity(estimate_token_count(source));
+    buffer.extend(Lexer::new(source));
+    buffer
+}
+
+/// [`tokenize_soa`] with an error for a source larger than [`MAX_U32_SOURCE_LEN`]
+/// # Errors
+/// See [`check_u32_offsets`]
+#[allow(clippy::module_name_repetitions)]
+pub fn try_tokenize_soa(source: &str) -> Result<TokenBuffer, String> {
+    check_u32_offsets(source.len())?;
+    Ok(tokenize_soa(source))
+}
@@ -12,7 +12,7 @@ impl State {
         Self { expr: true }
     }
 
-    pub fn update(&mut self, kind: &Kind) {
+    pub fn update(&mut self, kind: Kind) {
         if !matches!(kind, WhiteSpace | LineTerminator) {
             self.expr = kind.at_expr();
         }
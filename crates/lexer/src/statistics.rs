@@ -0,0 +1,126 @@
+//! Statistics
+
+use crate::kind::Kind;
+use crate::lexer::Lexer;
+use crate::token::Token;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Statistics {
+    /// Number of tokens, excluding EOF
+    pub tokens: usize,
+
+    /// Number of `Kind::Unknown` tokens
+    pub unknown_tokens: usize,
+
+    /// Number of whitespace, line terminator and comment tokens
+    pub trivia: usize,
+
+    /// Bytes of whitespace and line terminator tokens
+    pub whitespace_bytes: usize,
+
+    /// Bytes of comment tokens
+    pub comment_bytes: usize,
+
+    /// Bytes of the tokens a parser consumes, the rest of the source is whitespace and comments
+    pub significant_bytes: usize,
+
+    /// Number of tokens of each kind, excluding EOF, in the order the kinds first appear
+    pub kinds: Vec<(Kind, usize)>,
+
+    /// Identifier shapes common in minified or obfuscated code
+    pub obfuscation_hints: ObfuscationHints,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ObfuscationHints {
+    /// Number of identifiers
+    pub identifiers: usize,
+
+    /// Identifiers with a single character, e.g. `$`, `_`, `e`
+    pub single_char_identifiers: usize,
+
+    /// Identifiers that look like hex numbers, e.g. `_0x1a2b`, `$0xff`
+    pub hex_identifiers: usize,
+}
+
+impl Statistics {
+    /// Compute statistics for `source` in a single lex pass
+    #[must_use]
+    pub fn new(source: &str) -> Self {
+        let mut stats = Self::default();
+        for token in Lexer::new(source) {
+            match token.kind() {
+                Kind::EOF => continue,
+                Kind::Unknown => stats.unknown_tokens += 1,
+                Kind::Ident => stats
+                    .obfuscation_hints
+                    .record_identifier(&source[token.range()]),
+                kind if kind.is_trivia() => stats.trivia += 1,
+                _ => {}
+            }
+            match token.kind() {
+                Kind::WhiteSpace | Kind::LineTerminator => stats.whitespace_bytes += token.len(),
+                Kind::Comment | Kind::MultilineComment => stats.comment_bytes += token.len(),
+                _ => stats.significant_bytes += token.len(),
+            }
+            stats.tokens += 1;
+            stats.record_kind(*token.kind());
+        }
+        stats
+    }
+
+    fn record_kind(&mut self, kind: Kind) {
+        match self.kinds.iter_mut().find(|(k, _)| *k == kind) {
+            Some((_, count)) => *count += 1,
+            None => self.kinds.push((kind, 1)),
+        }
+    }
+
+    /// Share of the tokens which are trivia in percent, 0 without tokens
+    #[must_use]
+    pub fn trivia_percent(&self) -> f64 {
+        if self.tokens == 0 {
+            return 0.0;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let percent = self.trivia as f64 / self.tokens as f64 * 100.0;
+        percent
+    }
+
+    /// The `n` most frequent kinds, the first to appear on ties
+    #[must_use]
+    pub fn top_kinds(&self, n: usize) -> Vec<(Kind, usize)> {
+        let mut kinds = self.kinds.clone();
+        // stable, so ties stay in order of appearance
+        kinds.sort_by(|(_, a), (_, b)| b.cmp(a));
+        kinds.truncate(n);
+        kinds
+    }
+}
+
+/// Number of tokens a parser consumes, see [`crate::Token::is_significant`]
+#[must_use]
+pub fn count_significant_tokens(source: &str) -> usize {
+    Lexer::new(source).filter(Token::is_significant).count()
+}
+
+impl ObfuscationHints {
+    fn record_identifier(&mut self, name: &str) {
+        self.identifiers += 1;
+        if name.chars().nth(1).is_none() {
+            self.single_char_identifiers += 1;
+        }
+        if Self::is_hex_like(name) {
+            self.hex_identifiers += 1;
+        }
+    }
+
+    /// `_0x1a2b`: optional `_`/`$` prefix, `0x`, then hex digits
+    fn is_hex_like(name: &str) -> bool {
+        name.trim_start_matches(|c| c == '_' || c == '$')
+            .strip_prefix("0x")
+            .map_or(false, |digits| {
+                !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_hexdigit())
+            })
+    }
+}
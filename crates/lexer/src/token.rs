@@ -1,11 +1,15 @@
 //! Token
 
-use std::ops::Range;
+use std::{fmt, ops::Range};
 
-use crate::kind::Kind;
+use crate::asi::is_line_break;
+use crate::kind::{Kind, Quote, TokenGroup};
+use crate::lexer::Lexer;
+use crate::options::LexerOptions;
+use crate::span::Span;
 
 #[allow(dead_code)]
-#[derive(Debug, PartialEq)]
+#[derive(PartialEq)]
 pub struct Token {
     /// Token Kind
     kind: Kind,
@@ -15,12 +19,28 @@ pub struct Token {
 
     /// Length of token
     len: usize,
+
+    /// Opening quote of an `Unknown` token which is an unterminated string
+    unterminated_quote: Option<Quote>,
 }
 
 impl Token {
     #[must_use]
     pub const fn new(kind: Kind, offset: usize, len: usize) -> Self {
-        Self { kind, offset, len }
+        Self {
+            kind,
+            offset,
+            len,
+            unterminated_quote: None,
+        }
+    }
+
+    /// This `Unknown` token as an unterminated string opened by `quote`
+    pub(crate) const fn with_unterminated_quote(self, quote: Quote) -> Self {
+        Self {
+            unterminated_quote: Some(quote),
+            ..self
+        }
     }
 
     #[must_use]
@@ -43,8 +63,98 @@ impl Token {
         self.offset..(self.offset + self.len)
     }
 
+    #[must_use]
+    pub const fn span(&self) -> Span {
+        Span::new(self.offset, self.offset + self.len)
+    }
+
+    /// Shift the offset by `delta`, e.g. to rebase a token of a fragment lexed in isolation
+    /// onto the file it is spliced into. `None` if the offset would underflow or overflow.
+    #[must_use]
+    pub const fn with_offset_delta(self, delta: isize) -> Option<Self> {
+        let offset = if delta < 0 {
+            self.offset.checked_sub(delta.unsigned_abs())
+        } else {
+            self.offset.checked_add(delta.unsigned_abs())
+        };
+        match offset {
+            Some(offset) if offset.checked_add(self.len).is_some() => Some(Self { offset, ..self }),
+            _ => None,
+        }
+    }
+
+    /// Lex the text of this token in `source` on its own with `options`, e.g. the inside of a
+    /// template. The tokens are offset into `source`, `EOF` at the end of this token included.
+    /// # Panics
+    /// This token is out of the bounds of `source`
+    #[must_use]
+    pub fn relex(&self, source: &str, options: LexerOptions) -> Vec<Self> {
+        Lexer::with_options(&source[self.range()], options)
+            .map(|token| Self {
+                offset: self.offset + token.offset,
+                ..token
+            })
+            .collect()
+    }
+
     #[must_use]
     pub fn is_unknown(&self) -> bool {
         self.kind == Kind::Unknown
     }
+
+    /// See [`Kind::is_trivia`]
+    #[must_use]
+    pub const fn is_trivia(&self) -> bool {
+        self.kind.is_trivia()
+    }
+
+    /// The quote of a string literal, `None` for any other token.
+    /// An unterminated string is an `Unknown` token, its error is `UnterminatedString`,
+    /// with the quote it opens with.
+    #[must_use]
+    pub const fn quote(&self) -> Option<Quote> {
+        match self.kind {
+            Kind::Str(quote) => Some(quote),
+            _ => self.unterminated_quote,
+        }
+    }
+
+    /// See [`Kind::group`]
+    #[must_use]
+    pub const fn group(&self) -> TokenGroup {
+        self.kind.group()
+    }
+
+    /// Tokens a parser consumes, neither trivia nor `EOF`.
+    /// `EOF` marks the end of the token stream, it is not part of the source.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        !self.is_trivia() && self.kind != Kind::EOF
+    }
+}
+
+/// The quote of an unterminated string only, as the other tokens have none
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("Token");
+        debug
+            .field("kind", &self.kind)
+            .field("offset", &self.offset)
+            .field("len", &self.len);
+        if let Some(quote) = self.unterminated_quote {
+            debug.field("unterminated_quote", &quote);
+        }
+        debug.finish()
+    }
+}
+
+/// Whether a line break separates `tokens[index]` from the previous significant token.
+/// A `++` or `--` after a line break is not postfix, it is a prefix operator of the next statement.
+#[must_use]
+pub fn line_break_before(source: &str, tokens: &[Token], index: usize) -> bool {
+    tokens[..index]
+        .iter()
+        .rev()
+        .take_while(|token| token.is_trivia())
+        .any(|token| is_line_break(source, token))
 }
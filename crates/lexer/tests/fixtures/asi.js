@@ -0,0 +1,7 @@
+return
+x
+a
+++b
+throw e
+break
+label: for (;;) continue label
@@ -0,0 +1,8 @@
+/**/a/**/;
+/* // not a line comment */ b;
+// /* not a block comment
+c; /* multi
+line */ d;
+e /*
+*/ ++f;
+/*** stars ***/
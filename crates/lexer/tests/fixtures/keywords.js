@@ -0,0 +1,5 @@
+class A extends B { static get if() { return this.else; } }
+await x; yield y;
+a.class = b.new = c.typeof;
+let of = async => of;
+null; true; false; undefined;
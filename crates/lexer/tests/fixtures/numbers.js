@@ -0,0 +1,6 @@
+0 123 1_000_000 0.5 .5 5. 1e10 1.5e-3 2E+8;
+0x1F 0XaB_cd 0o17 0O7 0b1010 0B1_0;
+10n 0x1Fn 0b1n 0o7n;
+007 089 0.0;
+1..toString();
+1e;
@@ -0,0 +1,8 @@
+a?.b ?? c;
+a?.[0]?.(1);
+x ??= y ||= z &&= w;
+a ** b **= c;
+a >>>= b >>> c >> d << e;
+#priv in obj;
+a => ({ ...b });
+c ? .5 : d;
@@ -0,0 +1,6 @@
+a?.b??c?.[d]?.(e);
+x>>>=y**=z<<=1;a&&=b||=c??=d;
+f=(...g)=>{h:!i===~j!==k};
+l+++m---n;o**-p;q?r:s>=t<=u;
+[v,w]={...x}.y,z%=2^3|4&5;
+#priv in this;
@@ -0,0 +1,5 @@
+/a/dgimsuy;
+/a/gg;
+/a/uv;
+/a/g;
+/a/x;
@@ -0,0 +1,7 @@
+a = b / c / d;
+x = /[/]/g.test(y) / 2;
+if (/re/.test(s)) /x/i.exec(t);
+z = (1) / 2 / /y/m;
+f(/a\/b/, /[\]/]+/u, /\\/);
+g = h++ / 2, i = [] / /j/;
+} /k/
@@ -0,0 +1,9 @@
+'single \' quote';
+"double \" quote";
+"\\";
+"\n\r\t\b\f\v\0";
+"\x41A\u{1F600}";
+'line \
+continuation';
+"\d\e\q";
+"   ";
@@ -0,0 +1,8 @@
+`plain`;
+`a${b}c${d}e`;
+`a${ `b${c}` }d`;
+tag`x\`y\${z}`;
+`line
+break`;
+`\u{41}\x41\
+continued`;
@@ -0,0 +1,6 @@
+const café = 1;
+let π = 3.14;
+var \u0061b = x\u{62};
+ಠ_ಠ = 中文 + Ωmega;
+$ = _;
+a\u200Db;
@@ -0,0 +1,3 @@
+a = "ok";
+b = `ok`;
+c = "unterminated
@@ -0,0 +1,161 @@
+//! Random streams of valid token texts, rendered with the separators which keep adjacent
+//! tokens from merging, so that lexing the rendering gives back exactly the generated kinds.
+//! Shared by the `generated_tokens` test and the `tokens` fuzz target.
+
+use lexer::{Kind, Number, Quote, TokenGroup};
+
+/// A generated token, its kind and source text
+pub type Piece = (Kind, &'static str);
+
+/// Kinds whose `as_str` is a description rather than their source text
+const DESCRIPTIVE: [Kind; 12] = [
+    Kind::Unknown,
+    Kind::EOF,
+    Kind::WhiteSpace,
+    Kind::LineTerminator,
+    Kind::Comment,
+    Kind::MultilineComment,
+    Kind::Ident,
+    Kind::Undefined,
+    Kind::Regex,
+    Kind::Template,
+    Kind::VirtualSemicolon,
+    // experimental
+    Kind::PipeGt,
+];
+
+/// Tokens without a fixed source text, each with a few of its texts
+const LITERALS: &[Piece] = &[
+    (Kind::Ident, "a"),
+    (Kind::Ident, "_b1"),
+    (Kind::Ident, "$"),
+    (Kind::Number(Number::Decimal), "0"),
+    (Kind::Number(Number::Decimal), "1_000"),
+    (Kind::Number(Number::Decimal), "1e-3"),
+    (Kind::Number(Number::Float), "1.5"),
+    (Kind::Number(Number::Binary), "0b10"),
+    (Kind::Number(Number::Octal), "0o17"),
+    (Kind::Number(Number::Hex), "0xFF"),
+    (Kind::Number(Number::BigInt), "10n"),
+    (Kind::Str(Quote::Single), "'a'"),
+    (Kind::Str(Quote::Double), "\"b\\\"c\""),
+    (Kind::Template, "`t`"),
+    (Kind::Template, "`a${b}c`"),
+    // ending with flags, so that the word rule of `needs_separator` applies
+    (Kind::Regex, "/a/g"),
+    (Kind::Regex, "/[/]+/u"),
+    (Kind::WhiteSpace, " "),
+    (Kind::WhiteSpace, "\t"),
+    (Kind::LineTerminator, "\n"),
+    (Kind::Comment, "// c"),
+    (Kind::MultilineComment, "/* c */"),
+    (Kind::MultilineComment, "/*\n*/"),
+];
+
+/// Keywords, punctuators and [`LITERALS`], future reserved words are identifiers
+fn pieces() -> Vec<Piece> {
+    Kind::fixed_variants()
+        .iter()
+        .filter(|kind| !DESCRIPTIVE.contains(kind))
+        .map(|kind| {
+            let expected = if kind.is_future_reserved() {
+                Kind::Ident
+            } else {
+                *kind
+            };
+            (expected, kind.as_str())
+        })
+        .chain(LITERALS.iter().copied())
+        .collect()
+}
+
+/// Whether `/` would start a regex after the tokens so far, like the lexer's heuristic.
+/// `None` after a comment, where the heuristic is not meaningful.
+#[derive(Clone, Copy)]
+struct Slash(Option<bool>);
+
+impl Slash {
+    fn update(&mut self, kind: Kind) {
+        match kind {
+            Kind::WhiteSpace | Kind::LineTerminator => {}
+            Kind::Comment | Kind::MultilineComment => self.0 = None,
+            _ => self.0 = Some(kind.at_expr()),
+        }
+    }
+}
+
+/// Whether `next` may follow `prev`, which no separator could keep apart
+fn may_follow(prev: Option<Kind>, slash: Slash, next: Kind) -> bool {
+    match (prev, next) {
+        (Some(Kind::WhiteSpace), Kind::WhiteSpace)
+        | (Some(Kind::LineTerminator), Kind::LineTerminator) => false,
+        // the comment would end at the next line terminator
+        (Some(Kind::Comment), next) => next == Kind::LineTerminator,
+        (_, Kind::Regex) => slash.0 == Some(true),
+        (_, Kind::Slash) => slash.0 == Some(false),
+        _ => true,
+    }
+}
+
+/// Up to `attempts` pieces, `choose(n)` picks one of `n` candidates, `None` ends the stream.
+/// Candidates which may not follow the previous piece are skipped.
+pub fn generate(mut choose: impl FnMut(usize) -> Option<usize>, attempts: usize) -> Vec<Piece> {
+    let pieces = pieces();
+    let mut stream: Vec<Piece> = vec![];
+    let mut slash = Slash(Some(true));
+    for _ in 0..attempts {
+        let piece = match choose(pieces.len()) {
+            Some(index) => pieces[index],
+            None => break,
+        };
+        if may_follow(stream.last().map(|(kind, _)| *kind), slash, piece.0) {
+            slash.update(piece.0);
+            stream.push(piece);
+        }
+    }
+    stream
+}
+
+fn is_word(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Whether `a` and `b` would lex differently next to each other
+fn needs_separator(a: Piece, b: Piece, punctuators: &[&str]) -> bool {
+    let (kind, a) = a;
+    let b = b.1;
+    let (last, first) = match (a.chars().last(), b.chars().next()) {
+        (Some(last), Some(first)) => (last, first),
+        _ => return false,
+    };
+    let joined = format!("{a}{b}");
+    (is_word(last) && is_word(first))
+        || (matches!(kind, Kind::Number(_)) && first == '.')
+        || (last == '.' && first.is_ascii_digit())
+        || (last == '/' && (first == '/' || first == '*'))
+        // a longer punctuator starting at `a`, e.g. `=` `=` or `.` `.` of `...`
+        || punctuators.iter().any(|p| {
+            p.len() > a.len() && (p.starts_with(joined.as_str()) || joined.starts_with(p))
+        })
+}
+
+/// The source of `stream` and its expected kinds, with a space between pieces which would merge
+pub fn render(stream: &[Piece]) -> (String, Vec<Kind>) {
+    let punctuators = Kind::fixed_variants()
+        .iter()
+        .filter(|kind| kind.group() == TokenGroup::Punctuator && !DESCRIPTIVE.contains(kind))
+        .map(Kind::as_str)
+        .collect::<Vec<_>>();
+    let mut source = String::new();
+    let mut kinds = vec![];
+    for (i, piece) in stream.iter().enumerate() {
+        if i > 0 && needs_separator(stream[i - 1], *piece, &punctuators) {
+            source.push(' ');
+            kinds.push(Kind::WhiteSpace);
+        }
+        source.push_str(piece.1);
+        kinds.push(piece.0);
+    }
+    kinds.push(Kind::EOF);
+    (source, kinds)
+}
@@ -1,17 +1,137 @@
+mod generator;
+mod properties;
+mod unicode_id;
+
 #[cfg(test)]
-use lexer::{Kind, Lexer};
+use lexer::{
+    attach_comments, check_u32_offsets, classify_comment, cook_string, count_significant_tokens,
+    dump, estimate_token_count, is_directive_prologue_candidate, lex_comment_body,
+    lex_first_statement, line_break_before, sort_errors, spawn_lexer, token_text, tokenize_lossy,
+    tokenize_soa, try_tokenize_soa, Attached, CommentKind, CommentPosition, DumpOptions,
+    EcmaVersion, ErrorKind, Kind, Lexer, LexerError, LexerOptions, ObfuscationHints, Quote, Span,
+    Statistics, Token, TokenBuffer, TokenGroup, MAX_U32_SOURCE_LEN, REDACTED_COMMENT,
+};
 
 #[allow(clippy::enum_glob_use)]
 use lexer::Kind::*;
 #[allow(clippy::enum_glob_use)]
 use lexer::Number::*;
+#[allow(clippy::enum_glob_use)]
+use lexer::Quote::*;
+use std::{
+    collections::HashSet,
+    fs,
+    ops::{ControlFlow, Range},
+    path::{Path, PathBuf},
+};
 
+/// Every punctuator and its source
+const PUNCTUATORS: [(Kind, &str); 57] = [
+    (Amp, "&"),
+    (Amp2, "&&"),
+    (Amp2Eq, "&&="),
+    (AmpEq, "&="),
+    (Bang, "!"),
+    (Caret, "^"),
+    (CaretEq, "^="),
+    (Colon, ":"),
+    (Comma, ","),
+    (Dot, "."),
+    (Dot3, "..."),
+    (Eq, "="),
+    (Eq2, "=="),
+    (Eq3, "==="),
+    (FatArrow, "=>"),
+    (GtEq, ">="),
+    (LAngle, "<"),
+    (LBrack, "["),
+    (LCurly, "{"),
+    (LParen, "("),
+    (LtEq, "<="),
+    (Minus, "-"),
+    (Minus2, "--"),
+    (MinusEq, "-="),
+    (Neq, "!="),
+    (Neq2, "!=="),
+    (Percent, "%"),
+    (PercentEq, "%="),
+    (Pipe, "|"),
+    (Pipe2, "||"),
+    (Pipe2Eq, "||="),
+    (PipeEq, "|="),
+    (Plus, "+"),
+    (Plus2, "++"),
+    (PlusEq, "+="),
+    (Question, "?"),
+    (Question2, "??"),
+    (Question2Eq, "??="),
+    (QuestionDot, "?."),
+    (RAngle, ">"),
+    (RBrack, "]"),
+    (RCurly, "}"),
+    (RParen, ")"),
+    (Semicolon, ";"),
+    (ShiftLeft, "<<"),
+    (ShiftLeftEq, "<<="),
+    (ShiftRight, ">>"),
+    (ShiftRight3, ">>>"),
+    (ShiftRight3Eq, ">>>="),
+    (ShiftRightEq, ">>="),
+    (Slash, "/"),
+    (SlashEq, "/="),
+    (Star, "*"),
+    (Star2, "**"),
+    (Star2Eq, "**="),
+    (StarEq, "*="),
+    (Tilde, "~"),
+];
+
+/// Lex `input` as a single token of `kind`, see [`assert_lex`] for a sequence of tokens
 fn test(kind: Kind, input: &str) {
-    let tokens = Lexer::new(input).into_iter().collect::<Vec<_>>();
-    assert_eq!(tokens.len() - 1, 1, "{kind:?} {input} {tokens:?}");
-    let token = tokens.first().unwrap();
-    assert_eq!(token.kind(), &kind, "{kind:?} {input} {tokens:?}");
-    assert_eq!(token.range(), 0..input.len(), "{kind:?} {input} {tokens:?}");
+    assert_lex(input, true, &[(kind, input)]);
+}
+
+/// The kind of the string literal `s`, by its first quote
+fn string(s: &str) -> Kind {
+    Str(Quote::from_byte(s.as_bytes()[0]).unwrap())
+}
+
+/// Lex `source` and assert the kind and text of each token before `EOF`, skipping trivia
+/// unless `trivia`. The tokens must tile the source, failures print the whole token stream.
+fn assert_lex(source: &str, trivia: bool, expected: &[(Kind, &str)]) {
+    let stream = dump(source, DumpOptions::default());
+    let tokens = Lexer::new(source).collect::<Vec<_>>();
+    let mut end = 0;
+    for token in &tokens {
+        assert_eq!(
+            token.range().start,
+            end,
+            "{source:?} is not tiled\n{stream}"
+        );
+        end = token.range().end;
+    }
+    let actual = tokens
+        .iter()
+        .filter(|token| token.kind() != &EOF && (trivia || !token.is_trivia()))
+        .map(|token| (*token.kind(), &source[token.range()]))
+        .collect::<Vec<_>>();
+    assert_eq!(actual, expected, "{source:?}\n{stream}");
+}
+
+/// `assert_lex!("a+b", [Ident "a", Plus "+", Ident "b"])` without trivia,
+/// `assert_lex!("a b", trivia [Ident "a", WhiteSpace " ", Ident "b"])` with trivia
+macro_rules! assert_lex {
+    ($source:expr, [$($kind:ident $(($inner:ident))? $text:literal),* $(,)?]) => {
+        assert_lex($source, false, &[$(($kind $(($inner))?, $text)),*])
+    };
+    ($source:expr, trivia [$($kind:ident $(($inner:ident))? $text:literal),* $(,)?]) => {
+        assert_lex($source, true, &[$(($kind $(($inner))?, $text)),*])
+    };
+}
+
+/// `tests/fixtures/<name>.js` inputs, each with its snapshot `tests/snapshots/lib__<name>.snap`
+fn fixtures() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
 }
 
 #[test]
@@ -40,6 +160,38 @@ fn whitespace() {
     });
 }
 
+#[test]
+fn vertical_tab_and_form_feed() {
+    // whitespace between tokens, with other whitespace in a single token
+    assert_lex!("a\u{c}b", trivia [Ident "a", WhiteSpace "\u{c}", Ident "b"]);
+    assert_lex!("a \u{b}\u{c}\tb", trivia [Ident "a", WhiteSpace " \u{b}\u{c}\t", Ident "b"]);
+    // raw in a string, and escaped
+    assert_lex!("'\u{b}\u{c}'", [Str(Single) "'\u{b}\u{c}'"]);
+    assert_eq!(cook_string("'\u{b}\u{c}'").as_deref(), Some("\u{b}\u{c}"));
+    assert_eq!(cook_string("\"\\v\\f\"").as_deref(), Some("\u{b}\u{c}"));
+}
+
+#[test]
+fn cooked_strings() {
+    for (raw, cooked) in [
+        ("''", ""),
+        ("'a\\'b'", "a'b"),
+        ("\"\\b\\t\\n\\r\\0\\\\\"", "\u{8}\t\n\r\0\\"),
+        ("'\\x41\\u0042\\u{1F600}'", "AB\u{1F600}"),
+        // a surrogate pair is a code point
+        ("'\\uD83D\\uDE00'", "\u{1F600}"),
+        // line continuations
+        ("'a\\\nb\\\r\nc\\\u{2028}d'", "abcd"),
+        // legacy octal and non-escape characters
+        ("'\\101\\0\\8\\d\\400'", "A\u{0}8d\u{20}0"),
+    ] {
+        assert_eq!(cook_string(raw).as_deref(), Some(cooked), "{raw}");
+    }
+    for raw in ["'\\x4'", "'\\u{110000}'", "'\\u{}'", "'\\uD83D'", "'"] {
+        assert_eq!(cook_string(raw), None, "{raw}");
+    }
+}
+
 #[test]
 fn line_terminator() {
     [
@@ -66,6 +218,9 @@ fn multi_line_comment() {
     [
         "/* multi line comment */",
         "/* multi * \n / line \n comment */",
+        // unterminated
+        "/*",
+        "/* multi line comment *",
     ]
     .into_iter()
     .for_each(|s| {
@@ -111,6 +266,8 @@ fn reserved_word() {
         (While, "while"),
         (With, "with"),
         (Yield, "yield"),
+        (Let, "let"),
+        (Static, "static"),
         (Null, "null"),
         (True, "true"),
         (False, "false"),
@@ -121,6 +278,129 @@ fn reserved_word() {
     });
 }
 
+#[test]
+fn future_reserved_word() {
+    let strict = LexerOptions {
+        strict: true,
+        ..LexerOptions::default()
+    };
+    for (kind, s) in [
+        (Implements, "implements"),
+        (Interface, "interface"),
+        (Package, "package"),
+        (Private, "private"),
+        (Protected, "protected"),
+        (Public, "public"),
+    ] {
+        assert!(kind.is_future_reserved());
+        test(Ident, s);
+        let tokens = Lexer::with_options(s, strict).collect::<Vec<_>>();
+        assert_eq!(
+            tokens,
+            [Token::new(kind, 0, s.len()), Token::new(EOF, s.len(), 1)]
+        );
+    }
+    assert_eq!(Kind::keyword_from_str("public"), Some(Public));
+    assert!(!Let.is_future_reserved());
+    assert_eq!(
+        Lexer::with_options("let", strict).next().unwrap().kind(),
+        &Let
+    );
+}
+
+#[test]
+fn module_code() {
+    // `await` is a keyword in both, a parser reports it as an identifier in a module
+    let module = LexerOptions {
+        module: true,
+        ..LexerOptions::new()
+    };
+    for options in [LexerOptions::new(), module] {
+        let kinds = Lexer::with_options("await x", options)
+            .map(|token| *token.kind())
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, [Await, WhiteSpace, Ident, EOF]);
+    }
+    assert!(Await.is_reserved_in_module());
+    assert!(Let.is_reserved_in_module() && Yield.is_reserved_in_module());
+    assert!(!Import.is_reserved_in_module() && !Ident.is_reserved_in_module());
+    // module code is strict mode code
+    assert_eq!(
+        Lexer::with_options("public", module).next().unwrap().kind(),
+        &Public
+    );
+    for kind in Kind::fixed_variants() {
+        assert!(!(kind.is_always_reserved() && kind.is_reserved_in_module()));
+    }
+}
+
+#[test]
+fn reserved_word_context() {
+    assert!(!Await.is_always_reserved());
+    assert!(!Await.is_reserved_in_strict_only());
+    assert!(Function.is_always_reserved());
+    assert!(!Function.is_reserved_in_strict_only());
+    assert!(Let.is_reserved_in_strict_only());
+    assert!(Yield.is_reserved_in_strict_only());
+    assert!(!Ident.is_always_reserved());
+    for kind in Kind::fixed_variants() {
+        assert!(!(kind.is_always_reserved() && kind.is_reserved_in_strict_only()));
+    }
+}
+
+#[test]
+fn kind_from_str() {
+    for kind in [
+        Ident,
+        EOF,
+        Await,
+        Dot3,
+        ShiftRight3Eq,
+        VirtualSemicolon,
+        Number(Hex),
+    ] {
+        assert_eq!(format!("{:?}", kind).parse(), Ok(kind));
+    }
+    for kind in Kind::ALL {
+        assert_eq!(format!("{:?}", kind).parse(), Ok(*kind));
+    }
+    assert_eq!("Number::Float".parse(), Ok(Number(Float)));
+    assert_eq!("BigInt".parse(), Ok(Number(BigInt)));
+    assert_eq!(
+        "Number".parse::<Kind>(),
+        Err("invalid token kind `Number`".to_string())
+    );
+    assert_eq!(
+        "Number::Ident".parse::<Kind>(),
+        Err("invalid token kind `Number::Ident`".to_string())
+    );
+    assert_eq!(
+        "ident".parse::<Kind>(),
+        Err("invalid token kind `ident`".to_string())
+    );
+}
+
+#[test]
+fn restricted_production() {
+    assert!(Return.starts_restricted_production());
+    assert!(Throw.starts_restricted_production());
+    assert!(Plus2.starts_restricted_production());
+    assert!(!If.starts_restricted_production());
+    assert!(!Plus.starts_restricted_production());
+
+    let postfix_after_line_break = |source| {
+        let tokens = Lexer::new(source).collect::<Vec<_>>();
+        let index = tokens.iter().position(|token| token.kind() == &Plus2);
+        line_break_before(source, &tokens, index.unwrap())
+    };
+    assert!(!postfix_after_line_break("a++\nb"));
+    assert!(!postfix_after_line_break("a /* c */++"));
+    assert!(postfix_after_line_break("a\n++b"));
+    assert!(postfix_after_line_break("a /* \n */ ++b"));
+    assert!(postfix_after_line_break("a // c\n++b"));
+    assert!(!postfix_after_line_break("++a"));
+}
+
 #[test]
 fn identifier() {
     [
@@ -135,83 +415,150 @@ fn identifier() {
         "x\u{61}",
         "x‍",
         "x‌",
+        r#"a\u200db"#,
+        r#"a\u{200c}b"#,
+        r#"\u0024"#,
     ]
     .into_iter()
     .for_each(|s| test(Ident, s));
 }
 
+#[test]
+fn identifier_ascii_run() {
+    // from a run of ASCII bytes to the chars beyond ASCII and the escapes, and back
+    assert_lex!("abc\\u0064ef", [Ident "abc\\u0064ef"]);
+    assert_lex!("abcé1\\u{65}fg2", [Ident "abcé1\\u{65}fg2"]);
+    assert_lex!("a·b‍c", [Ident "a·b‍c"]);
+    assert_lex!("$_09azAZ", [Ident "$_09azAZ"]);
+    // neither an escape nor an identifier part
+    assert_lex!("ab\\x", [Ident "ab", Unknown "\\", Ident "x"]);
+    assert_lex!("ab\u{a0}c", [Ident "ab", Ident "c"]);
+    assert_lex!("ab-c", [Ident "ab", Minus "-", Ident "c"]);
+    assert_lex!("ab@", [Ident "ab", Unknown "@"]);
+    assert_lex!("ab\u{7f}", [Ident "ab", Unknown "\u{7f}"]);
+    assert_lex!("ab€", [Ident "ab", Unknown "€"]);
+}
+
+/// A char which starts no token is a whole `Unknown` token, the cursor was left within a char
+/// beyond ASCII before. Also run with `cargo miri test -p lexer --test lib unknown_char`.
+#[test]
+fn unknown_char() {
+    assert_lex!("a € b", [Ident "a", Unknown "€", Ident "b"]);
+    assert_lex!("€€", [Unknown "€", Unknown "€"]);
+    assert_lex!("\u{200d}x", [Unknown "\u{200d}", Ident "x"]);
+    assert_lex!("😀's'", [Unknown "😀", Str(Single) "'s'"]);
+    assert_lex!("a\\€", [Ident "a", Unknown "\\", Unknown "€"]);
+    let options = LexerOptions {
+        virtual_semicolons: true,
+        ..LexerOptions::new()
+    };
+    let unknown = Lexer::with_options("a\n€\nb", options)
+        .filter(Token::is_unknown)
+        .map(|token| token.range())
+        .collect::<Vec<_>>();
+    assert_eq!(unknown, [2..5]);
+    let mut lexer = Lexer::new("€ //é\n'€");
+    assert_eq!(lexer.by_ref().count(), 6);
+    let errors = lexer
+        .errors()
+        .iter()
+        .map(|error| (error.kind(), error.span().clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        errors,
+        [
+            (ErrorKind::InvalidIdentifier, 0..3),
+            (ErrorKind::UnterminatedString, 9..13),
+        ]
+    );
+}
+
+#[test]
+fn invalid_identifier_escape() {
+    // a space is not an identifier part, an emoji is not an identifier start,
+    // `\u0031` is a digit and `\uD800` a lone surrogate
+    for s in [r#"a\u0020b"#, r#"\u{1F600}x"#, r#"\u0031"#, r#"a\uD800"#] {
+        let mut lexer = Lexer::new(s);
+        let first = lexer.next().unwrap();
+        assert_eq!(first.kind(), &Unknown, "{s}");
+        lexer.by_ref().count();
+        assert_eq!(
+            lexer.errors()[0].kind(),
+            ErrorKind::InvalidIdentifier,
+            "{s}"
+        );
+    }
+}
+
 #[test]
 fn punctuator() {
-    [
-        (Amp, "&"),
-        (Amp2, "&&"),
-        (Amp2Eq, "&&="),
-        (AmpEq, "&="),
-        (Bang, "!"),
-        (Caret, "^"),
-        (CaretEq, "^="),
-        (Colon, ":"),
-        (Comma, ","),
-        (Dot, "."),
-        (Dot3, "..."),
-        (Eq, "="),
-        (Eq2, "=="),
-        (Eq3, "==="),
-        (FatArrow, "=>"),
-        (GtEq, ">="),
-        (LAngle, "<"),
-        (LBrack, "["),
-        (LCurly, "{"),
-        (LParen, "("),
-        (LtEq, "<="),
-        (Minus, "-"),
-        (Minus2, "--"),
-        (MinusEq, "-="),
-        (Neq, "!="),
-        (Neq2, "!=="),
-        (Percent, "%"),
-        (PercentEq, "%="),
-        (Pipe, "|"),
-        (Pipe2, "||"),
-        (Pipe2Eq, "||="),
-        (PipeEq, "|="),
-        (Plus, "+"),
-        (Plus2, "++"),
-        (PlusEq, "+="),
-        (Question, "?"),
-        (Question2, "??"),
-        (Question2Eq, "??="),
-        (QuestionDot, "?."),
-        (RAngle, ">"),
-        (RBrack, "]"),
-        (RCurly, "}"),
-        (RParen, ")"),
-        (Semicolon, ";"),
-        (ShiftLeft, "<<"),
-        (ShiftLeftEq, "<<="),
-        (ShiftRight, ">>"),
-        (ShiftRight3, ">>>"),
-        (ShiftRight3Eq, ">>>="),
-        (ShiftRightEq, ">>="),
-        (Slash, "/"),
-        (SlashEq, "/="),
-        (Star, "*"),
-        (Star2, "**"),
-        (Star2Eq, "**="),
-        (StarEq, "*="),
-        (Tilde, "~"),
-    ]
-    .into_iter()
-    .for_each(|(kind, s)| {
+    for (kind, s) in PUNCTUATORS {
         test(kind, s);
-    });
+    }
+}
+
+/// The longest punctuator at the start of each remaining input, like the punctuator readers
+fn maximal_munch(source: &str, options: LexerOptions) -> Vec<(Kind, Range<usize>)> {
+    let available = |kind: &Kind| match kind {
+        Amp2Eq | Pipe2Eq | Question2Eq => options.ecma_version >= EcmaVersion::ES2021,
+        _ => true,
+    };
+    let mut tokens = vec![];
+    let mut offset = 0;
+    while offset < source.len() {
+        let (kind, len) = PUNCTUATORS
+            .iter()
+            .chain(options.experimental.then(|| &(PipeGt, "|>")))
+            .filter(|(kind, s)| available(kind) && source[offset..].starts_with(s))
+            .map(|(kind, s)| (*kind, s.len()))
+            .max_by_key(|(_, len)| *len)
+            .unwrap();
+        tokens.push((kind, offset..offset + len));
+        offset += len;
+    }
+    tokens
+}
+
+/// Every input of up to four punctuator characters, with each option affecting the readers
+#[test]
+fn punctuator_readers() {
+    const ALPHABET: &[u8] = b"<>=!+-^%*&|?.";
+    let options = [
+        LexerOptions::default(),
+        LexerOptions {
+            experimental: true,
+            ..LexerOptions::default()
+        },
+        LexerOptions {
+            ecma_version: EcmaVersion::ES2020,
+            ..LexerOptions::default()
+        },
+    ];
+    for len in 1..=4_u32 {
+        for mut n in 0..ALPHABET.len().pow(len) {
+            let source = (0..len)
+                .map(|_| {
+                    let c = ALPHABET[n % ALPHABET.len()];
+                    n /= ALPHABET.len();
+                    char::from(c)
+                })
+                .collect::<String>();
+            for options in options {
+                let tokens = Lexer::with_options(&source, options)
+                    .filter(|token| token.kind() != &EOF)
+                    .map(|token| (*token.kind(), token.range()))
+                    .collect::<Vec<_>>();
+                assert_eq!(tokens, maximal_munch(&source, options), "{source}");
+            }
+        }
+    }
 }
 
 #[test]
 fn numeric_literal() {
     [
         "0", "0789", "0.", "0E-1", "0E+1", "0e-12", "0e+12", "0e0", "0e00", "0e01", "1e1", "1e23",
-        "123_456",
+        "123_456", "1e1_0", "1E+1_0",
     ]
     .into_iter()
     .for_each(|s| test(Number(Decimal), s));
@@ -227,9 +574,34 @@ fn numeric_literal() {
     ["0x1", "0X12", "0x1_2"]
         .into_iter()
         .for_each(|s| test(Number(Hex), s));
-    ["0.123", "1.0", "1.1", "1.0e1", "1.1_2"]
-        .into_iter()
-        .for_each(|s| test(Number(Float), s));
+    [
+        "0.123",
+        "1.0",
+        "1.1",
+        "1.0e1",
+        "1.1_2",
+        "1_000.5_5",
+        "1.5_5e1_0",
+    ]
+    .into_iter()
+    .for_each(|s| test(Number(Float), s));
+}
+
+#[test]
+fn numeric_separator() {
+    for source in [
+        "1._5", "1.5_", "1_.5", "1__0", "1_", "1_n", "1_e1", "1.5_e1", "1e_1", "1e1_", "1e1__0",
+        "1e+_1",
+    ] {
+        let mut lexer = Lexer::new(source);
+        let first = lexer.next().unwrap();
+        assert_eq!(first.kind(), &Unknown, "{source}");
+        assert_eq!(
+            lexer.errors()[0].kind(),
+            ErrorKind::InvalidNumber,
+            "{source}"
+        );
+    }
 }
 
 #[test]
@@ -248,7 +620,7 @@ fn string_literal() {
         r#""\\""#,
     ]
     .into_iter()
-    .for_each(|s| test(Str, s));
+    .for_each(|s| test(string(s), s));
 }
 
 #[test]
@@ -264,9 +636,1253 @@ fn regex() {
     .for_each(|s| test(Regex, s));
 }
 
+#[test]
+fn regex_flags() {
+    [r#"/a/g"#, r#"/a/gimsuy"#, r#"/a/\u0067"#, r#"/a/\u{67}i"#]
+        .into_iter()
+        .for_each(|s| test(Regex, s));
+
+    assert_lex!("/a/g\ni", trivia [Regex "/a/g", LineTerminator "\n", Ident "i"]);
+
+    // a single error token up to the end of the flags, the body is not lexed as code
+    for source in [
+        // not a flag letter, escaped or not
+        r#"/a/\u0041"#,
+        "/a/x",
+        "/a/A",
+        "/a/gé",
+        // escaped flags are decoded before checking for repeated flags and both `u` and `v`
+        r#"/a/gg"#,
+        r#"/a/uv"#,
+        r#"/a/\u0075\u0076"#,
+        r#"/a/u\u{76}"#,
+        r#"/a/\u0067g"#,
+        r#"/"/gg"#,
+    ] {
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer
+            .by_ref()
+            .map(|token| (*token.kind(), token.range()))
+            .collect::<Vec<_>>();
+        let end = source.len();
+        assert_eq!(tokens, [(Unknown, 0..end), (EOF, end..end + 1)], "{source}");
+        let error = &lexer.errors()[0];
+        assert_eq!(error.kind(), ErrorKind::InvalidRegex, "{source}");
+        assert_eq!(error.span(), &(0..end), "{source}");
+    }
+    assert_lex!("/a/gg;", trivia [Unknown "/a/gg", Semicolon ";"]);
+    [r#"/a/v"#, r#"/a/\u0076g"#, r#"/a/dgimsuy"#]
+        .into_iter()
+        .for_each(|s| test(Regex, s));
+}
+
 #[test]
 fn template_literal() {
-    [r#"``"#, r#"`123`"#, r#"`\`\r`"#, r#"`\\`"#]
+    [
+        r#"``"#,
+        r#"`123`"#,
+        r#"`\`\r`"#,
+        r#"`\\`"#,
+        r#"`\${x}`"#,
+        r#"`a\${b}`"#,
+        r#"`\``"#,
+        r#"`a\nb`"#,
+        "`a\\\nb`",
+        "`a\\\r\nb`",
+        "`a\\\u{2028}b`",
+        r#"`\é\"\'`"#,
+        r#"`\$`"#,
+        r#"`\u{41}`"#,
+        r#"`\x41`"#,
+        r#"`\\\``"#,
+    ]
+    .into_iter()
+    .for_each(|s| test(Template, s));
+}
+
+#[test]
+fn unterminated_at_eof() {
+    [
+        r#""a\"#, r#"'a\"#, r#""a"#, r#"`a\"#, r#"`a"#, r#"/a\"#, r#"/a"#, r#"/[a\"#,
+    ]
+    .into_iter()
+    .for_each(|s| test(Unknown, s));
+
+    assert_lex!(r#"a = "b\"#, trivia [
+        Ident "a", WhiteSpace " ", Eq "=", WhiteSpace " ", Unknown r#""b\"#,
+    ]);
+
+    // escapes in identifiers reaching EOF
+    for s in [r#"\u{61"#, r#"\u{"#, r#"a\u00"#] {
+        let tokens = Lexer::new(s).collect::<Vec<_>>();
+        assert_eq!(tokens.last().unwrap().kind(), &EOF, "{s}");
+    }
+}
+
+#[test]
+fn offset_delta() {
+    let token = Token::new(Ident, 2, 3);
+    let token = token.with_offset_delta(100).unwrap();
+    assert_eq!(token.range(), 102..105);
+    let token = token.with_offset_delta(-102).unwrap();
+    assert_eq!(token.range(), 0..3);
+    assert_eq!(token.with_offset_delta(-1), None);
+    assert_eq!(
+        Token::new(Ident, usize::MAX - 3, 3).with_offset_delta(1),
+        None
+    );
+}
+
+#[test]
+fn max_identifier_length() {
+    let options = LexerOptions {
+        max_identifier_length: Some(4),
+        ..LexerOptions::default()
+    };
+    let tokens = |source: &'static str, max| {
+        let options = LexerOptions {
+            max_identifier_length: Some(max),
+            ..options
+        };
+        Lexer::with_options(source, options)
+            .filter(|token| token.kind() != &EOF)
+            .map(|token| (*token.kind(), &source[token.range()]))
+            .collect::<Vec<_>>()
+    };
+    // the rest of the identifier is chunked too, not lexed as a keyword or a number
+    assert_eq!(
+        tokens("abcd abcdefghij", 4),
+        [
+            (Ident, "abcd"),
+            (WhiteSpace, " "),
+            (Unknown, "abcd"),
+            (Unknown, "efgh"),
+            (Unknown, "ij"),
+        ]
+    );
+    assert_eq!(
+        tokens("abcdewhile", 5),
+        [(Unknown, "abcde"), (Unknown, "while")]
+    );
+    assert_eq!(
+        tokens("abcd1234;", 4),
+        [(Unknown, "abcd"), (Unknown, "1234"), (Semicolon, ";")]
+    );
+    // `abc` once decoded
+    assert_eq!(tokens("a\\u0062c", 4), [(Ident, "a\\u0062c")]);
+    assert_eq!(
+        tokens("a\\u0062cde", 4),
+        [(Unknown, "a\\u0062cd"), (Unknown, "e")]
+    );
+    let mut lexer = Lexer::with_options("abcd1234", options);
+    lexer.by_ref().count();
+    let errors = lexer
+        .errors()
+        .iter()
+        .map(|error| (error.kind(), error.span().clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        errors,
+        [
+            (ErrorKind::InvalidIdentifier, 0..4),
+            (ErrorKind::InvalidIdentifier, 4..8)
+        ]
+    );
+    let kinds = Lexer::new("abcdefghij").map(|token| *token.kind());
+    assert_eq!(kinds.collect::<Vec<_>>(), [Ident, EOF]);
+}
+
+#[test]
+fn operator_tables() {
+    assert_eq!(Star2.binary_precedence(), Some(12));
+    assert!(Star.binary_precedence() > Plus.binary_precedence());
+    assert!(Plus.binary_precedence() > ShiftLeft.binary_precedence());
+    assert_eq!(In.binary_precedence(), LAngle.binary_precedence());
+    assert!(Amp2.binary_precedence() > Pipe2.binary_precedence());
+    assert!(Pipe2.binary_precedence() > Question2.binary_precedence());
+    assert_eq!(Bang.binary_precedence(), None);
+    assert_eq!(Eq.binary_precedence(), None);
+    for kind in Kind::fixed_variants() {
+        assert!(!(kind.is_assignment_operator() && kind.binary_precedence().is_some()));
+    }
+    assert!(Eq.is_assignment_operator() && Question2Eq.is_assignment_operator());
+    assert!(!Eq2.is_assignment_operator() && !FatArrow.is_assignment_operator());
+}
+
+#[test]
+fn can_start_expression() {
+    for kind in [
+        Ident,
+        LParen,
+        New,
+        LBrack,
+        LCurly,
+        Bang,
+        Typeof,
+        Function,
+        Class,
+        Str(Double),
+    ] {
+        assert!(kind.can_start_expression(), "{kind:?}");
+    }
+    for kind in [Semicolon, RParen, Comma, Eq, Star, Dot, In, Return, EOF] {
+        assert!(!kind.can_start_expression(), "{kind:?}");
+    }
+    // the token before an expression, not its first one
+    assert!(Eq.at_expr() && Return.at_expr());
+}
+
+#[test]
+fn arrow() {
+    // `=>` is never followed by `=` and `==` is never followed by `>`
+    test(FatArrow, "=>");
+    assert_lex!("=>=", [FatArrow "=>", Eq "="]);
+    assert_lex!("==>", [Eq2 "==", RAngle ">"]);
+    assert_lex!("===>", [Eq3 "===", RAngle ">"]);
+    assert!(FatArrow.is_arrow());
+    assert!(!Eq.is_arrow() && !RAngle.is_arrow() && !GtEq.is_arrow());
+}
+
+#[test]
+fn logical_assignment() {
+    let kinds = |source, ecma_version| {
+        let options = LexerOptions {
+            ecma_version,
+            ..LexerOptions::default()
+        };
+        Lexer::with_options(source, options)
+            .map(|token| *token.kind())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(kinds("&&=", EcmaVersion::ES2021), [Amp2Eq, EOF]);
+    assert_eq!(kinds("&&=", EcmaVersion::ES2020), [Amp2, Eq, EOF]);
+    assert_eq!(kinds("||=", EcmaVersion::ES2020), [Pipe2, Eq, EOF]);
+    assert_eq!(kinds("??=", EcmaVersion::ES2020), [Question2, Eq, EOF]);
+    assert_eq!(kinds("&=", EcmaVersion::ES2015), [AmpEq, EOF]);
+    assert_eq!(EcmaVersion::from_year(2021), Some(EcmaVersion::ES2021));
+    assert_eq!(EcmaVersion::from_year(2022), Some(EcmaVersion::LATEST));
+    assert_eq!(EcmaVersion::from_year(5), None);
+}
+
+#[test]
+fn errors() {
+    let source = "a @ 1e; /a/\\u0041 \"b";
+    let mut lexer = Lexer::new(source);
+    lexer.by_ref().count();
+    let errors = lexer
+        .errors_sorted()
+        .into_iter()
+        .map(|error| (error.kind(), &source[error.span().clone()]))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        errors,
+        [
+            (ErrorKind::UnexpectedCharacter, "@"),
+            (ErrorKind::InvalidNumber, "1"),
+            (ErrorKind::InvalidRegex, "/a/\\u0041"),
+            (ErrorKind::UnterminatedString, "\"b"),
+        ]
+    );
+
+    let errors = [
+        LexerError::new(ErrorKind::UnterminatedString, 10..12),
+        LexerError::new(ErrorKind::UnexpectedCharacter, 2..3),
+        LexerError::new(ErrorKind::UnterminatedString, 10..12),
+        LexerError::new(ErrorKind::InvalidNumber, 2..3),
+    ];
+    assert_eq!(sort_errors(&errors), [&errors[1], &errors[3], &errors[0]]);
+}
+
+#[test]
+fn comment_body() {
+    let comment = "// eslint-disable-next-line no-console, no-alert";
+    let words = lex_comment_body(comment)
+        .into_iter()
+        .filter(|token| token.kind() == &Ident)
+        .map(|token| &comment[token.range()])
+        .collect::<Vec<_>>();
+    assert_eq!(
+        words,
+        ["eslint-disable-next-line", "no-console", "no-alert"]
+    );
+
+    let comment = "/* @flow */";
+    let tokens = lex_comment_body(comment);
+    let kinds = tokens.iter().map(Token::kind).collect::<Vec<_>>();
+    assert_eq!(kinds, [&WhiteSpace, &Ident, &WhiteSpace]);
+    assert_eq!(&comment[tokens[1].range()], "@flow");
+
+    let comment = "//# sourceMappingURL=index.js.map";
+    let kinds = lex_comment_body(comment)
+        .into_iter()
+        .map(|token| *token.kind())
+        .collect::<Vec<_>>();
+    assert_eq!(kinds, [Hash, WhiteSpace, Ident, Eq, Ident]);
+}
+
+/// `(token, [(comment, position)])` for each token of `attach_comments(source)`, `EOF` as `""`
+fn attached(source: &str) -> Vec<(&str, Vec<(&str, CommentPosition)>)> {
+    attach_comments(source)
         .into_iter()
-        .for_each(|s| test(Template, s));
+        .map(|(token, comments)| {
+            let comments = comments
+                .into_iter()
+                .map(|Attached { comment, position }| (&source[comment.range()], position))
+                .collect();
+            let text = if token.kind() == &EOF {
+                ""
+            } else {
+                &source[token.range()]
+            };
+            (text, comments)
+        })
+        .collect()
+}
+
+#[test]
+fn attach_comments_position() {
+    use CommentPosition::{Leading, Trailing};
+    assert_eq!(
+        attached("x; // trailing"),
+        [
+            ("x", vec![]),
+            (";", vec![("// trailing", Trailing)]),
+            ("", vec![])
+        ]
+    );
+    assert_eq!(
+        attached("// leading\nx"),
+        [("x", vec![("// leading", Leading)]), ("", vec![])]
+    );
+    assert_eq!(
+        attached("a /* t */ /* t */\n\n/* l */ b /* t\n */ // l\nc"),
+        [
+            ("a", vec![("/* t */", Trailing), ("/* t */", Trailing)]),
+            ("b", vec![("/* l */", Leading), ("/* t\n */", Trailing)]),
+            ("c", vec![("// l", Leading)]),
+            ("", vec![]),
+        ]
+    );
+    // at the end, and in an empty source
+    assert_eq!(
+        attached("x\n// end"),
+        [("x", vec![]), ("", vec![("// end", Leading)])]
+    );
+    assert_eq!(attached("/**/"), [("", vec![("/**/", Leading)])]);
+}
+
+#[test]
+fn comment_kinds() {
+    for (source, kind) in [
+        ("/** @param {number} a */", CommentKind::JsDoc),
+        ("/**\n * @returns {void}\n */", CommentKind::JsDoc),
+        ("/*! license MIT */", CommentKind::Banner),
+        (
+            "// eslint-disable-next-line no-console",
+            CommentKind::Eslint,
+        ),
+        ("// eslint-disable-line", CommentKind::Eslint),
+        ("/* eslint-disable */", CommentKind::Eslint),
+        ("/* eslint-env node */", CommentKind::Eslint),
+        ("/* global a, b */", CommentKind::Eslint),
+        ("// @ts-ignore", CommentKind::TypeScript),
+        ("// @ts-expect-error: a reason", CommentKind::TypeScript),
+        (
+            "//# sourceMappingURL=index.js.map",
+            CommentKind::SourceMappingUrl,
+        ),
+        (
+            "//@ sourceMappingURL=index.js.map",
+            CommentKind::SourceMappingUrl,
+        ),
+        // empty, not JSDoc
+        ("/**/", CommentKind::Plain),
+        ("/***/", CommentKind::Plain),
+        ("/*** a ***/", CommentKind::Plain),
+        // only in a multi-line comment
+        ("// eslint is a linter", CommentKind::Plain),
+        ("// global state", CommentKind::Plain),
+        ("// see eslint-disable", CommentKind::Plain),
+        ("// ts-ignore", CommentKind::Plain),
+        ("//", CommentKind::Plain),
+    ] {
+        let token = Lexer::new(source).next().unwrap();
+        assert_eq!(classify_comment(source, &token), Some(kind), "{source}");
+    }
+    let token = Lexer::new("a").next().unwrap();
+    assert_eq!(classify_comment("a", &token), None);
+}
+
+#[test]
+fn obfuscation_hints() {
+    let source = "var _0x1a2b=[$,_],a=_0x1a2b[0];function $$(e){return e+_0xZZ}";
+    let stats = Statistics::new(source);
+    assert_eq!(stats.unknown_tokens, 0);
+    assert_eq!(
+        stats.obfuscation_hints,
+        ObfuscationHints {
+            identifiers: 9,
+            single_char_identifiers: 5,
+            hex_identifiers: 2,
+        }
+    );
+}
+
+#[test]
+fn kind_statistics() {
+    let stats = Statistics::new("a = b; // c\nd = a + b;");
+    assert_eq!(stats.tokens, 19);
+    assert_eq!(stats.trivia, 9);
+    assert_eq!(stats.trivia_percent(), 9.0 / 19.0 * 100.0);
+    // `=` and `;` tie, `=` appears first
+    assert_eq!(stats.top_kinds(3), [(WhiteSpace, 7), (Ident, 5), (Eq, 2)]);
+    assert_eq!(stats.top_kinds(100).len(), stats.kinds.len());
+    assert_eq!(
+        stats.kinds.iter().map(|(_, count)| count).sum::<usize>(),
+        stats.tokens
+    );
+
+    let empty = Statistics::new("");
+    assert_eq!(empty.tokens, 0);
+    assert_eq!(empty.kinds, []);
+    assert_eq!(empty.trivia_percent(), 0.0);
+    assert_eq!(empty.top_kinds(3), []);
+}
+
+#[test]
+fn byte_statistics() {
+    // 2 spaces, a tab, `\n` and `\r\n`, then `// c` and `/* d */`
+    let source = "a = 1;\t// c\n/* d */b\r\n";
+    let stats = Statistics::new(source);
+    assert_eq!(stats.whitespace_bytes, 6);
+    assert_eq!(stats.comment_bytes, 11);
+    assert_eq!(stats.significant_bytes, 5);
+    assert_eq!(
+        stats.whitespace_bytes + stats.comment_bytes + stats.significant_bytes,
+        source.len()
+    );
+
+    // an unknown token is significant
+    let stats = Statistics::new("a @");
+    assert_eq!(
+        (
+            stats.whitespace_bytes,
+            stats.comment_bytes,
+            stats.significant_bytes
+        ),
+        (1, 0, 2)
+    );
+}
+
+#[test]
+fn pipeline_operator() {
+    let kinds = |options| {
+        Lexer::with_options("a |> b", options)
+            .map(|token| *token.kind())
+            .filter(|kind| kind != &WhiteSpace)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        kinds(LexerOptions::default()),
+        [Ident, Pipe, RAngle, Ident, EOF]
+    );
+    let options = LexerOptions {
+        experimental: true,
+        ..LexerOptions::default()
+    };
+    assert_eq!(kinds(options), [Ident, PipeGt, Ident, EOF]);
+}
+
+#[test]
+fn all_kinds() {
+    // `discriminant` matches every variant, so a new variant has the next discriminant
+    // after `VirtualSemicolon`, the last one, and is missing from `ALL` until it is added
+    let variants = usize::from(VirtualSemicolon.discriminant()) + 1;
+    let numbers = [Decimal, Float, Binary, Octal, Hex, BigInt];
+    let quotes = [Single, Double];
+    assert_eq!(Kind::ALL.len(), variants - 2 + numbers.len() + quotes.len());
+    assert!(Kind::ALL
+        .windows(2)
+        .all(|w| w[0].discriminant() <= w[1].discriminant()));
+    let discriminants = Kind::ALL
+        .iter()
+        .map(|kind| usize::from(kind.discriminant()))
+        .collect::<HashSet<_>>();
+    assert_eq!(discriminants, (0..variants).collect());
+    for kind in Kind::fixed_variants() {
+        assert!(Kind::ALL.contains(kind), "{kind:?}");
+    }
+    for number in numbers {
+        assert!(Kind::ALL.contains(&Number(number)), "{number:?}");
+    }
+    for quote in quotes {
+        assert!(Kind::ALL.contains(&Str(quote)), "{quote:?}");
+    }
+}
+
+#[test]
+fn fixed_variants() {
+    // every variant except `Number(_)` and `Str(_)`, `VirtualSemicolon` is the last variant
+    const FIXED_VARIANTS: usize = VirtualSemicolon.discriminant() as usize - 1;
+    let fixed = Kind::fixed_variants();
+    assert_eq!(fixed.len(), FIXED_VARIANTS);
+    assert!(fixed
+        .windows(2)
+        .all(|w| w[0].discriminant() < w[1].discriminant()));
+
+    // kinds without a fixed source text
+    let descriptive = [
+        Unknown,
+        EOF,
+        WhiteSpace,
+        LineTerminator,
+        Comment,
+        MultilineComment,
+        Ident,
+        Undefined,
+        Str(Single),
+        Str(Double),
+        Regex,
+        Template,
+        VirtualSemicolon,
+    ];
+    let options = LexerOptions {
+        experimental: true,
+        strict: true,
+        ..LexerOptions::default()
+    };
+    for kind in Kind::ALL {
+        let text = kind.to_string();
+        if let Some(keyword) = Kind::keyword_from_str(&text) {
+            assert_eq!(&keyword, kind);
+        }
+        if !descriptive.contains(kind) && !matches!(kind, Number(_)) {
+            let tokens = Lexer::with_options(&text, options).collect::<Vec<_>>();
+            assert_eq!(tokens.len(), 2, "{kind:?} {tokens:?}");
+            assert_eq!(tokens[0].kind(), kind, "{kind:?} {tokens:?}");
+        }
+    }
+    assert_eq!(Kind::keyword_from_str("undefined"), None);
+}
+
+#[test]
+fn channel() {
+    let source = "let a = /re/g; // comment\nf(`t`, 1.5);";
+    let (handle, receiver) = spawn_lexer(source.to_string());
+    let received = receiver.into_iter().collect::<Vec<_>>();
+    handle.join().unwrap();
+    assert_eq!(received, Lexer::new(source).collect::<Vec<_>>());
+    assert_eq!(received.last().unwrap().kind(), &EOF);
+}
+
+#[test]
+fn first_statement() {
+    let first = |source| {
+        let (tokens, end) = lex_first_statement(source);
+        let kinds = tokens
+            .iter()
+            .map(|token| *token.kind())
+            .filter(|kind| !matches!(kind, WhiteSpace | LineTerminator))
+            .collect::<Vec<_>>();
+        (kinds, end)
+    };
+    assert_eq!(
+        first("a=1; b=2"),
+        (vec![Ident, Eq, Number(Decimal), Semicolon], 4)
+    );
+    assert_eq!(first("f(\n)"), (vec![Ident, LParen, RParen], 4));
+    assert_eq!(
+        first("{ a; b }; c"),
+        (vec![LCurly, Ident, Semicolon, Ident, RCurly, Semicolon], 9)
+    );
+    assert_eq!(first("a\nb"), (vec![Ident], 1));
+    assert_eq!(
+        first("a\n/* c */ .b\nc"),
+        (vec![Ident, MultilineComment, Dot, Ident], 12)
+    );
+    assert_eq!(first("a /*\n*/ b"), (vec![Ident], 2));
+    assert_eq!(first("a\n++b"), (vec![Ident], 1));
+    assert_eq!(first("a +\nb"), (vec![Ident, Plus, Ident], 5));
+    assert_eq!(first("return\na"), (vec![Return], 6));
+    assert_eq!(first("var\na"), (vec![Var, Ident], 5));
+    assert_eq!(
+        first("if (a)\n{ b }\nc"),
+        (vec![If, LParen, Ident, RParen, LCurly, Ident, RCurly], 12)
+    );
+    assert_eq!(first("a\n"), (vec![Ident], 1));
+    assert_eq!(first(""), (vec![], 0));
+}
+
+#[test]
+fn significant() {
+    let tokens = Lexer::new("a /* b */\n").collect::<Vec<_>>();
+    let classes = tokens
+        .iter()
+        .map(|token| (*token.kind(), token.is_trivia(), token.is_significant()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        classes,
+        [
+            (Ident, false, true),
+            (WhiteSpace, true, false),
+            (MultilineComment, true, false),
+            (LineTerminator, true, false),
+            (EOF, false, false),
+        ]
+    );
+    assert_eq!(count_significant_tokens("a /* b */\n"), 1);
+    assert_eq!(count_significant_tokens("let a = 1; // b"), 5);
+    assert_eq!(count_significant_tokens(""), 0);
+}
+
+#[test]
+fn token_group() {
+    let source = "let a = null; // b\nif (0x1 !== `c`) e = /d/ @";
+    let groups = Lexer::new(source)
+        .map(|token| {
+            (
+                token_text(source, &token, DumpOptions::default()),
+                token.group(),
+            )
+        })
+        .filter(|(_, group)| *group != TokenGroup::Trivia)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        groups,
+        [
+            ("let", TokenGroup::Keyword),
+            ("a", TokenGroup::Ident),
+            ("=", TokenGroup::Punctuator),
+            ("null", TokenGroup::Literal),
+            (";", TokenGroup::Punctuator),
+            ("if", TokenGroup::Keyword),
+            ("(", TokenGroup::Punctuator),
+            ("0x1", TokenGroup::Literal),
+            ("!==", TokenGroup::Punctuator),
+            ("`c`", TokenGroup::Literal),
+            (")", TokenGroup::Punctuator),
+            ("e", TokenGroup::Ident),
+            ("=", TokenGroup::Punctuator),
+            ("/d/", TokenGroup::Literal),
+            ("@", TokenGroup::Other),
+            ("", TokenGroup::Other),
+        ]
+    );
+    for kind in [WhiteSpace, LineTerminator, Comment, MultilineComment] {
+        assert_eq!(kind.group(), TokenGroup::Trivia);
+    }
+    assert_eq!(Public.group(), TokenGroup::Keyword);
+    assert_eq!(VirtualSemicolon.group(), TokenGroup::Punctuator);
+    // every kind but `Ident` and the trivia spells its group
+    for kind in Kind::ALL {
+        assert_eq!(
+            kind.group() == TokenGroup::Trivia,
+            kind.is_trivia(),
+            "{kind:?}"
+        );
+        if kind.group() == TokenGroup::Keyword {
+            assert_eq!(Kind::keyword_from_str(kind.as_str()), Some(*kind));
+        }
+    }
+}
+
+#[test]
+fn kinds_table() {
+    // the text of every keyword and punctuator lexes back as its kind, after an identifier
+    // so that `/` is a division
+    for kind in Kind::fixed_variants() {
+        assert_eq!(kind.is_keyword(), kind.group() == TokenGroup::Keyword);
+        if !matches!(kind.group(), TokenGroup::Keyword | TokenGroup::Punctuator)
+            || kind == &VirtualSemicolon
+        {
+            continue;
+        }
+        let source = format!("a {}", kind.as_str());
+        let options = LexerOptions {
+            experimental: true,
+            strict: true,
+            ..LexerOptions::new()
+        };
+        let tokens = Lexer::with_options(&source, options).collect::<Vec<_>>();
+        assert_eq!(tokens[2].kind(), kind, "{source}");
+        assert_eq!(tokens[2].range(), 2..source.len(), "{source}");
+    }
+}
+
+#[test]
+fn directive_prologue() {
+    fn directive(source: &str) -> Option<&str> {
+        let tokens = Lexer::new(source).collect::<Vec<_>>();
+        is_directive_prologue_candidate(source, &tokens).map(|span| &source[span.range()])
+    }
+    assert_eq!(directive("\"use strict\";\na = 1;"), Some("\"use strict\""));
+    assert_eq!(directive("// a\n'use asm'"), Some("'use asm'"));
+    // after other directives, ended by a line break or a `;`
+    assert_eq!(
+        directive("'a'\n\"b\"; \"use strict\"\nlet c"),
+        Some("\"use strict\"")
+    );
+    assert_eq!(directive("a;\n\"use strict\";"), None);
+    assert_eq!(directive("'a' + b;\n'use strict';"), None);
+    // an expression, not a statement of its own
+    assert_eq!(directive("'use strict'\n.length"), None);
+    assert_eq!(directive("'use strict' + a"), None);
+    // not spelled exactly
+    assert_eq!(directive("'use\\x20strict';"), None);
+    assert_eq!(directive("'use strict ';"), None);
+    assert_eq!(directive(""), None);
+}
+
+#[test]
+fn virtual_semicolons() {
+    let options = LexerOptions {
+        virtual_semicolons: true,
+        ..LexerOptions::default()
+    };
+    let tokens = Lexer::with_options("return\n1", options).collect::<Vec<_>>();
+    assert_eq!(tokens[1], Token::new(VirtualSemicolon, 6, 0));
+    assert!(tokens[1].is_empty() && tokens[1].is_significant());
+    let kinds = tokens.iter().map(Token::kind).collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            &Return,
+            &VirtualSemicolon,
+            &LineTerminator,
+            &Number(Decimal),
+            &VirtualSemicolon,
+            &EOF
+        ]
+    );
+
+    // the source with a `;` for each virtual semicolon
+    let insert = |source: &str| {
+        let mut s = source.to_string();
+        let tokens = Lexer::with_options(source, options).collect::<Vec<_>>();
+        for token in tokens.iter().rev() {
+            if token.kind() == &VirtualSemicolon {
+                s.insert(token.range().start, ';');
+            }
+        }
+        s
+    };
+    for (source, expected) in [
+        ("", ""),
+        ("throw\nerr", "throw;\nerr;"),
+        ("break /* \n */ label", "break; /* \n */ label;"),
+        ("continue // c\n", "continue; // c\n"),
+        ("a.return\nb", "a.return\nb;"),
+        ("return;\n", "return;\n"),
+        ("a = 1\nb = 2", "a = 1\nb = 2;"),
+        ("if (a) {\n  b()\n}", "if (a) {\n  b();\n}"),
+        ("f(() => {\n  a\n})", "f(() => {\n  a;\n});"),
+        ("x = {\n  a\n}", "x = {\n  a\n}"),
+        ("{ a }", "{ a }"),
+        ("{\n}", "{\n}"),
+    ] {
+        assert_eq!(insert(source), expected);
+    }
+
+    let mut kinds = Lexer::new("return\n1").map(|token| *token.kind());
+    assert!(kinds.all(|kind| kind != VirtualSemicolon));
+}
+
+#[test]
+fn token_count_estimate() {
+    let punctuators = PUNCTUATORS
+        .iter()
+        .map(|(_, s)| format!("a {s} b"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    for source in [
+        "",
+        "a",
+        "let a = 1;\r\n\r\nfoo.bar(baz, 'qu\\'ux');",
+        "/* license\n * header\n */\n// comment\nx = `a ${b} c`",
+        "if (a) { return \"b c\" } else { a++ }",
+        "x = 1.5e+3 + 0x1F + 10n",
+        "'unterminated\n\"unterminated",
+        "s = a.replace(/[\"'/]/g, '') / 2; return /`/.test(s)",
+        "t = `a ${b + \"`\"} c`; d = e / f / g",
+        &punctuators,
+    ] {
+        let count = Lexer::new(source).count();
+        let estimate = estimate_token_count(source);
+        assert!(
+            count <= estimate && estimate <= 2 * count,
+            "{source} {count} {estimate}"
+        );
+    }
+}
+
+#[test]
+fn token_buffer() {
+    let punctuators = PUNCTUATORS
+        .iter()
+        .map(|(_, s)| format!("a {s} b"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    for source in [
+        "",
+        "let a = 1;\r\n\r\nfoo.bar(baz, 'qu\\'ux');",
+        "/* c */ x = `a ${b} c` / /re/g; \\u0061 é",
+        &punctuators,
+    ] {
+        let tokens = Lexer::new(source).collect::<Vec<_>>();
+        let buffer = tokenize_soa(source);
+        assert_eq!(buffer.len(), tokens.len());
+        assert_eq!(buffer.iter().collect::<Vec<_>>(), tokens, "{source}");
+        assert_eq!(
+            buffer.kinds(),
+            tokens.iter().map(|t| *t.kind()).collect::<Vec<_>>()
+        );
+        for (i, token) in tokens.iter().enumerate() {
+            assert_eq!(buffer.starts()[i] as usize, token.range().start);
+            assert_eq!(buffer.lens()[i] as usize, token.len());
+            assert_eq!(buffer.get(i).as_ref(), Some(token));
+        }
+        assert_eq!(buffer.get(tokens.len()), None);
+    }
+}
+
+// a longer source than the limit is only possible with 64-bit `usize`
+#[cfg(target_pointer_width = "64")]
+#[test]
+fn u32_offsets() {
+    assert_eq!(MAX_U32_SOURCE_LEN, u32::MAX as usize - 1);
+    assert_eq!(check_u32_offsets(0), Ok(()));
+    assert_eq!(check_u32_offsets(MAX_U32_SOURCE_LEN), Ok(()));
+    // a 4 GiB source would not fit in the memory of a test
+    let error = check_u32_offsets(MAX_U32_SOURCE_LEN + 1).unwrap_err();
+    assert!(error.contains("4294967295 bytes"), "{}", error);
+    // the `EOF` of the largest source, which ends past it
+    let eof = Token::new(EOF, MAX_U32_SOURCE_LEN, 1);
+    let mut buffer = TokenBuffer::default();
+    buffer.push(&eof);
+    assert_eq!(buffer.get(0), Some(eof));
+    assert_eq!(try_tokenize_soa("a").unwrap(), tokenize_soa("a"));
+}
+
+/// A snapshot of the tokens of each fixture, approve a change with `cargo insta review`
+#[test]
+fn fixture_snapshots() {
+    let mut inputs = fs::read_dir(fixtures())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    inputs.sort();
+    assert!(!inputs.is_empty());
+    for input in inputs {
+        let name = input.file_stem().unwrap().to_str().unwrap();
+        let tokens = dump(&fs::read_to_string(&input).unwrap(), DumpOptions::default());
+        insta::assert_snapshot!(name, tokens);
+    }
+}
+
+#[test]
+fn redact_comments() {
+    let source = "a // secret\n/* token=abc */ b";
+    let redacted = DumpOptions {
+        redact_comments: true,
+    };
+    let dumped = dump(source, redacted);
+    assert!(!dumped.contains("secret"));
+    assert!(!dumped.contains("token=abc"));
+    assert_eq!(
+        dumped.lines().collect::<Vec<_>>(),
+        [
+            "Ident 0..1 \"a\"",
+            "WhiteSpace 1..2 \" \"",
+            "Comment 2..11 \"<comment>\"",
+            "LineTerminator 11..12 \"\\n\"",
+            "MultilineComment 12..27 \"<comment>\"",
+            "WhiteSpace 27..28 \" \"",
+            "Ident 28..29 \"b\"",
+            "EOF 29..30 \"\"",
+        ]
+    );
+
+    let tokens = Lexer::new(source).collect::<Vec<_>>();
+    assert_eq!(token_text(source, &tokens[2], redacted), REDACTED_COMMENT);
+    assert_eq!(
+        token_text(source, &tokens[2], DumpOptions::default()),
+        "// secret"
+    );
+    assert_eq!(token_text(source, &tokens[6], redacted), "b");
+}
+
+/// xorshift64*, choosing the pieces of [`generator::generate`]
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        usize::try_from(self.0.wrapping_mul(0x2545_f491_4f6c_dd1d) % n as u64).unwrap()
+    }
+}
+
+/// Random token streams lex back to the generated kinds, see the `tokens` fuzz target
+#[test]
+fn generated_tokens() {
+    let mut rng = Rng(0x5eed);
+    for _ in 0..1000 {
+        let stream = generator::generate(|n| Some(rng.below(n)), 64);
+        let (source, expected) = generator::render(&stream);
+        let kinds = Lexer::new(&source)
+            .map(|token| *token.kind())
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, expected, "{source:?}");
+    }
+}
+
+#[test]
+fn for_each_token() {
+    let source = "let a = b + 1.5 + 2;";
+    let mut visited = 0;
+    let first_number = Lexer::new(source).for_each_token(|token| {
+        visited += 1;
+        match token.kind() {
+            Number(_) => ControlFlow::Break(token.range()),
+            _ => ControlFlow::Continue(()),
+        }
+    });
+    assert_eq!(first_number, ControlFlow::Break(12..15));
+    assert_eq!(visited, 11);
+
+    let mut kinds = vec![];
+    let all = Lexer::new("a;").for_each_token(|token| {
+        kinds.push(*token.kind());
+        ControlFlow::<()>::Continue(())
+    });
+    assert_eq!(all, ControlFlow::Continue(()));
+    assert_eq!(kinds, [Ident, Semicolon, EOF]);
+}
+
+/// Every input of the fuzz corpus, so that a crash regression fails `cargo test` without the fuzzer
+#[test]
+fn fuzz_corpus() {
+    let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz/corpus");
+    let mut inputs = 0;
+    for target in fs::read_dir(corpus).unwrap() {
+        for input in fs::read_dir(target.unwrap().path()).unwrap() {
+            let path = input.unwrap().path();
+            let bytes = fs::read(&path).unwrap();
+            if let Ok(source) = std::str::from_utf8(&bytes) {
+                let last = Lexer::new(source).last().unwrap();
+                assert_eq!(last.kind(), &EOF, "{}", path.display());
+                assert_eq!(last.range().start, source.len(), "{}", path.display());
+                inputs += 1;
+            }
+        }
+    }
+    assert!(inputs > 0);
+}
+
+#[test]
+fn string_quote() {
+    let source = "'a' + \"b\" + `c` + \"d";
+    let mut lexer = Lexer::new(source);
+    let quotes = lexer
+        .by_ref()
+        .filter(Token::is_significant)
+        .map(|token| token.quote())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        quotes,
+        [
+            Some(Single),
+            None,
+            Some(Double),
+            None,
+            None,
+            None,
+            Some(Double)
+        ]
+    );
+    // an unterminated string is an `Unknown` token with its quote, see `LexerError`
+    assert_eq!(lexer.errors()[0].kind(), ErrorKind::UnterminatedString);
+    let tokens = Lexer::new("'a\n\"b").collect::<Vec<_>>();
+    assert_eq!(tokens[0].kind(), &Unknown);
+    assert_eq!(tokens[0].quote(), Some(Single));
+    assert_eq!(
+        format!("{:?}", tokens[0]),
+        "Token { kind: Unknown, offset: 0, len: 5, unterminated_quote: Single }"
+    );
+    // not a string
+    assert_eq!(Lexer::new("@").next().unwrap().quote(), None);
+    assert_eq!(Single.as_char(), '\'');
+    assert_eq!(Quote::from_byte(b'"'), Some(Double));
+    assert_eq!(Quote::from_byte(b'`'), None);
+    assert_eq!("Str(Single)".parse(), Ok(Str(Single)));
+    assert_eq!("Str::Double".parse(), Ok(Str(Double)));
+    assert!("Str".parse::<Kind>().is_err());
+}
+
+#[test]
+fn surrogate_escapes() {
+    // a pair, a lone high or low surrogate and a reversed pair are all valid in strings
+    [
+        r#""\uD834\uDD1E""#,
+        r#""\uD834""#,
+        r#"'\uDD1E'"#,
+        r#""\uDD1E\uD834""#,
+        r#""\u{D834}""#,
+    ]
+    .into_iter()
+    .for_each(|s| test(string(s), s));
+
+    // an identifier escape is a code point, a surrogate is invalid even as a pair.
+    // The identifier through the escape is the error.
+    for (s, error) in [
+        (r#"a\uD834b"#, r#"a\uD834"#),
+        (r#"a\uD834\uDD1Eb"#, r#"a\uD834"#),
+        (r#"\uD834"#, r#"\uD834"#),
+        (r#"a\u{DD1E}"#, r#"a\u{DD1E}"#),
+    ] {
+        let mut lexer = Lexer::new(s);
+        let token = lexer.next().unwrap();
+        assert_eq!(token.kind(), &Unknown, "{s}");
+        assert_eq!(&s[token.range()], error, "{s}");
+        lexer.by_ref().count();
+        assert_eq!(
+            lexer.errors()[0].kind(),
+            ErrorKind::InvalidIdentifier,
+            "{s}"
+        );
+        assert_eq!(&s[lexer.errors()[0].span().clone()], error, "{s}");
+    }
+    assert_lex!(r#"a\uD834b"#, trivia [Unknown r#"a\uD834"#, Ident "b"]);
+}
+
+#[test]
+fn number_followed_by_dot() {
+    // `EOF` is past the end
+    let tokens = |source: &'static str| {
+        Lexer::new(source)
+            .map(|token| (*token.kind(), source.get(token.range()).unwrap_or_default()))
+            .collect::<Vec<_>>()
+    };
+    // `1.` is a float, the second dot is a member access
+    assert_eq!(
+        tokens("1..toString()"),
+        [
+            (Number(Float), "1."),
+            (Dot, "."),
+            (Ident, "toString"),
+            (LParen, "("),
+            (RParen, ")"),
+            (EOF, ""),
+        ]
+    );
+    assert_eq!(
+        tokens("0..toString"),
+        [
+            (Number(Float), "0."),
+            (Dot, "."),
+            (Ident, "toString"),
+            (EOF, "")
+        ]
+    );
+    assert_eq!(
+        tokens("1.2.3"),
+        [
+            (Number(Float), "1.2"),
+            (Dot, "."),
+            (Number(Decimal), "3"),
+            (EOF, "")
+        ]
+    );
+}
+
+#[test]
+fn token_sequences() {
+    assert_lex!("a+b", [Ident "a", Plus "+", Ident "b"]);
+    assert_lex!("a /* c */\nb", trivia [
+        Ident "a", WhiteSpace " ", MultilineComment "/* c */", LineTerminator "\n", Ident "b",
+    ]);
+
+    // regex or divide, by the previous significant token
+    assert_lex!("a / b / c", [Ident "a", Slash "/", Ident "b", Slash "/", Ident "c"]);
+    assert_lex!("(a) / 2 / i", [
+        LParen "(", Ident "a", RParen ")", Slash "/", Number(Decimal) "2", Slash "/", Ident "i",
+    ]);
+    assert_lex!("x = /b/g / 2", [Ident "x", Eq "=", Regex "/b/g", Slash "/", Number(Decimal) "2"]);
+    assert_lex!("return /a/", [Return "return", Regex "/a/"]);
+    assert_lex!("a /= 2", [Ident "a", SlashEq "/=", Number(Decimal) "2"]);
+
+    // `?.` is not optional chaining before a digit
+    assert_lex!("a?.5:b", [
+        Ident "a", Question "?", Dot ".", Number(Decimal) "5", Colon ":", Ident "b",
+    ]);
+    assert_lex!("a?.b", [Ident "a", QuestionDot "?.", Ident "b"]);
+    assert_lex!("a?.[0]", [Ident "a", QuestionDot "?.", LBrack "[", Number(Decimal) "0", RBrack "]"]);
+    // maximal munch, `??` is taken before a `?.` can start
+    assert_lex!("a??.b", [Ident "a", Question2 "??", Dot ".", Ident "b"]);
+    assert_lex!("a??.5", [Ident "a", Question2 "??", Dot ".", Number(Decimal) "5"]);
+    assert_lex!("a?.?.b", [Ident "a", QuestionDot "?.", QuestionDot "?.", Ident "b"]);
+    assert_lex!("a???.b", [Ident "a", Question2 "??", QuestionDot "?.", Ident "b"]);
+    assert_lex!("a???=b", [Ident "a", Question2 "??", Question "?", Eq "=", Ident "b"]);
+
+    // a leading dot is a `Dot`, see `number_followed_by_dot`
+    assert_lex!(".5", [Dot ".", Number(Decimal) "5"]);
+    assert_lex!("1..toString", [Number(Float) "1.", Dot ".", Ident "toString"]);
+    assert_lex!("a...b", [Ident "a", Dot3 "...", Ident "b"]);
+    assert_lex!("a..b", [Ident "a", Dot ".", Dot ".", Ident "b"]);
+}
+
+#[test]
+fn regex_after_colon() {
+    assert_lex!("a ? b : /re/", [Ident "a", Question "?", Ident "b", Colon ":", Regex "/re/"]);
+    assert_lex!("l: /re/g", [Ident "l", Colon ":", Regex "/re/g"]);
+    assert_lex!("({ a: /re/ })", [
+        LParen "(", LCurly "{", Ident "a", Colon ":", Regex "/re/", RCurly "}", RParen ")",
+    ]);
+    assert!(Colon.at_expr());
+}
+
+#[test]
+fn lossy() {
+    let kinds = |bytes: &[u8]| {
+        tokenize_lossy(bytes)
+            .into_iter()
+            .map(|token| (*token.kind(), token.range()))
+            .collect::<Vec<_>>()
+    };
+    // a stray byte between two identifiers
+    assert_eq!(
+        kinds(b"foo\xFFbar"),
+        [(Ident, 0..3), (Unknown, 3..4), (Ident, 4..7), (EOF, 7..8)]
+    );
+    assert_eq!(
+        kinds(b"a \xE2\x80 b\n"),
+        [
+            (Ident, 0..1),
+            (WhiteSpace, 1..2),
+            (Unknown, 2..4),
+            (WhiteSpace, 4..5),
+            (Ident, 5..6),
+            (LineTerminator, 6..7),
+            (EOF, 7..8),
+        ]
+    );
+    // truncated at the end
+    assert_eq!(
+        kinds(b"x\xE2\x80"),
+        [(Ident, 0..1), (Unknown, 1..3), (EOF, 3..4)]
+    );
+    assert_eq!(
+        kinds(b"\xFF\xFF"),
+        [(Unknown, 0..1), (Unknown, 1..2), (EOF, 2..3)]
+    );
+    let source = "let a = \"é\";";
+    assert_eq!(
+        tokenize_lossy(source.as_bytes()),
+        Lexer::new(source).collect::<Vec<_>>()
+    );
+}
+
+/// Kinds which may lex differently in isolation than in their source,
+/// e.g. a `TemplateMiddle` would need the template it continues
+const RELEX_EXCEPTIONS: [Kind; 0] = [];
+
+/// The kind and length of `text` lexed in isolation, after an identifier if the token it comes
+/// from was lexed where a `/` is a division
+fn relex(text: &str, expr: bool) -> Option<(Kind, usize)> {
+    let prefix = if expr { "" } else { "a " };
+    let source = format!("{prefix}{text}");
+    let mut tokens = Lexer::new(&source).skip_while(|token| token.range().start < prefix.len());
+    let token = tokens.next()?;
+    let next = tokens.next()?;
+    (token.range().start == prefix.len() && next.kind() == &EOF)
+        .then(|| (*token.kind(), token.len()))
+}
+
+/// Tokens of `source` which do not lex to themselves in isolation, as `kind range text`
+fn relex_violations(source: &str) -> Vec<String> {
+    let mut expr = true;
+    let mut violations = Vec::new();
+    for token in Lexer::new(source) {
+        let kind = *token.kind();
+        if !token.is_trivia() && !token.is_unknown() && kind != EOF {
+            let text = &source[token.range()];
+            let relexed = relex(text, expr);
+            if relexed != Some((kind, token.len())) && !RELEX_EXCEPTIONS.contains(&kind) {
+                violations.push(format!("{kind:?} {:?} {text:?} {relexed:?}", token.range()));
+            }
+        }
+        if !matches!(kind, WhiteSpace | LineTerminator) {
+            expr = kind.at_expr();
+        }
+    }
+    violations
+}
+
+#[test]
+fn relex_idempotence() {
+    let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz/corpus");
+    let inputs = fs::read_dir(fixtures())
+        .unwrap()
+        .chain(
+            fs::read_dir(corpus)
+                .unwrap()
+                .flat_map(|target| fs::read_dir(target.unwrap().path()).unwrap()),
+        )
+        .map(|entry| entry.unwrap().path());
+    let mut violations = Vec::new();
+    for path in inputs {
+        if let Ok(source) = fs::read_to_string(&path) {
+            for violation in relex_violations(&source) {
+                violations.push(format!("{}: {violation}", path.display()));
+            }
+        }
+    }
+    assert!(violations.is_empty(), "{}", violations.join("\n"));
+}
+
+#[test]
+fn relex_token() {
+    let source = "x = `a ${b + 1}`;";
+    let tokens = Lexer::new(source).collect::<Vec<_>>();
+    let template = &tokens[4];
+    assert_eq!(template.kind(), &Template);
+    assert_eq!(
+        template.relex(source, LexerOptions::default()),
+        [Token::new(Template, 4, 12), Token::new(EOF, 16, 1)]
+    );
+    // between the backticks
+    let inner = Token::new(Template, template.range().start + 1, template.len() - 2);
+    let mut relexed = inner.relex(source, LexerOptions::default());
+    assert_eq!(relexed.pop(), Some(Token::new(EOF, 15, 1)));
+    let relexed = relexed
+        .iter()
+        .map(|token| (*token.kind(), &source[token.range()]))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        relexed,
+        [
+            (Ident, "a"),
+            (WhiteSpace, " "),
+            (Ident, "$"),
+            (LCurly, "{"),
+            (Ident, "b"),
+            (WhiteSpace, " "),
+            (Plus, "+"),
+            (WhiteSpace, " "),
+            (Number(Decimal), "1"),
+            (RCurly, "}"),
+        ]
+    );
+}
+
+#[test]
+fn span() {
+    let token = Token::new(Ident, 4, 3);
+    let span = token.span();
+    assert_eq!(span, Span::new(4, 7));
+    assert_eq!(span.range(), token.range());
+    assert_eq!((span.len(), span.is_empty()), (3, false));
+    assert_eq!(Span::from(0..1).to(span), Span::new(0, 7));
+    assert_eq!(format!("{span:?}"), "4..7");
 }
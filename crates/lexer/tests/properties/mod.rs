@@ -0,0 +1,108 @@
+//! Property based tests, each lexing a generated literal as exactly one token of its kind.
+//! A failure is shrunk to a minimal literal.
+
+use lexer::{Kind, Lexer, Number, Quote};
+use proptest::prelude::*;
+
+use super::test;
+
+/// Cases per property, few enough for `cargo test` to stay fast
+const CASES: u32 = 256;
+
+/// A number of each kind, with numeric separators between digits, also in the exponent
+fn numeric_literal() -> impl Strategy<Value = (String, Number)> {
+    prop_oneof![
+        "(0|[1-9](_?[0-9]){0,8})([eE][+-]?[0-9](_?[0-9]){0,2})?".prop_map(|s| (s, Number::Decimal)),
+        "(0|[1-9](_?[0-9]){0,8})\\.[0-9](_?[0-9]){0,4}([eE][+-]?[0-9](_?[0-9]){0,2})?"
+            .prop_map(|s| (s, Number::Float)),
+        "0[bB][01](_?[01]){0,8}".prop_map(|s| (s, Number::Binary)),
+        "0[oO][0-7](_?[0-7]){0,8}".prop_map(|s| (s, Number::Octal)),
+        "0[xX][0-9a-fA-F](_?[0-9a-fA-F]){0,8}".prop_map(|s| (s, Number::Hex)),
+        "(0|[1-9](_?[0-9]){0,8})n".prop_map(|s| (s, Number::BigInt)),
+    ]
+}
+
+/// A character of `class`, literal or as a `\u0061` or `\u{61}` escape sequence
+fn identifier_char(class: &'static str) -> impl Strategy<Value = String> {
+    (class, 0..3_u8).prop_map(|(c, spelling)| {
+        let c = u32::from(c.chars().next().unwrap());
+        match spelling {
+            0 => char::from_u32(c).unwrap().to_string(),
+            1 => format!("\\u{c:04x}"),
+            _ => format!("\\u{{{c:x}}}"),
+        }
+    })
+}
+
+/// An identifier which does not spell a keyword, an escaped keyword is an identifier
+fn identifier() -> impl Strategy<Value = String> {
+    (
+        identifier_char("[a-zA-Z_$μ]"),
+        prop::collection::vec(identifier_char("[a-zA-Z0-9_$μ\u{200c}\u{200d}]"), 0..8),
+    )
+        .prop_map(|(start, parts)| start + &parts.concat())
+        .prop_filter("keyword", |s| Kind::keyword_from_str(s).is_none())
+}
+
+/// Every char of `source` as a `\u{61}` escape sequence, lexed char by char
+fn escape_all(source: &str) -> String {
+    source
+        .chars()
+        .map(|c| format!("\\u{{{:x}}}", u32::from(c)))
+        .collect()
+}
+
+/// A string of printable characters and escape sequences, neither a quote nor a backslash alone
+fn string_literal() -> impl Strategy<Value = String> {
+    let part = prop_oneof![
+        "[ !#-&(-\\[\\]-~é€\u{2028}]",
+        "\\\\[nrtbfv0'\"\\\\]",
+        "\\\\x[0-9a-fA-F]{2}",
+        "\\\\u[0-9a-fA-F]{4}",
+        "\\\\u\\{[0-9a-fA-F]{1,5}\\}",
+        // line continuations
+        "\\\\(\n|\r\n|\u{2028})",
+    ];
+    ("['\"]", prop::collection::vec(part, 0..8))
+        .prop_map(|(quote, parts)| format!("{quote}{}{quote}", parts.concat()))
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    #[test]
+    fn numeric_literal_properties((source, number) in numeric_literal()) {
+        test(Kind::Number(number), &source);
+    }
+
+    #[test]
+    fn identifier_properties(source in identifier()) {
+        test(Kind::Ident, &source);
+    }
+
+    // the ASCII runs of an identifier, scanned with a bitmask, end where its escapes do,
+    // which are read one by one, whatever follows
+    #[test]
+    fn identifier_ascii_run_properties((source, follow) in (
+        "[a-zA-Z_$μ中𝐀][a-zA-Z0-9_$μ中𝐀·\u{200c}\u{200d}]{0,12}",
+        "[ (.\\-@#é€\u{a0}]{0,2}",
+    )) {
+        prop_assume!(Kind::keyword_from_str(&source).is_none());
+        let lex = |source: String| {
+            let mut lexer = Lexer::new(&source);
+            let first = lexer.next().unwrap();
+            let rest = lexer.map(|token| *token.kind()).collect::<Vec<_>>();
+            (*first.kind(), source.len() - first.len(), rest)
+        };
+        prop_assert_eq!(
+            lex(source.clone() + &follow),
+            lex(escape_all(&source) + &follow)
+        );
+    }
+
+    #[test]
+    fn string_literal_properties(source in string_literal()) {
+        let quote = Quote::from_byte(source.as_bytes()[0]).unwrap();
+        test(Kind::Str(quote), &source);
+    }
+}
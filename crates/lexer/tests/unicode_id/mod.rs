@@ -0,0 +1,119 @@
+//! Classification of code points as identifier start, continue or neither, against tables
+//! generated from `DerivedCoreProperties.txt` of Unicode 14.0.0, the version of `unicode-id`,
+//! with the additions of ECMAScript: `$` and `_` start, U+200C and U+200D continue.
+//! Updating `unicode-id` to another Unicode version fails these tests on purpose,
+//! regenerate the expectations from the new UCD.
+
+use lexer::{Kind, Lexer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Class {
+    Start,
+    /// `ID_Continue` but not `ID_Start`
+    Continue,
+    Neither,
+}
+
+/// Code points at the edges of the tables: `Other_ID_Start`, `Other_ID_Continue`,
+/// digits of other scripts and the exceptions of `Pattern_Syntax`
+const BOUNDARIES: [(char, Class); 32] = [
+    ('$', Class::Start),
+    ('_', Class::Start),
+    ('\u{aa}', Class::Start),
+    ('\u{b5}', Class::Start),
+    ('\u{ba}', Class::Start),
+    ('\u{37a}', Class::Start),
+    ('\u{e33}', Class::Start),
+    ('\u{1885}', Class::Start),
+    ('\u{1886}', Class::Start),
+    ('\u{2118}', Class::Start),
+    ('\u{212e}', Class::Start),
+    ('\u{2160}', Class::Start),
+    ('\u{3007}', Class::Start),
+    ('\u{309b}', Class::Start),
+    ('\u{309c}', Class::Start),
+    ('\u{ff21}', Class::Start),
+    ('0', Class::Continue),
+    ('\u{b7}', Class::Continue),
+    ('\u{387}', Class::Continue),
+    ('\u{660}', Class::Continue),
+    ('\u{1369}', Class::Continue),
+    ('\u{19da}', Class::Continue),
+    ('\u{200c}', Class::Continue),
+    ('\u{200d}', Class::Continue),
+    ('\u{2040}', Class::Continue),
+    ('\u{2054}', Class::Continue),
+    ('\u{fe33}', Class::Continue),
+    ('\u{ff10}', Class::Continue),
+    ('\u{a0}', Class::Neither),
+    ('\u{d7}', Class::Neither),
+    ('\u{2e2f}', Class::Neither),
+    // `ID_Continue` since Unicode 15.1
+    ('\u{30fb}', Class::Neither),
+];
+
+/// Size of the blocks of the checksum
+const BLOCK: u32 = 0x1000;
+
+/// Per block of the BMP, the number of start code points and of continue only code points
+const CHECKSUMS: [(usize, usize); 16] = [
+    (2300, 858),
+    (3079, 513),
+    (515, 67),
+    (3464, 8),
+    (4032, 0),
+    (4096, 0),
+    (4096, 0),
+    (4096, 0),
+    (4096, 0),
+    (4096, 0),
+    (3526, 219),
+    (4096, 0),
+    (4096, 0),
+    (2028, 0),
+    (0, 0),
+    (1421, 49),
+];
+
+/// Whether the source is a single identifier, only lexing its first token
+fn is_ident(source: &str) -> bool {
+    Lexer::new(source).next().map_or(false, |token| {
+        token.kind() == &Kind::Ident && token.range() == (0..source.len())
+    })
+}
+
+fn classify(c: char) -> Class {
+    if is_ident(&c.to_string()) {
+        Class::Start
+    } else if is_ident(&format!("a{c}")) {
+        Class::Continue
+    } else {
+        Class::Neither
+    }
+}
+
+#[test]
+fn boundaries() {
+    for (c, class) in BOUNDARIES {
+        assert_eq!(classify(c), class, "U+{:04X}", u32::from(c));
+    }
+}
+
+#[test]
+fn block_checksums() {
+    for (block, checksum) in (0..).zip(CHECKSUMS) {
+        let start = block * BLOCK;
+        let classes = (start..start + BLOCK)
+            .filter_map(char::from_u32)
+            .map(classify)
+            .collect::<Vec<_>>();
+        let count = |class| classes.iter().filter(|c| **c == class).count();
+        assert_eq!(
+            (count(Class::Start), count(Class::Continue)),
+            checksum,
+            "(start, continue) of U+{:04X}..U+{:04X}",
+            start,
+            start + BLOCK - 1
+        );
+    }
+}
@@ -0,0 +1,22 @@
+// acorn-like tokens from the single buffer of the binding
+const binding = require("./lexer.node");
+
+// shared by all the tokens, as acorn's token types are
+const types = binding.tokenTypes().map((label) => ({ label }));
+
+function tokenize(source, options) {
+  const buffer = binding.tokenizeBuffer(source, options);
+  const tokens = new Array(buffer.length / 3);
+  for (let i = 0; i < buffer.length; i += 3) {
+    const start = buffer[i + 1];
+    const end = buffer[i + 2];
+    tokens[i / 3] = { type: types[buffer[i]], value: source.slice(start, end), start, end };
+  }
+  return tokens;
+}
+
+function check(source) {
+  return binding.check(source);
+}
+
+module.exports = { tokenize, check };
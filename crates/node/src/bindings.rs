@@ -0,0 +1,85 @@
+//! The napi functions, wrapped by `index.js`
+
+// napi passes the arguments by value
+#![allow(clippy::needless_pass_by_value)]
+
+use lexer::{EcmaVersion, LexerOptions};
+use napi::bindgen_prelude::Uint32Array;
+use napi_derive::napi;
+
+#[napi(object)]
+pub struct TokenizeOptions {
+    /// 2015 to 2022, the latest by default
+    pub ecma_version: Option<u32>,
+    pub experimental: Option<bool>,
+    pub strict: Option<bool>,
+    pub module: Option<bool>,
+}
+
+impl TokenizeOptions {
+    fn lexer_options(&self) -> napi::Result<LexerOptions> {
+        let ecma_version = match self.ecma_version {
+            None => EcmaVersion::LATEST,
+            Some(year) => EcmaVersion::from_year(year).ok_or_else(|| {
+                napi::Error::from_reason(format!(
+                    "Unsupported ecmaVersion {year}, expected 2015 to 2022"
+                ))
+            })?,
+        };
+        Ok(LexerOptions {
+            experimental: self.experimental.unwrap_or_default(),
+            ecma_version,
+            strict: self.strict.unwrap_or_default(),
+            module: self.module.unwrap_or_default(),
+            ..LexerOptions::new()
+        })
+    }
+}
+
+/// `kind` is the name of the `ErrorKind`, e.g. `UnterminatedString`
+#[napi(object)]
+pub struct Diagnostic {
+    pub kind: String,
+    pub start: u32,
+    pub end: u32,
+}
+
+/// See [`crate::token_types`], called once by `index.js`
+#[napi]
+#[must_use]
+pub fn token_types() -> Vec<String> {
+    crate::token_types().into_iter().map(String::from).collect()
+}
+
+/// See [`crate::encode_tokens`]
+/// # Errors
+/// Invalid options, or a source too long for `u32` offsets
+#[napi]
+pub fn tokenize_buffer(
+    source: String,
+    options: Option<TokenizeOptions>,
+) -> napi::Result<Uint32Array> {
+    let options = match options {
+        Some(options) => options.lexer_options()?,
+        None => LexerOptions::new(),
+    };
+    let buffer = crate::encode_tokens(&source, options).map_err(napi::Error::from_reason)?;
+    Ok(Uint32Array::new(buffer))
+}
+
+/// See [`crate::check`]
+/// # Errors
+/// A source too long for `u32` offsets
+#[napi]
+pub fn check(source: String) -> napi::Result<Vec<Diagnostic>> {
+    let diagnostics = crate::check(&source).map_err(napi::Error::from_reason)?;
+    let diagnostics = diagnostics
+        .into_iter()
+        .map(|diagnostic| Diagnostic {
+            kind: format!("{:?}", diagnostic.kind),
+            start: diagnostic.start,
+            end: diagnostic.end,
+        })
+        .collect();
+    Ok(diagnostics)
+}
@@ -0,0 +1,145 @@
+//! The lexer for Node.js, `npm run build` then `require("./index.js")`.
+//!
+//! `tokenize(source, options)` returns the tokens of acorn's `tokenizer`, `{ type, value, start,
+//! end }` with `type.label` the label of the acorn token type. The binding returns a single
+//! `Uint32Array` for all the tokens, which `index.js` turns into objects, so that only the source
+//! crosses the boundary as a string. Values are the source text, numbers and strings are not
+//! cooked. `check(source)` returns the errors of the lexer as `{ kind, start, end }`.
+//!
+//! This is the conversion layer, the napi functions are behind the `bindings` feature.
+//! Offsets are `u32` UTF-16 code units, those of JavaScript strings, a source longer than
+//! [`lexer::MAX_U32_SOURCE_LEN`] bytes is an error rather than truncated offsets.
+
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+#[cfg(feature = "bindings")]
+mod bindings;
+
+use lexer::{check_u32_offsets, ErrorKind, Kind, Lexer, LexerOptions};
+
+/// Converts UTF-8 offsets of a source to UTF-16 offsets, counting from the previous offset
+pub struct Utf16Offsets<'a> {
+    source: &'a str,
+    utf8: usize,
+    utf16: usize,
+}
+
+impl<'a> Utf16Offsets<'a> {
+    #[must_use]
+    pub const fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            utf8: 0,
+            utf16: 0,
+        }
+    }
+
+    /// # Panics
+    /// `offset` is not at a char boundary
+    // the callers check the source with `check_u32_offsets`, UTF-16 is not longer than UTF-8
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn convert(&mut self, offset: usize) -> u32 {
+        if offset < self.utf8 {
+            *self = Self::new(self.source);
+        }
+        let text = &self.source[self.utf8..offset];
+        self.utf16 += if text.is_ascii() {
+            text.len()
+        } else {
+            text.chars().map(char::len_utf16).sum()
+        };
+        self.utf8 = offset;
+        self.utf16 as u32
+    }
+}
+
+/// The label of the acorn token type of `kind`, e.g. `name`, `+/-` or `const`
+#[must_use]
+pub const fn acorn_label(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Ident | Kind::Undefined => "name",
+        Kind::Number(_) => "num",
+        Kind::Str(_) => "string",
+        Kind::Regex => "regexp",
+        Kind::Template => "template",
+        Kind::EOF => "eof",
+        Kind::VirtualSemicolon => ";",
+        Kind::Eq => "=",
+        Kind::PlusEq
+        | Kind::MinusEq
+        | Kind::StarEq
+        | Kind::Star2Eq
+        | Kind::SlashEq
+        | Kind::PercentEq
+        | Kind::ShiftLeftEq
+        | Kind::ShiftRightEq
+        | Kind::ShiftRight3Eq
+        | Kind::AmpEq
+        | Kind::PipeEq
+        | Kind::CaretEq
+        | Kind::Amp2Eq
+        | Kind::Pipe2Eq
+        | Kind::Question2Eq => "_=",
+        Kind::Plus2 | Kind::Minus2 => "++/--",
+        Kind::Bang | Kind::Tilde => "!/~",
+        Kind::Eq2 | Kind::Neq | Kind::Eq3 | Kind::Neq2 => "==/!=/===/!==",
+        Kind::LAngle | Kind::RAngle | Kind::LtEq | Kind::GtEq => "</>/<=/>=",
+        Kind::ShiftLeft | Kind::ShiftRight | Kind::ShiftRight3 => "<</>>/>>>",
+        Kind::Plus | Kind::Minus => "+/-",
+        // the keywords and the other punctuators, and `unknown`
+        _ => kind.as_str(),
+    }
+}
+
+/// [`acorn_label`] of every kind, indexed by [`Kind::discriminant`]
+#[must_use]
+pub fn token_types() -> Vec<&'static str> {
+    let mut kinds = Kind::ALL.to_vec();
+    // a kind for each payload of `Number` and `Str`
+    kinds.dedup_by_key(|kind| kind.discriminant());
+    kinds.into_iter().map(acorn_label).collect()
+}
+
+/// `[type, start, end]` for each token of acorn, without the trivia and `EOF`,
+/// `type` an index of [`token_types`]
+/// # Errors
+/// `source` is longer than [`lexer::MAX_U32_SOURCE_LEN`]
+pub fn encode_tokens(source: &str, options: LexerOptions) -> Result<Vec<u32>, String> {
+    check_u32_offsets(source.len())?;
+    let mut offsets = Utf16Offsets::new(source);
+    let mut buffer = vec![];
+    for token in Lexer::with_options(source, options).filter(lexer::Token::is_significant) {
+        let range = token.range();
+        buffer.push(u32::from(token.kind().discriminant()));
+        buffer.push(offsets.convert(range.start));
+        buffer.push(offsets.convert(range.end));
+    }
+    Ok(buffer)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub kind: ErrorKind,
+    pub start: u32,
+    pub end: u32,
+}
+
+/// The errors of the lexer, in source order
+/// # Errors
+/// `source` is longer than [`lexer::MAX_U32_SOURCE_LEN`]
+pub fn check(source: &str) -> Result<Vec<Diagnostic>, String> {
+    check_u32_offsets(source.len())?;
+    let mut lexer = Lexer::new(source);
+    lexer.by_ref().count();
+    let mut offsets = Utf16Offsets::new(source);
+    let diagnostics = lexer
+        .errors_sorted()
+        .into_iter()
+        .map(|error| Diagnostic {
+            kind: error.kind(),
+            start: offsets.convert(error.span().start),
+            end: offsets.convert(error.span().end),
+        })
+        .collect();
+    Ok(diagnostics)
+}
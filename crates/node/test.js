@@ -0,0 +1,24 @@
+// smoke test of the built binding, `npm run build && npm test`
+const assert = require("assert");
+const { tokenize, check } = require("./index.js");
+
+const tokens = tokenize("const é = a + 1; // b", { ecmaVersion: 2022 });
+assert.deepStrictEqual(
+  tokens.map((token) => [token.type.label, token.value, token.start, token.end]),
+  [
+    ["const", "const", 0, 5],
+    ["name", "é", 6, 7],
+    ["=", "=", 8, 9],
+    ["name", "a", 10, 11],
+    ["+/-", "+", 12, 13],
+    ["num", "1", 14, 15],
+    [";", ";", 15, 16],
+  ],
+);
+assert.strictEqual(tokens[1].type, tokenize("b")[0].type);
+assert.throws(() => tokenize("a", { ecmaVersion: 5 }), /ecmaVersion/);
+
+assert.deepStrictEqual(check("a = 'b"), [{ kind: "UnterminatedString", start: 4, end: 6 }]);
+assert.deepStrictEqual(check("a"), []);
+
+console.log("ok");
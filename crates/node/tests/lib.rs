@@ -0,0 +1,57 @@
+use lexer::{ErrorKind, Kind, LexerOptions};
+use node::{acorn_label, check, encode_tokens, token_types, Diagnostic, Utf16Offsets};
+
+#[test]
+fn utf16_offsets() {
+    // 1, 2 and 3 UTF-8 bytes for 1 UTF-16 code unit, then 4 bytes for a surrogate pair
+    let source = "aé中𝐀b";
+    let mut offsets = Utf16Offsets::new(source);
+    assert_eq!(offsets.convert(1), 1);
+    assert_eq!(offsets.convert(3), 2);
+    assert_eq!(offsets.convert(6), 3);
+    assert_eq!(offsets.convert(10), 5);
+    assert_eq!(offsets.convert(11), 6);
+    // from the start again
+    assert_eq!(offsets.convert(3), 2);
+}
+
+#[test]
+fn tokens() {
+    let types = token_types();
+    assert_eq!(types.len(), 118);
+    for kind in [Kind::Ident, Kind::Const, Kind::PlusEq, Kind::Semicolon] {
+        assert_eq!(types[usize::from(kind.discriminant())], acorn_label(kind));
+    }
+
+    let source = "const é = a += 1; // b";
+    let buffer = encode_tokens(source, LexerOptions::new()).unwrap();
+    let tokens = buffer
+        .chunks(3)
+        .map(|token| (types[token[0] as usize], token[1], token[2]))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        tokens,
+        [
+            ("const", 0, 5),
+            ("name", 6, 7),
+            ("=", 8, 9),
+            ("name", 10, 11),
+            ("_=", 12, 14),
+            ("num", 15, 16),
+            (";", 16, 17),
+        ]
+    );
+}
+
+#[test]
+fn diagnostics() {
+    assert_eq!(
+        check("é = 'b").unwrap(),
+        [Diagnostic {
+            kind: ErrorKind::UnterminatedString,
+            start: 4,
+            end: 6,
+        }]
+    );
+    assert_eq!(check("a").unwrap(), []);
+}
@@ -0,0 +1,45 @@
+//! Parse Diagnostics
+
+use std::ops::Range;
+
+use lexer::ErrorKind;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+    message: String,
+    span: Range<usize>,
+}
+
+impl Diagnostic {
+    #[must_use]
+    pub fn new(message: impl Into<String>, span: Range<usize>) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+
+    /// The diagnostic of an error token
+    #[must_use]
+    pub fn from_lexer_error(kind: ErrorKind, span: Range<usize>) -> Self {
+        let message = match kind {
+            ErrorKind::UnexpectedCharacter => "Unexpected character",
+            ErrorKind::UnterminatedString => "Unterminated string",
+            ErrorKind::UnterminatedTemplate => "Unterminated template",
+            ErrorKind::InvalidRegex => "Invalid regular expression",
+            ErrorKind::InvalidIdentifier => "Invalid identifier",
+            ErrorKind::InvalidNumber => "Invalid number",
+        };
+        Self::new(message, span)
+    }
+
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    #[must_use]
+    pub const fn span(&self) -> &Range<usize> {
+        &self.span
+    }
+}
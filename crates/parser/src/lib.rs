@@ -0,0 +1,9 @@
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+mod diagnostic;
+mod parser;
+mod source;
+
+pub use crate::diagnostic::*;
+pub use crate::parser::*;
+pub use crate::source::*;
@@ -0,0 +1,657 @@
+//! Expression Parser
+
+use ast::{
+    Argument, ArrayExpression, AssignmentExpression, AssignmentOperator, BinaryExpression,
+    BinaryOperator, BooleanLiteral, CallExpression, ConditionalExpression, Expression, Identifier,
+    InvalidExpression, LogicalExpression, LogicalOperator, MemberExpression, NewExpression,
+    NullLiteral, NumberLiteral, ObjectExpression, ObjectProperty, ParenthesizedExpression,
+    Property, RegExp, RegexLiteral, SequenceExpression, Span, SpreadElement, StringLiteral,
+    TaggedTemplateExpression, TemplateLiteral, ThisExpression, UnaryExpression, UnaryOperator,
+    UpdateExpression, UpdateOperator,
+};
+use lexer::{Kind, LexerOptions};
+
+use crate::diagnostic::Diagnostic;
+use crate::source::TokenSource;
+
+type Result<T> = std::result::Result<T, Diagnostic>;
+
+#[derive(Debug)]
+#[allow(clippy::module_name_repetitions)]
+pub struct ParserReturn {
+    /// `None` after a syntax error
+    pub expression: Option<Expression>,
+
+    /// Error tokens in source order, then the syntax error
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Parse `source` as a single expression
+#[must_use]
+pub fn parse_expression(source: &str) -> ParserReturn {
+    Parser::new(source).parse_expression()
+}
+
+/// Recursive descent parser of expressions, as in script code outside of functions,
+/// where `await` and `yield` are identifiers. Error tokens are reported and parsed as
+/// [`InvalidExpression`], the first syntax error stops parsing.
+/// With [`LexerOptions::module`], the words of [`Kind::is_reserved_in_module`] are reported
+/// as identifiers, and parsed as such.
+pub struct Parser<'a> {
+    tokens: TokenSource<'a>,
+
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Parser<'a> {
+    #[must_use]
+    pub const fn new(source: &'a str) -> Self {
+        Self::with_options(source, LexerOptions::new())
+    }
+
+    #[must_use]
+    pub const fn with_options(source: &'a str, options: LexerOptions) -> Self {
+        Self {
+            tokens: TokenSource::with_options(source, options),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn parse_expression(mut self) -> ParserReturn {
+        let result = self.expression().and_then(|expression| {
+            self.expect(Kind::EOF)?;
+            Ok(expression)
+        });
+        let expression = result.map_err(|e| self.diagnostics.push(e)).ok();
+        ParserReturn {
+            expression,
+            diagnostics: self.diagnostics,
+        }
+    }
+
+    /// Section 13.16 Comma Operator
+    fn expression(&mut self) -> Result<Expression> {
+        let first = self.assignment()?;
+        if self.tokens.kind() != Kind::Comma {
+            return Ok(first);
+        }
+        let mut expressions = vec![first];
+        while self.eat(Kind::Comma) {
+            expressions.push(self.assignment()?);
+        }
+        let span = expressions[0]
+            .span()
+            .to(expressions[expressions.len() - 1].span());
+        Ok(SequenceExpression { span, expressions }.into())
+    }
+
+    /// Section 13.15 Assignment Operators
+    fn assignment(&mut self) -> Result<Expression> {
+        let left = self.conditional()?;
+        let operator = match AssignmentOperator::from_kind(self.tokens.kind()) {
+            Some(operator) => operator,
+            None => return Ok(left),
+        };
+        if !is_assignment_target(&left, operator == AssignmentOperator::Assign) {
+            let span = left.span().range();
+            return Err(Diagnostic::new("Invalid assignment target", span));
+        }
+        self.tokens.bump();
+        // right associative
+        let right = self.assignment()?;
+        let span = left.span().to(right.span());
+        Ok(AssignmentExpression {
+            span,
+            left,
+            operator,
+            right,
+        }
+        .into())
+    }
+
+    /// Section 13.14 Conditional Operator
+    fn conditional(&mut self) -> Result<Expression> {
+        let test = self.binary(1)?;
+        if !self.eat(Kind::Question) {
+            return Ok(test);
+        }
+        let consequent = self.assignment()?;
+        self.expect(Kind::Colon)?;
+        let alternate = self.assignment()?;
+        let span = test.span().to(alternate.span());
+        Ok(ConditionalExpression {
+            span,
+            test,
+            consequent,
+            alternate,
+        }
+        .into())
+    }
+
+    /// Binary operators binding at least as tight as `min_precedence`, see
+    /// [`Kind::binary_precedence`]
+    fn binary(&mut self, min_precedence: u8) -> Result<Expression> {
+        let mut left = self.unary()?;
+        loop {
+            // the lexer reads a regex after e.g. a postfix `++`
+            if matches!(self.tokens.kind(), Kind::Regex | Kind::Unknown)
+                && self.tokens.text().starts_with('/')
+            {
+                self.tokens.relex_division();
+            }
+            let operator = self.tokens.kind();
+            let precedence = match operator.binary_precedence() {
+                Some(precedence) if precedence >= min_precedence => precedence,
+                _ => return Ok(left),
+            };
+            let start = self.start();
+            if operator == Kind::Star2 && matches!(left, Expression::UnaryExpression(_)) {
+                let message = "Parenthesize the unary expression on the left of `**`";
+                return Err(Diagnostic::new(message, left.span().range()));
+            }
+            if mixes_coalesce(operator, &left) {
+                let message = "Parenthesize `??` mixed with `||` or `&&`";
+                return Err(Diagnostic::new(message, start..start + 2));
+            }
+            self.tokens.bump();
+            let right = if operator == Kind::Star2 {
+                self.binary(precedence)?
+            } else {
+                self.binary(precedence + 1)?
+            };
+            if mixes_coalesce(operator, &right) {
+                let message = "Parenthesize `??` mixed with `||` or `&&`";
+                return Err(Diagnostic::new(message, right.span().range()));
+            }
+            left = binary_expression(operator, left, right);
+        }
+    }
+
+    /// Section 13.5 Unary Operators and 13.4 Update Expressions
+    fn unary(&mut self) -> Result<Expression> {
+        let kind = self.tokens.kind();
+        let start = self.start();
+        if let Some(operator) = UnaryOperator::from_kind(kind) {
+            self.tokens.bump();
+            let argument = self.unary()?;
+            let span = Span::new(start, argument.span().end);
+            return Ok(UnaryExpression {
+                span,
+                operator,
+                argument,
+            }
+            .into());
+        }
+        let operator = match UpdateOperator::from_kind(kind) {
+            Some(operator) => operator,
+            None => return self.postfix(),
+        };
+        self.tokens.bump();
+        let argument = self.unary()?;
+        if !is_simple_target(&argument) {
+            let span = argument.span().range();
+            return Err(Diagnostic::new("Invalid update target", span));
+        }
+        let span = Span::new(start, argument.span().end);
+        Ok(UpdateExpression {
+            span,
+            operator,
+            prefix: true,
+            argument,
+        }
+        .into())
+    }
+
+    /// A postfix `++` or `--`, not after a line break
+    fn postfix(&mut self) -> Result<Expression> {
+        let argument = self.left_hand_side()?;
+        let operator = match UpdateOperator::from_kind(self.tokens.kind()) {
+            Some(operator) if !self.tokens.peek().newline_before => operator,
+            _ => return Ok(argument),
+        };
+        if !is_simple_target(&argument) {
+            let span = argument.span().range();
+            return Err(Diagnostic::new("Invalid update target", span));
+        }
+        let end = self.tokens.peek().token.range().end;
+        self.tokens.bump();
+        let span = Span::new(argument.span().start, end);
+        Ok(UpdateExpression {
+            span,
+            operator,
+            prefix: false,
+            argument,
+        }
+        .into())
+    }
+
+    /// Section 13.3 Left-Hand-Side Expressions, member accesses and calls
+    fn left_hand_side(&mut self) -> Result<Expression> {
+        let mut expression = if self.tokens.kind() == Kind::New {
+            self.new_expression()?
+        } else {
+            self.primary()?
+        };
+        loop {
+            expression = match self.tokens.kind() {
+                Kind::LParen => self.call(expression, false)?,
+                Kind::QuestionDot => {
+                    self.tokens.bump();
+                    if self.tokens.kind() == Kind::LParen {
+                        self.call(expression, true)?
+                    } else {
+                        self.member(expression, true)?
+                    }
+                }
+                Kind::Dot | Kind::LBrack | Kind::Template => self.member(expression, false)?,
+                _ => return Ok(expression),
+            };
+        }
+    }
+
+    /// `new a.b(c)`, the callee is a member expression without calls
+    fn new_expression(&mut self) -> Result<Expression> {
+        let start = self.start();
+        self.expect(Kind::New)?;
+        let mut callee = if self.tokens.kind() == Kind::New {
+            self.new_expression()?
+        } else {
+            self.primary()?
+        };
+        while matches!(
+            self.tokens.kind(),
+            Kind::Dot | Kind::LBrack | Kind::Template
+        ) {
+            callee = self.member(callee, false)?;
+        }
+        let (arguments, end) = if self.tokens.kind() == Kind::LParen {
+            self.arguments()?
+        } else {
+            (Vec::new(), callee.span().end)
+        };
+        Ok(NewExpression {
+            span: Span::new(start, end),
+            callee,
+            arguments,
+        }
+        .into())
+    }
+
+    /// `(b)` after `callee`, or after its `?.` when `optional`
+    fn call(&mut self, callee: Expression, optional: bool) -> Result<Expression> {
+        let (arguments, end) = self.arguments()?;
+        Ok(CallExpression {
+            span: Span::new(callee.span().start, end),
+            callee,
+            arguments,
+            optional,
+        }
+        .into())
+    }
+
+    /// `.b`, `[b]` or a tagged template after `object`, or after its `?.` when `optional`
+    fn member(&mut self, object: Expression, optional: bool) -> Result<Expression> {
+        let start = object.span().start;
+        let kind = self.tokens.kind();
+        if kind == Kind::Template && !optional {
+            let quasi = self.template();
+            return Ok(TaggedTemplateExpression {
+                span: Span::new(start, quasi.span.end),
+                tag: object,
+                quasi,
+            }
+            .into());
+        }
+        if kind == Kind::LBrack {
+            self.tokens.bump();
+            let property = self.expression()?;
+            let end = self.expect(Kind::RBrack)?;
+            return Ok(MemberExpression {
+                span: Span::new(start, end),
+                object,
+                property,
+                computed: true,
+                optional,
+            }
+            .into());
+        }
+        if !optional {
+            self.expect(Kind::Dot)?;
+        }
+        let property = self.identifier_name()?;
+        Ok(MemberExpression {
+            span: Span::new(start, property.span().end),
+            object,
+            property,
+            computed: false,
+            optional,
+        }
+        .into())
+    }
+
+    /// `(a, ...b)` with the end of the `)`
+    fn arguments(&mut self) -> Result<(Vec<Argument>, usize)> {
+        self.expect(Kind::LParen)?;
+        let mut arguments = Vec::new();
+        while self.tokens.kind() != Kind::RParen {
+            arguments.push(self.argument()?);
+            if !self.eat(Kind::Comma) {
+                break;
+            }
+        }
+        let end = self.expect(Kind::RParen)?;
+        Ok((arguments, end))
+    }
+
+    /// An argument or an element of an array
+    fn argument(&mut self) -> Result<Argument> {
+        if self.tokens.kind() == Kind::Dot3 {
+            self.spread_element().map(Argument::SpreadElement)
+        } else {
+            self.assignment().map(Argument::Expression)
+        }
+    }
+
+    /// `...a`
+    fn spread_element(&mut self) -> Result<SpreadElement> {
+        let start = self.start();
+        self.expect(Kind::Dot3)?;
+        let argument = self.assignment()?;
+        Ok(SpreadElement {
+            span: Span::new(start, argument.span().end),
+            argument,
+        })
+    }
+
+    /// Section 13.2 Primary Expression
+    fn primary(&mut self) -> Result<Expression> {
+        // the lexer reads a division e.g. after a comment
+        if matches!(self.tokens.kind(), Kind::Slash | Kind::SlashEq) {
+            self.tokens.relex_regex();
+        }
+        let token = self.tokens.peek();
+        let kind = *token.token.kind();
+        let span = token.token.span();
+        let error = token.error;
+        let raw = self.tokens.text().to_string();
+        let expression = match kind {
+            // `implements` to `public` are lexed as keywords in strict mode and module code only
+            _ if kind == Kind::Ident || kind.is_reserved_in_module() => {
+                let options = self.tokens.options();
+                let message = if options.module && kind.is_reserved_in_module() {
+                    Some(format!("`{kind}` is reserved in module code"))
+                } else if options.strict && kind.is_reserved_in_strict_only() {
+                    Some(format!("`{kind}` is reserved in strict mode code"))
+                } else {
+                    None
+                };
+                if let Some(message) = message {
+                    self.diagnostics
+                        .push(Diagnostic::new(message, span.range()));
+                }
+                Identifier { span, name: raw }.into()
+            }
+            Kind::This => ThisExpression { span }.into(),
+            Kind::Null => NullLiteral { span }.into(),
+            Kind::True | Kind::False => BooleanLiteral {
+                span,
+                value: kind == Kind::True,
+            }
+            .into(),
+            Kind::Number(number) => NumberLiteral {
+                span,
+                raw,
+                kind: number,
+            }
+            .into(),
+            Kind::Str(_) => StringLiteral { span, raw }.into(),
+            Kind::Regex => RegexLiteral {
+                span,
+                regex: RegExp::from_raw(&raw),
+                raw,
+            }
+            .into(),
+            Kind::Template => return Ok(self.template().into()),
+            Kind::LParen => return self.parenthesized(),
+            Kind::LBrack => return self.array(),
+            Kind::LCurly => return self.object(),
+            Kind::Unknown => {
+                if let Some(error) = error {
+                    let diagnostic = Diagnostic::from_lexer_error(error, span.range());
+                    self.diagnostics.push(diagnostic);
+                }
+                InvalidExpression { span }.into()
+            }
+            _ => return Err(self.unexpected("an expression")),
+        };
+        self.tokens.bump();
+        Ok(expression)
+    }
+
+    /// A template is a single token, its substitutions are not parsed
+    fn template(&mut self) -> TemplateLiteral {
+        let span = self.tokens.peek().token.span();
+        let raw = self.tokens.text().to_string();
+        self.tokens.bump();
+        TemplateLiteral { span, raw }
+    }
+
+    fn parenthesized(&mut self) -> Result<Expression> {
+        let start = self.start();
+        self.expect(Kind::LParen)?;
+        let expression = self.expression()?;
+        let end = self.expect(Kind::RParen)?;
+        Ok(ParenthesizedExpression {
+            span: Span::new(start, end),
+            expression,
+        }
+        .into())
+    }
+
+    /// `[a, , ...b]`
+    fn array(&mut self) -> Result<Expression> {
+        let start = self.start();
+        self.expect(Kind::LBrack)?;
+        let mut elements = Vec::new();
+        while self.tokens.kind() != Kind::RBrack {
+            if self.eat(Kind::Comma) {
+                elements.push(None);
+                continue;
+            }
+            elements.push(Some(self.argument()?));
+            if !self.eat(Kind::Comma) {
+                break;
+            }
+        }
+        let end = self.expect(Kind::RBrack)?;
+        Ok(ArrayExpression {
+            span: Span::new(start, end),
+            elements,
+        }
+        .into())
+    }
+
+    /// `{ a: b, [c]: d, e, ...f }`
+    fn object(&mut self) -> Result<Expression> {
+        let start = self.start();
+        self.expect(Kind::LCurly)?;
+        let mut properties = Vec::new();
+        while self.tokens.kind() != Kind::RCurly {
+            properties.push(self.property()?);
+            if !self.eat(Kind::Comma) {
+                break;
+            }
+        }
+        let end = self.expect(Kind::RCurly)?;
+        Ok(ObjectExpression {
+            span: Span::new(start, end),
+            properties,
+        }
+        .into())
+    }
+
+    fn property(&mut self) -> Result<ObjectProperty> {
+        let start = self.start();
+        let kind = self.tokens.kind();
+        if kind == Kind::Dot3 {
+            return self.spread_element().map(ObjectProperty::SpreadElement);
+        }
+        let computed = kind == Kind::LBrack;
+        let key = match kind {
+            Kind::LBrack => {
+                self.tokens.bump();
+                let key = self.assignment()?;
+                self.expect(Kind::RBrack)?;
+                key
+            }
+            Kind::Str(_) | Kind::Number(_) => self.primary()?,
+            _ => self.identifier_name()?,
+        };
+        if !computed
+            && matches!(key, Expression::Identifier(_))
+            && matches!(self.tokens.kind(), Kind::Comma | Kind::RCurly)
+        {
+            // a reference, unlike the name of a property any keyword can be
+            let options = self.tokens.options();
+            let is_reference = kind == Kind::Ident
+                || (kind.is_reserved_in_module()
+                    && !options.module
+                    && !(options.strict && kind.is_reserved_in_strict_only()));
+            if !is_reference {
+                let message = format!("Unexpected keyword `{kind}` in a shorthand property");
+                return Err(Diagnostic::new(message, key.span().range()));
+            }
+            return Ok(ObjectProperty::Property(Property {
+                span: key.span(),
+                value: key.clone(),
+                key,
+                computed,
+                shorthand: true,
+            }));
+        }
+        self.expect(Kind::Colon)?;
+        let value = self.assignment()?;
+        Ok(ObjectProperty::Property(Property {
+            span: Span::new(start, value.span().end),
+            key,
+            value,
+            computed,
+            shorthand: false,
+        }))
+    }
+
+    /// An identifier or a keyword, e.g. the property of `a.if`
+    fn identifier_name(&mut self) -> Result<Expression> {
+        let kind = self.tokens.kind();
+        let text = self.tokens.text();
+        let is_name =
+            kind == Kind::Ident || (kind.as_str() == text && text.starts_with(char::is_alphabetic));
+        if !is_name {
+            return Err(self.unexpected("an identifier"));
+        }
+        let span = self.tokens.peek().token.span();
+        self.tokens.bump();
+        let name = text.to_string();
+        Ok(Identifier { span, name }.into())
+    }
+
+    /// Start of the current token
+    fn start(&mut self) -> usize {
+        self.tokens.peek().token.range().start
+    }
+
+    /// Consume the current token if it is `kind`
+    fn eat(&mut self, kind: Kind) -> bool {
+        let matched = self.tokens.kind() == kind;
+        if matched {
+            self.tokens.bump();
+        }
+        matched
+    }
+
+    /// Consume the current token of `kind`, returning its end
+    fn expect(&mut self, kind: Kind) -> Result<usize> {
+        if self.tokens.kind() != kind {
+            let expected = if kind == Kind::EOF {
+                kind.to_string()
+            } else {
+                format!("`{kind}`")
+            };
+            return Err(self.unexpected(&expected));
+        }
+        let end = self.tokens.peek().token.range().end;
+        self.tokens.bump();
+        Ok(end)
+    }
+
+    /// A syntax error at the current token, or its lexer error for an error token
+    fn unexpected(&mut self, expected: &str) -> Diagnostic {
+        let token = self.tokens.peek();
+        let (kind, span) = (*token.token.kind(), token.token.range());
+        if let Some(error) = token.error {
+            return Diagnostic::from_lexer_error(error, span);
+        }
+        let found = if kind == Kind::EOF {
+            kind.to_string()
+        } else {
+            format!("`{}`", self.tokens.text())
+        };
+        Diagnostic::new(format!("Expected {expected} but found {found}"), span)
+    }
+}
+
+/// A `LogicalExpression` for `||`, `&&` and `??`, a `BinaryExpression` otherwise
+fn binary_expression(operator: Kind, left: Expression, right: Expression) -> Expression {
+    let span = left.span().to(right.span());
+    if let Some(operator) = LogicalOperator::from_kind(operator) {
+        return LogicalExpression {
+            span,
+            left,
+            operator,
+            right,
+        }
+        .into();
+    }
+    BinaryExpression {
+        span,
+        left,
+        operator: BinaryOperator::from_kind(operator).expect("a binary operator"),
+        right,
+    }
+    .into()
+}
+
+/// Identifiers and member expressions, also parenthesized
+fn is_simple_target(expression: &Expression) -> bool {
+    match expression {
+        Expression::Identifier(_) => true,
+        Expression::MemberExpression(member) => !member.optional,
+        Expression::ParenthesizedExpression(parenthesized) => {
+            is_simple_target(&parenthesized.expression)
+        }
+        _ => false,
+    }
+}
+
+/// With `destructuring` for `=`, arrays and objects too, which are patterns
+fn is_assignment_target(expression: &Expression, destructuring: bool) -> bool {
+    is_simple_target(expression)
+        || (destructuring
+            && matches!(
+                expression,
+                Expression::ArrayExpression(_) | Expression::ObjectExpression(_)
+            ))
+}
+
+/// Whether `??` and `||` or `&&` are operator and operand without parentheses
+fn mixes_coalesce(operator: Kind, operand: &Expression) -> bool {
+    match operand {
+        Expression::LogicalExpression(logical) => {
+            let coalesce = logical.operator == LogicalOperator::Coalesce;
+            (operator == Kind::Question2 && !coalesce)
+                || (matches!(operator, Kind::Pipe2 | Kind::Amp2) && coalesce)
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,175 @@
+//! Significant tokens for the parser, skipping trivia and re-lexing a `/` on demand
+
+use lexer::{ErrorKind, Kind, Lexer, LexerOptions, Token};
+
+/// A significant token with what the parser needs to know about the trivia before it
+#[derive(Debug, PartialEq)]
+#[allow(clippy::module_name_repetitions)]
+pub struct SourceToken {
+    pub token: Token,
+
+    /// Whether a line terminator is in the trivia before the token, for ASI and the
+    /// restricted productions
+    pub newline_before: bool,
+
+    /// Error of an `Unknown` token
+    pub error: Option<ErrorKind>,
+}
+
+/// Position to [`TokenSource::rewind`] to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint(usize);
+
+/// Tokens are lexed lazily and kept, so that the parser can look ahead and rewind.
+///
+/// The lexer guesses whether a `/` starts a regex from the previous token, the parser knows.
+/// [`TokenSource::relex_regex`] and [`TokenSource::relex_division`] fix a wrong guess
+/// by lexing again from the current token, discarding the tokens lexed after it.
+#[allow(clippy::module_name_repetitions)]
+pub struct TokenSource<'a> {
+    source: &'a str,
+
+    lexer: Lexer<'a>,
+
+    /// Options of `lexer`, for lexing again
+    options: LexerOptions,
+
+    /// Offset of the source of `lexer`
+    base: usize,
+
+    tokens: Vec<SourceToken>,
+
+    /// Index of the current token in `tokens`
+    index: usize,
+}
+
+impl<'a> TokenSource<'a> {
+    #[must_use]
+    pub const fn new(source: &'a str) -> Self {
+        Self::with_options(source, LexerOptions::new())
+    }
+
+    #[must_use]
+    pub const fn with_options(source: &'a str, options: LexerOptions) -> Self {
+        Self {
+            source,
+            lexer: Lexer::with_options(source, options),
+            options,
+            base: 0,
+            tokens: Vec::new(),
+            index: 0,
+        }
+    }
+
+    #[must_use]
+    pub const fn options(&self) -> LexerOptions {
+        self.options
+    }
+
+    #[must_use]
+    pub const fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// The current token, `EOF` at the end
+    pub fn peek(&mut self) -> &SourceToken {
+        self.nth(0)
+    }
+
+    /// The `n`th token after the current one, `EOF` past the end
+    pub fn nth(&mut self, n: usize) -> &SourceToken {
+        while self.tokens.len() <= self.index + n {
+            match self.lex() {
+                Some(token) => self.tokens.push(token),
+                None => break,
+            }
+        }
+        // `EOF` past the end
+        &self.tokens[(self.index + n).min(self.tokens.len() - 1)]
+    }
+
+    /// Kind of the current token
+    pub fn kind(&mut self) -> Kind {
+        *self.peek().token.kind()
+    }
+
+    /// Text of the current token, empty for `EOF`
+    pub fn text(&mut self) -> &'a str {
+        let source = self.source;
+        source.get(self.peek().token.range()).unwrap_or_default()
+    }
+
+    /// Move past the current token, staying at `EOF`
+    pub fn bump(&mut self) {
+        if self.kind() != Kind::EOF {
+            self.index += 1;
+        }
+    }
+
+    #[must_use]
+    pub const fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.index)
+    }
+
+    /// Go back to `checkpoint`, the tokens after it are kept for lexing them again
+    pub fn rewind(&mut self, checkpoint: Checkpoint) {
+        self.index = checkpoint.0;
+    }
+
+    /// Lex the current `/` or `/=` again as the start of a regex
+    pub fn relex_regex(&mut self) {
+        let start = self.peek().token.range().start;
+        self.relex_from(start);
+    }
+
+    /// Lex the current regex, or the `Unknown` of an unterminated one, again as `/` or `/=`
+    pub fn relex_division(&mut self) {
+        let newline_before = self.peek().newline_before;
+        let start = self.peek().token.range().start;
+        let (kind, len) = if self.source[start..].starts_with("/=") {
+            (Kind::SlashEq, 2)
+        } else {
+            (Kind::Slash, 1)
+        };
+        // an expression follows the operator, as the lexer starts
+        self.relex_from(start + len);
+        self.tokens.push(SourceToken {
+            token: Token::new(kind, start, len),
+            newline_before,
+            error: None,
+        });
+    }
+
+    /// Discard the tokens from the current one and lex from `offset` on
+    fn relex_from(&mut self, offset: usize) {
+        self.tokens.truncate(self.index);
+        self.lexer = Lexer::with_options(&self.source[offset..], self.options);
+        self.base = offset;
+    }
+
+    /// The next significant token of the lexer
+    fn lex(&mut self) -> Option<SourceToken> {
+        let mut newline_before = false;
+        loop {
+            let token = self.lexer.next()?;
+            let range = token.range();
+            let token = Token::new(*token.kind(), self.base + range.start, token.len());
+            if token.is_trivia() {
+                // a line terminator or a multi line comment with one
+                newline_before |= self.source[token.range()]
+                    .contains(|c| matches!(c, '\n' | '\r' | '\u{2028}' | '\u{2029}'));
+                continue;
+            }
+            let error = if token.is_unknown() {
+                self.lexer.errors().last().map(lexer::LexerError::kind)
+            } else {
+                None
+            };
+            return Some(SourceToken {
+                token,
+                newline_before,
+                error,
+            });
+        }
+    }
+}
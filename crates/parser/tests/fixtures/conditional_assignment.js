@@ -0,0 +1,6 @@
+a ? b : c ? d : e
+a = b = c
+a.b += 1
+[a, b] = c
+a ??= b ? c : d
+a = b, c
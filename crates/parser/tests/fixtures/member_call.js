@@ -0,0 +1,8 @@
+a.b.c
+a[b](c, ...d)
+a?.b?.[c]?.(d)
+new A.B(c)
+new new A()()
+new A
+tag`x`
+a.if.class
@@ -0,0 +1,11 @@
+a
+this
+null
+true
+1.5
+'s'
+/re/g
+`t ${x}`
+[a, , ...b]
+{ a, b: 1, [c]: d, 'e': f, ...g }
+(a, b)
@@ -0,0 +1,3 @@
+a++ / b / c
+a = /* c */ /re/
+x[a-- / 2] / 1
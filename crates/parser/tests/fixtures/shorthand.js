@@ -0,0 +1,4 @@
+{ a, let, yield }
+{ if }
+{ this }
+{ a, true }
@@ -0,0 +1,9 @@
+-a * +b
+!a && b || c
+a + b * c - d
+a ** b ** c
+(-a) ** b
+a < b == c instanceof d
+a ?? (b || c)
+typeof a in b
+++a + b--
@@ -0,0 +1,290 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use ast::{Expression, Identifier, NumberLiteral, RegexLiteral, StringLiteral, Visit};
+use lexer::{Kind, LexerOptions};
+use parser::{parse_expression, Parser, TokenSource};
+
+/// `tests/fixtures/<name>.js` inputs of an expression per line,
+/// each with its snapshot `tests/snapshots/lib__<name>.snap`
+fn fixtures() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+/// The AST of `source` then its diagnostics
+fn render(source: &str) -> String {
+    let ret = parse_expression(source);
+    let mut s = format!("{:#?}\n", ret.expression);
+    for diagnostic in &ret.diagnostics {
+        s.push_str(&format!(
+            "{} {:?}\n",
+            diagnostic.message(),
+            diagnostic.span()
+        ));
+    }
+    s
+}
+
+fn diagnostics(source: &str) -> Vec<(String, &str)> {
+    parse_expression(source)
+        .diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let text = source.get(diagnostic.span().clone()).unwrap_or_default();
+            (diagnostic.message().to_string(), text)
+        })
+        .collect()
+}
+
+/// A snapshot of the AST of each line of each fixture, approve a change with `cargo insta review`
+#[test]
+fn fixture_snapshots() {
+    let mut inputs = fs::read_dir(fixtures())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    inputs.sort();
+    assert!(!inputs.is_empty());
+    for input in inputs {
+        let name = input.file_stem().unwrap().to_str().unwrap();
+        let asts = fs::read_to_string(&input)
+            .unwrap()
+            .lines()
+            .map(|line| format!("{line}\n{}", render(line)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        insta::assert_snapshot!(name, asts);
+    }
+}
+
+#[test]
+fn error_tokens() {
+    // reported, parsing goes on
+    let ret = parse_expression("a + @ + \"b");
+    assert!(ret.expression.is_some());
+    assert_eq!(
+        diagnostics("a + @ + \"b"),
+        [
+            ("Unexpected character".to_string(), "@"),
+            ("Unterminated string".to_string(), "\"b"),
+        ]
+    );
+    assert_eq!(
+        diagnostics("a @ b"),
+        [("Unexpected character".to_string(), "@")]
+    );
+}
+
+#[test]
+fn syntax_errors() {
+    for (source, message, text) in [
+        ("1 +", "Expected an expression but found end of file", ""),
+        ("(a", "Expected `)` but found end of file", ""),
+        ("a b", "Expected end of file but found `b`", "b"),
+        ("a.1", "Expected an identifier but found `1`", "1"),
+        (
+            "-a ** b",
+            "Parenthesize the unary expression on the left of `**`",
+            "-a",
+        ),
+        (
+            "a ?? b || c",
+            "Parenthesize `??` mixed with `||` or `&&`",
+            "b || c",
+        ),
+        (
+            "a || b ?? c",
+            "Parenthesize `??` mixed with `||` or `&&`",
+            "??",
+        ),
+        ("1 = a", "Invalid assignment target", "1"),
+        ("[a] += b", "Invalid assignment target", "[a]"),
+        ("a?.b = c", "Invalid assignment target", "a?.b"),
+        ("++a()", "Invalid update target", "a()"),
+    ] {
+        let ret = parse_expression(source);
+        assert_eq!(ret.expression, None, "{source}");
+        assert_eq!(
+            diagnostics(source),
+            [(message.to_string(), text)],
+            "{source}"
+        );
+    }
+}
+
+#[test]
+fn await_in_module() {
+    let module = LexerOptions {
+        module: true,
+        ..LexerOptions::new()
+    };
+    // an identifier in a script
+    assert!(parse_expression("await + 1").diagnostics.is_empty());
+    let ret = Parser::with_options("await + yield + public", module).parse_expression();
+    assert!(ret.expression.is_some());
+    let diagnostics = ret
+        .diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.message(), diagnostic.span().clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        diagnostics,
+        [
+            ("`await` is reserved in module code", 0..5),
+            ("`yield` is reserved in module code", 8..13),
+            ("`public` is reserved in module code", 16..22),
+        ]
+    );
+    // a property name
+    let ret = Parser::with_options("a.await", module).parse_expression();
+    assert!(ret.diagnostics.is_empty());
+    // not a shorthand property
+    let ret = Parser::with_options("{ await }", module).parse_expression();
+    assert_eq!(ret.expression, None);
+    assert_eq!(
+        ret.diagnostics[0].message(),
+        "Unexpected keyword `await` in a shorthand property"
+    );
+}
+
+#[test]
+fn reserved_in_strict_mode() {
+    let strict = LexerOptions {
+        strict: true,
+        ..LexerOptions::new()
+    };
+    // an identifier in sloppy mode
+    assert!(parse_expression("public + let").diagnostics.is_empty());
+    let ret = Parser::with_options("await + yield + public", strict).parse_expression();
+    assert!(ret.expression.is_some());
+    let diagnostics = ret
+        .diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.message(), diagnostic.span().clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        diagnostics,
+        [
+            ("`yield` is reserved in strict mode code", 8..13),
+            ("`public` is reserved in strict mode code", 16..22),
+        ]
+    );
+    // `await` is only reserved in module code
+    let ret = Parser::with_options("{ await }", strict).parse_expression();
+    assert!(ret.diagnostics.is_empty());
+    let ret = Parser::with_options("{ let }", strict).parse_expression();
+    assert_eq!(ret.expression, None);
+    assert_eq!(
+        ret.diagnostics[0].message(),
+        "Unexpected keyword `let` in a shorthand property"
+    );
+}
+
+#[test]
+fn postfix_after_line_break() {
+    assert_eq!(
+        diagnostics("a\n++b"),
+        [("Expected end of file but found `++`".to_string(), "++")]
+    );
+    let ret = parse_expression("a /* \n */ + b");
+    assert!(matches!(
+        ret.expression.unwrap(),
+        Expression::BinaryExpression(_)
+    ));
+}
+
+#[test]
+fn token_source() {
+    let source = "a /* c */ / b";
+    let mut tokens = TokenSource::new(source);
+    assert_eq!(tokens.kind(), Kind::Ident);
+    let checkpoint = tokens.checkpoint();
+    tokens.bump();
+    assert_eq!(tokens.text(), "/");
+    assert_eq!(tokens.nth(1).token.kind(), &Kind::Ident);
+    assert_eq!(tokens.nth(5).token.kind(), &Kind::EOF);
+
+    // a regex after all, up to the end
+    tokens.relex_regex();
+    assert_eq!(tokens.kind(), Kind::Unknown);
+    assert_eq!(tokens.text(), "/ b");
+    tokens.relex_division();
+    assert_eq!(tokens.kind(), Kind::Slash);
+    tokens.bump();
+    assert_eq!(tokens.text(), "b");
+    assert!(!tokens.peek().newline_before);
+
+    tokens.rewind(checkpoint);
+    assert_eq!(tokens.text(), "a");
+    tokens.bump();
+    assert_eq!(tokens.kind(), Kind::Slash);
+    tokens.bump();
+    tokens.bump();
+    tokens.bump();
+    assert_eq!(tokens.kind(), Kind::EOF);
+    assert_eq!(tokens.peek().token.range().start, source.len());
+}
+
+/// The identifiers and the number of each kind of literal
+#[derive(Default)]
+struct Collect {
+    identifiers: Vec<Identifier>,
+    numbers: usize,
+    strings: usize,
+    regexes: usize,
+}
+
+impl Visit for Collect {
+    fn visit_identifier(&mut self, identifier: &Identifier) {
+        self.identifiers.push(identifier.clone());
+    }
+
+    fn visit_number_literal(&mut self, _literal: &NumberLiteral) {
+        self.numbers += 1;
+    }
+
+    fn visit_string_literal(&mut self, _literal: &StringLiteral) {
+        self.strings += 1;
+    }
+
+    fn visit_regex_literal(&mut self, _literal: &RegexLiteral) {
+        self.regexes += 1;
+    }
+}
+
+#[test]
+fn visit() {
+    let source = "a.b(c, ...[1, 'd']) ? { e, [f]: /g/ } : h++";
+    let mut collect = Collect::default();
+    collect.visit_expression(&parse_expression(source).expression.unwrap());
+    let names = collect
+        .identifiers
+        .iter()
+        .map(|identifier| identifier.name.as_str())
+        .collect::<Vec<_>>();
+    // the shorthand `e` is both the key and the value
+    assert_eq!(names, ["a", "b", "c", "e", "e", "f", "h"]);
+    assert_eq!(
+        (collect.numbers, collect.strings, collect.regexes),
+        (1, 1, 1)
+    );
+
+    // every identifier of the fixtures spans its name
+    let mut count = 0;
+    for input in fs::read_dir(fixtures()).unwrap() {
+        let code = fs::read_to_string(input.unwrap().path()).unwrap();
+        for line in code.lines() {
+            let mut collect = Collect::default();
+            if let Some(expression) = parse_expression(line).expression {
+                collect.visit_expression(&expression);
+            }
+            for identifier in collect.identifiers {
+                assert_eq!(line[identifier.span.range()], identifier.name, "{line}");
+                count += 1;
+            }
+        }
+    }
+    assert!(count > 0);
+}
@@ -0,0 +1,154 @@
+//! The lexer for JavaScript, `wasm-pack build crates/wasm --target web` for a browser.
+//!
+//! `tokenize(source, options)` returns `{ tokens, errors }`, with tokens shaped like those of
+//! esprima's `tokenize`, `{ type, value, start, end }` and the `quote` of a string, and the errors of the lexer as
+//! `{ kind, start, end }` objects. Only invalid options throw, an `Error` rather than a string.
+//!
+//! This crate only depends on `lexer`, which builds for `wasm32-unknown-unknown`.
+
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+use lexer::{
+    token_text, DumpOptions, EcmaVersion, Kind, Lexer, LexerError, LexerOptions, Quote, Token,
+};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+/// Options of `tokenize`, in camelCase like those of acorn, all optional
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+struct Options {
+    /// 2015 to 2022, the latest by default
+    ecma_version: Option<u32>,
+    experimental: bool,
+    strict: bool,
+    module: bool,
+    max_identifier_length: Option<usize>,
+    /// Also return the comments, as `LineComment` and `BlockComment` tokens
+    comments: bool,
+}
+
+impl Options {
+    /// `undefined` and `null` for the defaults
+    fn from_js(options: JsValue) -> Result<Self, JsError> {
+        if options.is_undefined() || options.is_null() {
+            return Ok(Self::default());
+        }
+        Ok(serde_wasm_bindgen::from_value(options)?)
+    }
+
+    fn lexer_options(&self) -> Result<LexerOptions, JsError> {
+        let ecma_version = match self.ecma_version {
+            None => EcmaVersion::LATEST,
+            Some(year) => EcmaVersion::from_year(year).ok_or_else(|| {
+                JsError::new(&format!(
+                    "Unsupported ecmaVersion {year}, expected 2015 to 2022"
+                ))
+            })?,
+        };
+        Ok(LexerOptions {
+            experimental: self.experimental,
+            ecma_version,
+            max_identifier_length: self.max_identifier_length,
+            strict: self.strict,
+            module: self.module,
+            ..LexerOptions::new()
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct TokenObject<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    value: &'a str,
+    start: usize,
+    end: usize,
+    /// `'` or `"` for a `String`, and for the `Unknown` of an unterminated string
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quote: Option<char>,
+}
+
+/// `kind` is the name of the `ErrorKind`, e.g. `UnterminatedString`
+#[derive(Debug, Serialize)]
+struct ErrorObject {
+    kind: String,
+    start: usize,
+    end: usize,
+}
+
+impl From<&LexerError> for ErrorObject {
+    fn from(error: &LexerError) -> Self {
+        Self {
+            kind: format!("{:?}", error.kind()),
+            start: error.span().start,
+            end: error.span().end,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Output<'a> {
+    tokens: Vec<TokenObject<'a>>,
+    errors: Vec<ErrorObject>,
+}
+
+/// The esprima token type of `kind`, `Unknown` for an error, `None` for the other trivia and `EOF`
+const fn token_type(kind: Kind) -> Option<&'static str> {
+    let token_type = match kind {
+        Kind::WhiteSpace | Kind::LineTerminator | Kind::EOF | Kind::VirtualSemicolon => {
+            return None
+        }
+        Kind::Unknown => "Unknown",
+        Kind::Comment => "LineComment",
+        Kind::MultilineComment => "BlockComment",
+        Kind::Ident | Kind::Undefined => "Identifier",
+        Kind::True | Kind::False => "Boolean",
+        Kind::Null => "Null",
+        Kind::Number(_) => "Numeric",
+        Kind::Str(_) => "String",
+        Kind::Regex => "RegularExpression",
+        Kind::Template => "Template",
+        _ if kind.is_keyword() => "Keyword",
+        _ => "Punctuator",
+    };
+    Some(token_type)
+}
+
+fn token_object<'a>(source: &'a str, token: &Token, comments: bool) -> Option<TokenObject<'a>> {
+    if matches!(token.kind(), Kind::Comment | Kind::MultilineComment) && !comments {
+        return None;
+    }
+    Some(TokenObject {
+        kind: token_type(*token.kind())?,
+        value: token_text(source, token, DumpOptions::default()),
+        start: token.range().start,
+        end: token.range().end,
+        quote: token.quote().map(Quote::as_char),
+    })
+}
+
+/// The tokens and the errors of `source`, see the crate documentation
+/// # Errors
+/// Unknown or invalid options
+// wasm_bindgen takes the `JsValue` by value
+#[allow(clippy::needless_pass_by_value)]
+#[wasm_bindgen]
+pub fn tokenize(source: &str, options: JsValue) -> Result<JsValue, JsError> {
+    let options = Options::from_js(options)?;
+    let mut lexer = Lexer::with_options(source, options.lexer_options()?);
+    let tokens = lexer
+        .by_ref()
+        .filter_map(|token| token_object(source, &token, options.comments))
+        .collect();
+    let errors = lexer.errors().iter().map(ErrorObject::from).collect();
+    Ok(serde_wasm_bindgen::to_value(&Output { tokens, errors })?)
+}
+
+/// Version of this crate, for a playground to show
+#[must_use]
+#[wasm_bindgen]
+pub fn version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}
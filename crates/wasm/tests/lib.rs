@@ -0,0 +1,115 @@
+//! `wasm-pack test --headless --firefox crates/wasm`
+
+#![cfg(target_arch = "wasm32")]
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+use wasm::{tokenize, version};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Token {
+    #[serde(rename = "type")]
+    kind: String,
+    value: String,
+    start: usize,
+    end: usize,
+    #[serde(default)]
+    quote: Option<char>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Error {
+    kind: String,
+    start: usize,
+    end: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct Output {
+    tokens: Vec<Token>,
+    errors: Vec<Error>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Options {
+    ecma_version: u16,
+    comments: bool,
+}
+
+fn tokens(source: &str, options: JsValue) -> Output {
+    serde_wasm_bindgen::from_value(tokenize(source, options).unwrap()).unwrap()
+}
+
+fn token(kind: &str, value: &str, start: usize) -> Token {
+    Token {
+        kind: kind.to_string(),
+        value: value.to_string(),
+        start,
+        end: start + value.len(),
+        quote: None,
+    }
+}
+
+#[wasm_bindgen_test]
+fn tokenize_source() {
+    let output = tokens("const a = /b/g; // c", JsValue::UNDEFINED);
+    assert_eq!(
+        output.tokens,
+        [
+            token("Keyword", "const", 0),
+            token("Identifier", "a", 6),
+            token("Punctuator", "=", 8),
+            token("RegularExpression", "/b/g", 10),
+            token("Punctuator", ";", 14),
+        ]
+    );
+    assert_eq!(output.errors, []);
+
+    let options = Options {
+        ecma_version: 2022,
+        comments: true,
+    };
+    let output = tokens("a // c", serde_wasm_bindgen::to_value(&options).unwrap());
+    assert_eq!(output.tokens[1], token("LineComment", "// c", 2));
+    assert!(!version().is_empty());
+
+    let output = tokens("'a' + \"b\"", JsValue::UNDEFINED);
+    let quotes = output
+        .tokens
+        .iter()
+        .map(|token| token.quote)
+        .collect::<Vec<_>>();
+    assert_eq!(quotes, [Some('\''), None, Some('"')]);
+}
+
+#[wasm_bindgen_test]
+fn tokenize_errors() {
+    let output = tokens("a = 'b", JsValue::NULL);
+    // an unterminated string keeps its quote
+    assert_eq!(
+        output.tokens[2],
+        Token {
+            quote: Some('\''),
+            ..token("Unknown", "'b", 4)
+        }
+    );
+    assert_eq!(
+        output.errors,
+        [Error {
+            kind: "UnterminatedString".to_string(),
+            start: 4,
+            end: 6,
+        }]
+    );
+
+    let options = Options {
+        ecma_version: 5,
+        comments: false,
+    };
+    assert!(tokenize("a", serde_wasm_bindgen::to_value(&options).unwrap()).is_err());
+}
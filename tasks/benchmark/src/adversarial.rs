@@ -0,0 +1,75 @@
+//! Adversarial inputs unlike any library code, where quadratic behavior would hide
+
+use std::{
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+use lexer::Lexer;
+
+/// Names of the pathological inputs, benchmarked in the `pathological` criterion group
+pub const PATHOLOGICAL: [&str; 5] = [
+    "long_string",
+    "template_openers",
+    "file_comment",
+    "dots_and_digits",
+    "regex_or_divide",
+];
+
+/// Size of the pathological inputs which are not counted in repetitions
+pub const PATHOLOGICAL_SIZE: usize = 1_000_000;
+
+/// Generous bound on lexing a pathological input, even in a debug build
+pub const PATHOLOGICAL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `${` openers of `template_openers`
+const TEMPLATE_OPENERS: usize = 100_000;
+
+/// `/` between a value and a regex of `regex_or_divide`
+const AMBIGUITIES: usize = 50_000;
+
+/// The pathological input `name`, `None` for an unknown input
+#[must_use]
+pub fn pathological(name: &str) -> Option<String> {
+    let code = match name {
+        // a single line string with escaped quotes
+        "long_string" => {
+            let content = "abc\\\"".repeat(PATHOLOGICAL_SIZE / 5);
+            format!("\"{content}\"")
+        }
+        "template_openers" => "`${".repeat(TEMPLATE_OPENERS),
+        // with everything which would start another token outside a comment
+        "file_comment" => {
+            let content = "* a / b `${ \"c' // d\n".repeat(PATHOLOGICAL_SIZE / 22);
+            format!("/*{content}*/")
+        }
+        // `0.1..2...3.4..5...`
+        "dots_and_digits" => {
+            let mut code = String::with_capacity(PATHOLOGICAL_SIZE + 16);
+            let mut i = 0_usize;
+            while code.len() < PATHOLOGICAL_SIZE {
+                code.push_str(&i.to_string());
+                code.push_str(&".".repeat(i % 3 + 1));
+                i += 1;
+            }
+            code
+        }
+        // `x / /a/g / /a/g ...`, a division then a regex
+        "regex_or_divide" => format!("x{}", " / /a/g".repeat(AMBIGUITIES)),
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// The number of tokens of `code`, lexed on another thread so that a hang is an error
+/// # Errors
+/// Lexing takes longer than `timeout` or panics
+pub fn lex_within(name: &str, code: String, timeout: Duration) -> Result<usize, String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || sender.send(Lexer::new(&code).count()));
+    receiver.recv_timeout(timeout).map_err(|e| match e {
+        RecvTimeoutError::Timeout => format!("Lexing `{}` took longer than {:?}", name, timeout),
+        RecvTimeoutError::Disconnected => format!("Lexing `{}` panicked", name),
+    })
+}
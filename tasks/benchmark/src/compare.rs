@@ -0,0 +1,137 @@
+//! Compare a run with a saved criterion baseline with `--baseline <name>`
+
+use std::{
+    env,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{Metric, Sample};
+
+/// Regressions over this many percent fail without `--threshold`
+pub const DEFAULT_THRESHOLD: f64 = 5.0;
+
+/// Where criterion saves its estimates, `CRITERION_HOME` or `criterion` in the cargo target directory
+#[must_use]
+pub fn criterion_dir() -> PathBuf {
+    env::var_os("CRITERION_HOME").map_or_else(
+        || {
+            env::var_os("CARGO_TARGET_DIR")
+                .map_or_else(|| PathBuf::from("target"), PathBuf::from)
+                .join("criterion")
+        },
+        PathBuf::from,
+    )
+}
+
+/// Median time per iteration in nanoseconds,
+/// from `<dir>/<group>/<benchmark>/<baseline>/estimates.json`.
+/// The latest run is the `new` baseline.
+/// # Errors
+/// The estimates are missing or invalid
+pub fn read_median(
+    dir: &Path,
+    group: &str,
+    benchmark: &str,
+    baseline: &str,
+) -> Result<f64, String> {
+    let path = dir
+        .join(group)
+        .join(benchmark)
+        .join(baseline)
+        .join("estimates.json");
+    let json = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let estimates = serde_json::from_str::<serde_json::Value>(&json)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    estimates["median"]["point_estimate"]
+        .as_f64()
+        .ok_or_else(|| format!("{}: no median point estimate", path.display()))
+}
+
+/// A benchmark in the baseline and in the latest run
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub benchmark: String,
+    pub baseline: Sample,
+    pub new: Sample,
+}
+
+impl Comparison {
+    /// Change of the time per byte or token in percent, positive is slower
+    #[must_use]
+    pub fn delta(&self, metric: Metric) -> f64 {
+        (self.baseline.per_second(metric) / self.new.per_second(metric) - 1.0) * 100.0
+    }
+
+    /// Slower by more than `threshold` percent
+    #[must_use]
+    pub fn regressed(&self, metric: Metric, threshold: f64) -> bool {
+        self.delta(metric) > threshold
+    }
+}
+
+/// Compare the samples of the latest run with `baseline`.
+/// A baseline saved without its input size is assumed to have the same input.
+/// # Errors
+/// The estimates of a benchmark are missing or invalid
+pub fn compare(
+    dir: &Path,
+    group: &str,
+    new: &[(String, Sample)],
+    baseline: &str,
+) -> Result<Vec<Comparison>, String> {
+    new.iter()
+        .map(|(benchmark, new)| {
+            Ok(Comparison {
+                benchmark: benchmark.to_string(),
+                baseline: Sample::read(dir, group, benchmark, baseline, new.size)?,
+                new: *new,
+            })
+        })
+        .collect()
+}
+
+/// Table of the throughput in `metric`, marking the regressions by more than `threshold` percent
+#[must_use]
+pub fn render_comparisons(comparisons: &[Comparison], metric: Metric, threshold: f64) -> String {
+    let width = comparisons
+        .iter()
+        .map(|comparison| comparison.benchmark.len())
+        .chain(std::iter::once("benchmark".len()))
+        .max()
+        .unwrap_or_default();
+    let unit = match metric {
+        Metric::Bytes => "MB/s",
+        Metric::Tokens => "Mtokens/s",
+    };
+    let mut s = format!(
+        "{:width$}  {:>20}  {:>20}  {:>8}\n",
+        "benchmark",
+        "baseline",
+        "new",
+        "delta",
+        width = width
+    );
+    for comparison in comparisons {
+        let regressed = if comparison.regressed(metric, threshold) {
+            "  regressed"
+        } else {
+            ""
+        };
+        writeln!(
+            s,
+            "{:width$}  {:>10.1} {:9}  {:>10.1} {:9}  {:>+7.2}%{}",
+            comparison.benchmark,
+            comparison.baseline.per_second(metric) / 1e6,
+            unit,
+            comparison.new.per_second(metric) / 1e6,
+            unit,
+            comparison.delta(metric),
+            regressed,
+            width = width
+        )
+        .unwrap();
+    }
+    s
+}
@@ -0,0 +1,86 @@
+//! What each benchmarked source is made of, printed before measuring so that a minified
+//! library or a download replaced by an HTML error page stands out
+
+use std::fmt::Write;
+
+use lexer::Statistics;
+
+/// Number of most frequent token kinds of [`SourceStats::top_kinds`]
+pub const TOP_KINDS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceStats {
+    pub bytes: usize,
+    /// Every token excluding `EOF`
+    pub tokens: usize,
+    pub trivia_percent: f64,
+    /// The [`TOP_KINDS`] most frequent kinds with their number of tokens
+    pub top_kinds: Vec<(String, usize)>,
+}
+
+impl SourceStats {
+    #[must_use]
+    pub fn new(code: &str) -> Self {
+        let statistics = Statistics::new(code);
+        Self {
+            bytes: code.len(),
+            tokens: statistics.tokens,
+            trivia_percent: statistics.trivia_percent(),
+            top_kinds: statistics
+                .top_kinds(TOP_KINDS)
+                .into_iter()
+                .map(|(kind, count)| (format!("{:?}", kind), count))
+                .collect(),
+        }
+    }
+
+    /// Share of the tokens of `count` in percent
+    #[must_use]
+    pub fn percent(&self, count: usize) -> f64 {
+        if self.tokens == 0 {
+            return 0.0;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let percent = count as f64 / self.tokens as f64 * 100.0;
+        percent
+    }
+}
+
+/// Table of the size, trivia and most frequent kinds of every source
+#[must_use]
+pub fn render_source_stats(sources: &[(String, SourceStats)]) -> String {
+    let width = sources
+        .iter()
+        .map(|(benchmark, _)| benchmark.len())
+        .chain(std::iter::once("benchmark".len()))
+        .max()
+        .unwrap_or_default();
+    let mut s = format!(
+        "{:width$}  {:>10}  {:>10}  {:>7}  {}\n",
+        "benchmark",
+        "bytes",
+        "tokens",
+        "trivia",
+        "top kinds",
+        width = width
+    );
+    for (benchmark, stats) in sources {
+        let top_kinds = stats
+            .top_kinds
+            .iter()
+            .map(|(kind, count)| format!("{} {:.1}%", kind, stats.percent(*count)))
+            .collect::<Vec<_>>();
+        let line = format!(
+            "{:width$}  {:>10}  {:>10}  {:>6.1}%  {}",
+            benchmark,
+            stats.bytes,
+            stats.tokens,
+            stats.trivia_percent,
+            top_kinds.join(", "),
+            width = width
+        );
+        // without kinds for an empty source
+        writeln!(s, "{}", line.trim_end()).unwrap();
+    }
+    s
+}
@@ -0,0 +1,85 @@
+//! Differential comparison with another Rust JS lexer, [ress](https://crates.io/crates/ress),
+//! behind the non-default `compare` feature.
+//! Besides putting the throughput in perspective, token counts far apart
+//! usually mean that one of the lexers mis-lexes something.
+
+use std::fmt::Write;
+
+use lexer::{Kind, Lexer};
+
+use crate::{Metric, Sample};
+
+/// Token counts differing by more than this many percent are logged
+pub const COUNT_TOLERANCE: f64 = 10.0;
+
+/// Tokens of `code` as counted by lexers which skip whitespaces and line terminators,
+/// comments included and `EOF` excluded
+#[must_use]
+pub fn comparable_token_count(code: &str) -> usize {
+    Lexer::new(code)
+        .filter(|token| {
+            !matches!(
+                token.kind(),
+                Kind::WhiteSpace | Kind::LineTerminator | Kind::EOF
+            )
+        })
+        .count()
+}
+
+/// Tokens of `code` according to ress, comments included and `EOF` excluded,
+/// up to the first error
+#[cfg(feature = "compare")]
+#[must_use]
+pub fn ress_token_count(code: &str) -> usize {
+    ress::Scanner::new(code)
+        .map_while(Result::ok)
+        .take_while(|item| !item.token.is_eof())
+        .count()
+}
+
+/// A warning when the token count of the other lexer is off by more than [`COUNT_TOLERANCE`] percent
+#[must_use]
+pub fn count_mismatch(benchmark: &str, ours: usize, other: &str, theirs: usize) -> Option<String> {
+    #[allow(clippy::cast_precision_loss)]
+    let difference = (theirs as f64 / ours.max(1) as f64 - 1.0) * 100.0;
+    (difference.abs() > COUNT_TOLERANCE).then(|| {
+        format!(
+            "[{}] - {} tokens but {} with {} ({:+.1}%), one of the lexers likely mis-lexes something",
+            benchmark, ours, theirs, other, difference
+        )
+    })
+}
+
+/// Table of the throughput of this lexer and `other` side by side, in MB/s
+#[must_use]
+pub fn render_side_by_side(other: &str, rows: &[(String, Sample, Sample)]) -> String {
+    let width = rows
+        .iter()
+        .map(|(benchmark, _, _)| benchmark.len())
+        .chain(std::iter::once("benchmark".len()))
+        .max()
+        .unwrap_or_default();
+    let mut s = format!(
+        "{:width$}  {:>10}  {:>10}  {:>8}\n",
+        "benchmark",
+        "lexer MB/s",
+        format!("{} MB/s", other),
+        "speedup",
+        width = width
+    );
+    for (benchmark, ours, theirs) in rows {
+        let ours = ours.per_second(Metric::Bytes);
+        let theirs = theirs.per_second(Metric::Bytes);
+        writeln!(
+            s,
+            "{:width$}  {:>10.1}  {:>10.1}  {:>7.2}x",
+            benchmark,
+            ours / 1e6,
+            theirs / 1e6,
+            ours / theirs,
+            width = width
+        )
+        .unwrap();
+    }
+    s
+}
@@ -0,0 +1,121 @@
+//! Downloads of the benchmark libraries, retried with backoff on transient failures
+
+use std::{io::Read, thread, time::Duration};
+
+/// Attempts after the first one without `--retries`
+pub const DEFAULT_RETRIES: u32 = 3;
+
+/// Timeout of a whole request in seconds without `--timeout`
+pub const DEFAULT_TIMEOUT: u64 = 60;
+
+/// Sent with every request, some CDNs reject requests without one
+pub const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DownloadOptions {
+    /// Attempts after the first one on a 5xx or 429 status, a network error or a truncated body
+    pub retries: u32,
+
+    /// Timeout of a whole request
+    pub timeout: Duration,
+
+    /// Wait before the first retry, doubled after each retry
+    pub backoff: Duration,
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        Self {
+            retries: DEFAULT_RETRIES,
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT),
+            backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+/// A failed attempt
+struct Failure {
+    message: String,
+
+    /// Worth retrying
+    transient: bool,
+}
+
+impl Failure {
+    const fn transient(message: String) -> Self {
+        Self {
+            message,
+            transient: true,
+        }
+    }
+}
+
+/// Download `url`, retrying transient failures
+/// # Errors
+/// The status code or network error of the last attempt, with the number of attempts if retried
+pub fn download(url: &str, options: &DownloadOptions) -> Result<String, String> {
+    let agent = ureq::AgentBuilder::new()
+        .timeout(options.timeout)
+        .user_agent(USER_AGENT)
+        .build();
+    let mut backoff = options.backoff;
+    let mut attempts = 1;
+    loop {
+        let failure = match attempt(&agent, url) {
+            Ok(code) => return Ok(code),
+            Err(failure) => failure,
+        };
+        if !failure.transient || attempts > options.retries {
+            if attempts == 1 {
+                return Err(failure.message);
+            }
+            return Err(format!("{} after {} attempts", failure.message, attempts));
+        }
+        println!(
+            "[{}] - {}, retrying in {}ms",
+            url,
+            failure.message,
+            backoff.as_millis()
+        );
+        thread::sleep(backoff);
+        backoff *= 2;
+        attempts += 1;
+    }
+}
+
+/// Download `url` once, checking its length against the `Content-Length` header
+fn attempt(agent: &ureq::Agent, url: &str) -> Result<String, Failure> {
+    let response = match agent.get(url).call() {
+        Ok(response) => response,
+        Err(ureq::Error::Status(status, response)) => {
+            return Err(Failure {
+                message: format!("HTTP {} {}", status, response.status_text()),
+                transient: status >= 500 || status == 429,
+            });
+        }
+        Err(ureq::Error::Transport(transport)) => {
+            return Err(Failure::transient(transport.to_string()));
+        }
+    };
+    let expected_len = response
+        .header("Content-Length")
+        .and_then(|len| len.parse::<usize>().ok());
+    let mut bytes = vec![];
+    response
+        .into_reader()
+        .read_to_end(&mut bytes)
+        .map_err(|e| Failure::transient(e.to_string()))?;
+    if let Some(expected_len) = expected_len {
+        if bytes.len() != expected_len {
+            return Err(Failure::transient(format!(
+                "received {} of {} bytes",
+                bytes.len(),
+                expected_len
+            )));
+        }
+    }
+    String::from_utf8(bytes).map_err(|e| Failure {
+        message: e.to_string(),
+        transient: false,
+    })
+}
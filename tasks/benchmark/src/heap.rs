@@ -0,0 +1,119 @@
+//! Allocations of lexing under a counting global allocator, `cargo benchmark allocations`
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    fmt::Write,
+};
+
+use lexer::{Lexer, Token};
+
+/// Lexing a library into a `Vec` of tokens fails over this many allocations,
+/// room for the growths of the `Vec` but not for an allocation per token
+pub const ALLOCATION_BUDGET: usize = 64;
+
+thread_local! {
+    static ALLOCATIONS: Cell<Allocations> = Cell::new(Allocations::default());
+}
+
+/// Allocations of a run, a reallocation counts as an allocation of its new size
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Allocations {
+    pub count: usize,
+    pub bytes: usize,
+}
+
+/// Global allocator counting the allocations of each thread, see [`count_allocations`]
+pub struct CountingAllocator;
+
+fn record(bytes: usize) {
+    // the thread local is gone while the thread exits
+    ALLOCATIONS
+        .try_with(|allocations| {
+            let Allocations {
+                count,
+                bytes: total,
+            } = allocations.get();
+            allocations.set(Allocations {
+                count: count + 1,
+                bytes: total + bytes,
+            });
+        })
+        .ok();
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+}
+
+/// Allocations of the current thread while running `f`,
+/// none unless [`CountingAllocator`] is the `#[global_allocator]`
+pub fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, Allocations) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    let after = ALLOCATIONS.with(Cell::get);
+    let allocations = Allocations {
+        count: after.count - before.count,
+        bytes: after.bytes - before.bytes,
+    };
+    (result, allocations)
+}
+
+/// Allocations of lexing `code` into a `Vec` of tokens
+#[must_use]
+pub fn lex_allocations(code: &str) -> Allocations {
+    count_allocations(|| Lexer::new(code).collect::<Vec<Token>>()).1
+}
+
+/// Table of the allocations of every benchmark, marking those over [`ALLOCATION_BUDGET`]
+#[must_use]
+pub fn render_allocations(allocations: &[(String, Allocations)]) -> String {
+    let width = allocations
+        .iter()
+        .map(|(benchmark, _)| benchmark.len())
+        .chain(std::iter::once("benchmark".len()))
+        .max()
+        .unwrap_or_default();
+    let mut s = format!(
+        "{:width$}  {:>11}  {:>12}\n",
+        "benchmark",
+        "allocations",
+        "bytes",
+        width = width
+    );
+    for (benchmark, allocations) in allocations {
+        let over = if allocations.count > ALLOCATION_BUDGET {
+            "  over budget"
+        } else {
+            ""
+        };
+        writeln!(
+            s,
+            "{:width$}  {:>11}  {:>12}{}",
+            benchmark,
+            allocations.count,
+            allocations.bytes,
+            over,
+            width = width
+        )
+        .unwrap();
+    }
+    s
+}
@@ -0,0 +1,161 @@
+//! Benchmark results as a flat JSON array with `--json <path>`, for keeping a history across commits.
+//! `cargo benchmark compare <old> <new>` prints the deltas between two of them.
+
+use std::{fs, path::Path, process::Command};
+
+use crate::{Comparison, InputSize, Metric, Sample, SourceStats};
+
+/// A benchmark of a run, `<group>/<benchmark>`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub name: String,
+    pub sample: Sample,
+    /// `HEAD` of the benchmarked tree, `None` outside of a git repository
+    pub commit: Option<String>,
+    /// Of the libraries of the `lexer` group
+    pub statistics: Option<SourceStats>,
+}
+
+/// Hash of the `HEAD` commit
+#[must_use]
+pub fn git_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// A record for each benchmark of each group
+#[must_use]
+pub fn to_records(groups: &[(&str, Vec<(String, Sample)>)], commit: Option<&str>) -> Vec<Record> {
+    groups
+        .iter()
+        .flat_map(|(group, samples)| {
+            samples.iter().map(move |(benchmark, sample)| Record {
+                name: format!("{}/{}", group, benchmark),
+                sample: *sample,
+                commit: commit.map(str::to_string),
+                statistics: None,
+            })
+        })
+        .collect()
+}
+
+/// Render the records as a JSON array
+/// # Panics
+/// Serialization failed
+#[must_use]
+pub fn render_records(records: &[Record]) -> String {
+    let records = records
+        .iter()
+        .map(|record| {
+            let mut json = serde_json::json!({
+                "name": record.name.as_str(),
+                "bytes": record.sample.size.bytes,
+                "tokens": record.sample.size.tokens,
+                "median_ns": record.sample.median,
+                "megabytes_per_second": record.sample.per_second(Metric::Bytes) / 1e6,
+                "tokens_per_second": record.sample.per_second(Metric::Tokens),
+                "commit": record.commit.as_deref(),
+            });
+            if let Some(statistics) = &record.statistics {
+                json["statistics"] = render_statistics(statistics);
+            }
+            json
+        })
+        .collect();
+    serde_json::to_string_pretty(&serde_json::Value::Array(records)).unwrap()
+}
+
+/// # Errors
+/// Not an array of records
+pub fn parse_records(json: &str) -> Result<Vec<Record>, String> {
+    let json = serde_json::from_str::<serde_json::Value>(json).map_err(|e| e.to_string())?;
+    let records = json.as_array().ok_or("expected an array of benchmarks")?;
+    records
+        .iter()
+        .map(|record| {
+            let name = record["name"].as_str().ok_or("benchmark without a name")?;
+            let invalid = |field: &str| format!("`{}` has no valid `{}`", name, field);
+            let count = |field: &str| {
+                record[field]
+                    .as_u64()
+                    .and_then(|n| usize::try_from(n).ok())
+                    .ok_or_else(|| invalid(field))
+            };
+            Ok(Record {
+                name: name.to_string(),
+                sample: Sample {
+                    median: record["median_ns"]
+                        .as_f64()
+                        .ok_or_else(|| invalid("median_ns"))?,
+                    size: InputSize {
+                        bytes: count("bytes")?,
+                        tokens: count("tokens")?,
+                    },
+                },
+                commit: record["commit"].as_str().map(str::to_string),
+                statistics: match &record["statistics"] {
+                    serde_json::Value::Null => None,
+                    statistics => {
+                        Some(parse_statistics(statistics).ok_or_else(|| invalid("statistics"))?)
+                    }
+                },
+            })
+        })
+        .collect()
+}
+
+fn render_statistics(statistics: &SourceStats) -> serde_json::Value {
+    let top_kinds = statistics
+        .top_kinds
+        .iter()
+        .map(|(kind, count)| serde_json::json!({ "kind": kind.as_str(), "count": *count }))
+        .collect();
+    serde_json::json!({
+        "bytes": statistics.bytes,
+        "tokens": statistics.tokens,
+        "trivia_percent": statistics.trivia_percent,
+        "top_kinds": serde_json::Value::Array(top_kinds),
+    })
+}
+
+fn parse_statistics(statistics: &serde_json::Value) -> Option<SourceStats> {
+    let count = |value: &serde_json::Value| usize::try_from(value.as_u64()?).ok();
+    Some(SourceStats {
+        bytes: count(&statistics["bytes"])?,
+        tokens: count(&statistics["tokens"])?,
+        trivia_percent: statistics["trivia_percent"].as_f64()?,
+        top_kinds: statistics["top_kinds"]
+            .as_array()?
+            .iter()
+            .map(|kind| Some((kind["kind"].as_str()?.to_string(), count(&kind["count"])?)))
+            .collect::<Option<_>>()?,
+    })
+}
+
+/// # Errors
+/// IO error or invalid records
+pub fn read_records(path: &Path) -> Result<Vec<Record>, String> {
+    let json = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    parse_records(&json).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// The benchmarks of `new` which are also in `old`, in the order of `new`
+#[must_use]
+pub fn compare_records(old: &[Record], new: &[Record]) -> Vec<Comparison> {
+    new.iter()
+        .filter_map(|new| {
+            let old = old.iter().find(|old| old.name == new.name)?;
+            Some(Comparison {
+                benchmark: new.name.clone(),
+                baseline: old.sample,
+                new: new.sample,
+            })
+        })
+        .collect()
+}
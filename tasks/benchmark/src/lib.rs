@@ -1,51 +1,289 @@
 #![warn(clippy::all, clippy::pedantic, clippy::nursery)]
 
-use std::{path::PathBuf, str::FromStr};
+mod adversarial;
+mod compare;
+mod corpus;
+mod differential;
+mod fetch;
+mod heap;
+mod history;
+mod sample;
+mod scaling;
+mod workloads;
+
+pub use crate::adversarial::*;
+pub use crate::compare::*;
+pub use crate::corpus::*;
+pub use crate::differential::*;
+pub use crate::fetch::*;
+pub use crate::heap::*;
+pub use crate::history::*;
+pub use crate::sample::*;
+pub use crate::scaling::*;
+pub use crate::workloads::*;
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 fn err_to_string<E: std::fmt::Debug>(e: E) -> String {
     format!("{:?}", e)
 }
 
+/// `<length> <FNV-1a hash>` of a download, stored next to it as `<filename>.checksum`
+fn checksum(code: &str) -> String {
+    let hash = code.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{} {:016x}", code.len(), hash)
+}
+
+fn checksum_path(dir: &Path, filename: &str) -> PathBuf {
+    dir.join(format!("{}.checksum", filename))
+}
+
+/// Write to a temporary file renamed to `path`, so an interrupted write never leaves a truncated `path`
+fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)
+}
+
+/// The download of `filename` in `dir`,
+/// `None` if it is missing or does not match its checksum, e.g. after an interrupted download
+#[must_use]
+pub fn read_cached(dir: &Path, filename: &str) -> Option<String> {
+    let code = fs::read_to_string(dir.join(filename)).ok()?;
+    let expected = fs::read_to_string(checksum_path(dir, filename)).ok()?;
+    (expected.trim() == checksum(&code)).then(|| code)
+}
+
+/// Store a download in `dir` along with its checksum
 /// # Errors
-pub fn get_code(lib: &str) -> Result<(String, String), String> {
+/// IO error
+pub fn write_cached(dir: &Path, filename: &str, code: &str) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    write_atomic(&dir.join(filename), code)?;
+    write_atomic(&checksum_path(dir, filename), &checksum(code))
+}
+
+/// Where downloads are cached without `--cache-dir`
+pub const DEFAULT_CACHE_DIR: &str = "target";
+
+/// Urls of a list of libraries, one per line. Blank lines and lines starting with `#` are skipped.
+pub fn parse_libs(list: &str) -> impl Iterator<Item = &str> {
+    list.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+/// Urls of the libraries to benchmark by default, embedded from `libs.txt`
+pub fn libs() -> impl Iterator<Item = &'static str> {
+    parse_libs(include_str!("./libs.txt"))
+}
+
+/// The libraries to benchmark: those listed in the file `path` and the `urls`,
+/// or the default [`libs`] without either
+/// # Errors
+/// The list cannot be read
+pub fn select_libs(path: Option<&Path>, urls: Vec<String>) -> Result<Vec<String>, String> {
+    let mut libs = match path {
+        Some(path) => {
+            let list =
+                fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            parse_libs(&list).map(ToString::to_string).collect()
+        }
+        None if urls.is_empty() => libs().map(ToString::to_string).collect(),
+        None => vec![],
+    };
+    libs.extend(urls);
+    Ok(libs)
+}
+
+/// Whether the benchmark `id`, e.g. the file name of a library or a workload, contains `filter`
+#[must_use]
+pub fn matches_filter(id: &str, filter: Option<&str>) -> bool {
+    filter.map_or(true, |filter| id.contains(filter))
+}
+
+/// The first `first` of the `libs` whose file name matches `filter`, all of them without either
+#[must_use]
+pub fn filter_libs(libs: Vec<String>, filter: Option<&str>, first: Option<usize>) -> Vec<String> {
+    libs.into_iter()
+        .filter(|lib| lib_filename(lib).map_or(false, |filename| matches_filter(&filename, filter)))
+        .take(first.unwrap_or(usize::MAX))
+        .collect()
+}
+
+/// Check the urls before downloading or measuring anything
+/// # Errors
+/// Lists every invalid url, and the urls with the same file name which would overwrite each other in the cache
+pub fn validate_libs<'a>(libs: impl IntoIterator<Item = &'a str>) -> Result<(), String> {
+    let mut filenames = HashMap::new();
+    let mut invalid = vec![];
+    for lib in libs {
+        match lib_filename(lib) {
+            Ok(filename) => {
+                if let Some(first) = filenames.insert(filename, lib) {
+                    invalid.push(format!("  {}: same file name as {}", lib, first));
+                }
+            }
+            Err(e) => invalid.push(format!("  {}: {}", lib, e)),
+        }
+    }
+    if invalid.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "{} invalid benchmark urls:\n{}",
+        invalid.len(),
+        invalid.join("\n")
+    ))
+}
+
+/// Whether `CARGO_NET_OFFLINE` is set, like for `cargo --offline`
+#[must_use]
+pub fn is_offline_env() -> bool {
+    std::env::var("CARGO_NET_OFFLINE").map_or(false, |value| value == "true" || value == "1")
+}
+
+/// The last segment of the url of `lib`
+/// # Errors
+/// Invalid url, not `http` or `https`, or without a file name
+pub fn lib_filename(lib: &str) -> Result<String, String> {
     let url = url::Url::from_str(lib).map_err(err_to_string)?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!("lib url is not http or https: {}", url.scheme()));
+    }
 
-    let segments = url
+    let mut segments = url
         .path_segments()
         .ok_or_else(|| "lib url has no segments".to_string())?;
 
-    let filename = segments
-        .last()
-        .ok_or_else(|| "lib url has no segments".to_string())?;
+    segments
+        .next_back()
+        .filter(|filename| !filename.is_empty())
+        .map(ToString::to_string)
+        .ok_or_else(|| "lib url has no file name".to_string())
+}
+
+/// `cargo benchmark fetch`, with `--cache-dir` unless it is the default
+fn fetch_command(dir: &Path) -> String {
+    if dir == Path::new(DEFAULT_CACHE_DIR) {
+        "cargo benchmark fetch".to_string()
+    } else {
+        format!("cargo benchmark fetch --cache-dir {}", dir.display())
+    }
+}
+
+/// Check that every file of `libs` is cached in `dir` before running offline
+/// # Errors
+/// Lists every missing or corrupted file and how to fetch them
+pub fn check_offline<'a>(
+    dir: &Path,
+    libs: impl IntoIterator<Item = &'a str>,
+) -> Result<(), String> {
+    let mut missing = vec![];
+    for lib in libs {
+        let filename = lib_filename(lib)?;
+        if read_cached(dir, &filename).is_none() {
+            missing.push(format!("  {} from {}", dir.join(filename).display(), lib));
+        }
+    }
+    if missing.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "Offline, but {} benchmark files are missing or corrupted:\n{}\nDownload them with `{}`",
+        missing.len(),
+        missing.join("\n"),
+        fetch_command(dir)
+    ))
+}
 
-    let mut file = PathBuf::from_str("target").map_err(err_to_string)?;
-    file.push(filename);
+/// The file name and code of `lib`, cached in `dir`.
+/// Downloaded with `options` when it is not cached yet, unless `offline`.
+/// A failed download leaves nothing in the cache.
+/// # Errors
+/// Missing offline, or the download failed
+pub fn get_code(
+    lib: &str,
+    dir: &Path,
+    offline: bool,
+    options: &DownloadOptions,
+) -> Result<(String, String), String> {
+    let filename = lib_filename(lib)?;
+    let file = dir.join(&filename);
 
-    if let Ok(code) = std::fs::read_to_string(&file) {
+    if let Some(code) = read_cached(dir, &filename) {
         println!("[{}] - using [{}]", filename, file.display());
-        Ok((filename.to_string(), code))
-    } else {
-        println!(
-            "[{}] - Downloading [{}] to [{}]",
-            filename,
+        return Ok((filename, code));
+    }
+
+    if offline {
+        return Err(format!(
+            "Offline, but [{}] is missing or corrupted, download it with `{}`",
+            file.display(),
+            fetch_command(dir)
+        ));
+    }
+
+    println!(
+        "[{}] - Downloading [{}] to [{}]",
+        filename,
+        lib,
+        file.display()
+    );
+    let code = download(lib, options).map_err(|e| {
+        format!(
+            "Failed to download [{}] to [{}]: {}",
             lib,
-            file.display()
-        );
-        match ureq::get(lib).call() {
-            Ok(response) => {
-                let mut reader = response.into_reader();
-
-                #[allow(clippy::let_underscore_drop)]
-                let _ = std::fs::remove_file(&file);
-                let mut writer = std::fs::File::create(&file).map_err(err_to_string)?;
-                #[allow(clippy::let_underscore_drop)]
-                let _ = std::io::copy(&mut reader, &mut writer);
-
-                std::fs::read_to_string(&file)
-                    .map_err(err_to_string)
-                    .map(|code| (filename.to_string(), code))
-            }
-            Err(e) => Err(format!("{:?}", e)),
+            file.display(),
+            e
+        )
+    })?;
+    write_cached(dir, &filename, &code)
+        .map_err(|e| format!("Failed to write [{}]: {}", file.display(), e))?;
+    Ok((filename, code))
+}
+
+/// The file name and code of each of `libs`, see [`get_code`].
+/// Every lib is tried, so that a run fails before measuring anything with every failure.
+/// # Errors
+/// Lists every lib which failed to download or is missing offline
+pub fn get_codes<'a>(
+    libs: impl IntoIterator<Item = &'a str>,
+    dir: &Path,
+    offline: bool,
+    options: &DownloadOptions,
+) -> Result<Vec<(String, String)>, String> {
+    let mut codes = vec![];
+    let mut failed = vec![];
+    for lib in libs {
+        match get_code(lib, dir, offline, options) {
+            Ok(code) => codes.push(code),
+            Err(e) => failed.push(format!("  {}", e)),
         }
     }
+    if failed.is_empty() {
+        return Ok(codes);
+    }
+    Err(format!(
+        "Failed to get {} benchmark files:\n{}",
+        failed.len(),
+        failed.join("\n")
+    ))
+}
+
+/// Synthetic input of mostly punctuators, the multi-byte ones in particular,
+/// to measure the punctuator readers in isolation from identifiers and literals
+#[must_use]
+pub fn punctuator_code() -> (String, String) {
+    let line = "a += b++ - --c; x = y === z ? p ?? q : r !== s; m >>>= n << 2 >= o;\n\
+                f => g && h || i ** j; k &= l |= m ^= n %= o *= p; ...q?.r;\n";
+    ("punctuators".to_string(), line.repeat(20_000))
 }
@@ -1,37 +1,514 @@
-use benchmark::get_code;
-use criterion::{black_box, Criterion, Throughput};
+//! `cargo benchmark` downloads the libraries of `libs.txt` on first use.
+//! `cargo benchmark fetch` only downloads them, so that `cargo benchmark --offline`
+//! (or with `CARGO_NET_OFFLINE=true`) runs without touching the network.
+//! Both take `--cache-dir <dir>`, `target` by default. A download is retried `--retries` times
+//! on a server error or a timeout of `--timeout` seconds.
+//!
+//! `--save-baseline <name>` saves the run as a criterion baseline, `--baseline <name>` compares
+//! the run with it and fails when the throughput regresses by more than `--threshold` percent,
+//! in bytes or with `--metric tokens` in tokens per second.
+//!
+//! `--filter <text>` only runs the libraries and workloads whose name contains the text,
+//! and `--first <n>` only the first `n` libraries. `--quick` measures for a second instead of 10,
+//! on the first two libraries unless `--first` or `--filter` is given, for a low-confidence result
+//! while iterating on the lexer. It cannot save a baseline.
+//!
+//! The size, token count, trivia share and most frequent token kinds of every library are printed
+//! before measuring. `--json <path>` also writes the results as a flat JSON array with the commit
+//! hash and these statistics, and `cargo benchmark compare <old> <new>` prints the deltas
+//! between two of them.
+//!
+//! `cargo benchmark allocations` lexes every library and workload under a counting allocator
+//! instead, and fails when one of them takes more than `ALLOCATION_BUDGET` allocations.
+//!
+//! The libraries are those of `--libs <file>`, one url per line, and the urls given as arguments,
+//! e.g. `cargo benchmark https://cdn.jsdelivr.net/npm/jquery@3.6.0/dist/jquery.js`,
+//! or the embedded `libs.txt` without either. Every url is checked and every library fetched
+//! before measuring anything.
+//!
+//! Besides the `lexer` group of real libraries, each synthetic workload of `workloads.rs`
+//! is a group of its own, to see which reader a change affects. `long_identifiers` and
+//! `unicode_identifiers` have the lines of `identifiers` with `camelCase` identifiers of several
+//! words and with identifiers beyond ASCII, for the ASCII runs of identifiers and their unicode
+//! path. The `pathological` group lexes the adversarial inputs of `adversarial.rs`,
+//! each first checked to finish within `PATHOLOGICAL_TIMEOUT` so that a hang fails instead of
+//! stalling the run. The `parallel` group
+//! lexes all the libraries on 1, 2, 4 and as many threads as cores, reporting the speedup.
+//!
+//! With the `compare` feature, `cargo run -p benchmark --release --features compare`,
+//! each library is also lexed by ress in a `compare-<library>` group, see `differential.rs`.
+
+use benchmark::{
+    check_offline, compare, compare_records, criterion_dir, filter_libs, get_codes, git_commit,
+    is_offline_env, lex_allocations, lex_many, lex_within, matches_filter, pathological,
+    punctuator_code, read_records, render_allocations, render_comparisons, render_records,
+    render_samples, render_source_stats, render_speedups, select_libs, thread_counts, to_records,
+    validate_libs, workload, write_input_size, CountingAllocator, DownloadOptions, InputSize,
+    Metric, Sample, SourceStats, ALLOCATION_BUDGET, DEFAULT_CACHE_DIR, DEFAULT_RETRIES,
+    DEFAULT_THRESHOLD, DEFAULT_TIMEOUT, PATHOLOGICAL, PATHOLOGICAL_TIMEOUT, WORKLOADS,
+};
+use criterion::{BenchmarkId, Criterion, Throughput};
 use pico_args::Arguments;
-use std::time::Duration;
+use rayon::ThreadPoolBuilder;
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    process,
+    time::Duration,
+};
 
 use lexer::Lexer;
 
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Measurement time of each benchmark of a full run, for baselines
+const MEASUREMENT_TIME: Duration = Duration::from_secs(10);
+
+/// Measurement time of each benchmark with `--quick`
+const QUICK_MEASUREMENT_TIME: Duration = Duration::from_secs(1);
+
+/// Samples of each benchmark with `--quick`, the minimum of criterion
+const QUICK_SAMPLE_SIZE: usize = 10;
+
+/// Libraries benchmarked with `--quick` without `--first` or `--filter`
+const QUICK_LIBS: usize = 2;
+
+/// Exit with the error, e.g. of a missing download or estimate
+fn or_exit<T, E: fmt::Display>(result: Result<T, E>) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    })
+}
+
+/// Where and how to get the libraries
+struct Source {
+    libs: Vec<String>,
+    dir: PathBuf,
+    offline: bool,
+    download: DownloadOptions,
+}
+
+/// The file name and code of every library, exiting with every failure before measuring anything
+fn lib_codes(source: &Source) -> Vec<(String, String)> {
+    let libs = || source.libs.iter().map(String::as_str);
+    if source.offline {
+        or_exit(check_offline(&source.dir, libs()));
+    }
+    or_exit(get_codes(
+        libs(),
+        &source.dir,
+        source.offline,
+        &source.download,
+    ))
+}
+
+/// `cargo benchmark fetch`: download every library into the cache directory
+fn fetch(source: &Source) {
+    if source.offline {
+        eprintln!("`fetch` downloads the benchmark files, it cannot run offline");
+        process::exit(1);
+    }
+    lib_codes(source);
+}
+
+/// `cargo benchmark allocations`: the allocations of lexing every library and workload
+fn allocations(source: &Source) {
+    let codes = lib_codes(source)
+        .into_iter()
+        .chain(std::iter::once(punctuator_code()))
+        .chain(
+            WORKLOADS
+                .iter()
+                .map(|name| (name.to_string(), workload(name).unwrap())),
+        );
+    let allocations = codes
+        .map(|(id, code)| (id, lex_allocations(&code)))
+        .collect::<Vec<_>>();
+    print!("{}", render_allocations(&allocations));
+    let over = allocations
+        .iter()
+        .filter(|(_, allocations)| allocations.count > ALLOCATION_BUDGET)
+        .count();
+    if over > 0 {
+        eprintln!(
+            "{} benchmarks took more than {} allocations",
+            over, ALLOCATION_BUDGET
+        );
+        process::exit(1);
+    }
+}
+
+/// `cargo benchmark compare <old> <new>`: the deltas between two results of `--json`
+fn compare_files(paths: &[String], metric: Metric, threshold: f64) {
+    let (old, new) = match paths {
+        [old, new] => (old, new),
+        _ => {
+            eprintln!("`compare` takes two results of `--json`, the old and the new one");
+            process::exit(1);
+        }
+    };
+    let old = or_exit(read_records(Path::new(old)));
+    let new = or_exit(read_records(Path::new(new)));
+    let comparisons = compare_records(&old, &new);
+    print!("{}", render_comparisons(&comparisons, metric, threshold));
+}
+
 pub fn main() {
     let mut args = Arguments::from_env();
-    let baseline: Option<String> = args.opt_value_from_str("--save-baseline").unwrap();
+    let subcommand = args.subcommand().unwrap();
+    let save_baseline: Option<String> = args.opt_value_from_str("--save-baseline").unwrap();
+    let baseline: Option<String> = args.opt_value_from_str("--baseline").unwrap();
+    let threshold = args
+        .opt_value_from_str("--threshold")
+        .unwrap()
+        .unwrap_or(DEFAULT_THRESHOLD);
+    let metric = args
+        .opt_value_from_str("--metric")
+        .unwrap()
+        .unwrap_or(Metric::Bytes);
+    let offline = args.contains("--offline") || is_offline_env();
+    let dir = args
+        .opt_value_from_str("--cache-dir")
+        .unwrap()
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_CACHE_DIR));
+    let libs_path: Option<PathBuf> = args.opt_value_from_str("--libs").unwrap();
+    let filter: Option<String> = args.opt_value_from_str("--filter").unwrap();
+    let json: Option<PathBuf> = args.opt_value_from_str("--json").unwrap();
+    let quick = args.contains("--quick");
+    let first = args.opt_value_from_str("--first").unwrap().or_else(|| {
+        // a filter selects libraries by itself
+        (quick && filter.is_none()).then(|| QUICK_LIBS)
+    });
+    let download = DownloadOptions {
+        retries: args
+            .opt_value_from_str("--retries")
+            .unwrap()
+            .unwrap_or(DEFAULT_RETRIES),
+        timeout: Duration::from_secs(
+            args.opt_value_from_str("--timeout")
+                .unwrap()
+                .unwrap_or(DEFAULT_TIMEOUT),
+        ),
+        ..DownloadOptions::default()
+    };
+    let mut urls = or_exit(args.free());
+
+    // the first url is taken for a subcommand
+    let subcommand = match subcommand {
+        Some(url) if url.contains("://") => {
+            urls.insert(0, url);
+            None
+        }
+        subcommand => subcommand,
+    };
+    if subcommand.as_deref() == Some("compare") {
+        compare_files(&urls, metric, threshold);
+        return;
+    }
+    let libs = or_exit(select_libs(libs_path.as_deref(), urls));
+    or_exit(validate_libs(libs.iter().map(String::as_str)));
+    let libs = filter_libs(libs, filter.as_deref(), first);
+    let source = Source {
+        libs,
+        dir,
+        offline,
+        download,
+    };
+
+    match subcommand.as_deref() {
+        Some("fetch") => {
+            fetch(&source);
+            return;
+        }
+        Some("allocations") => {
+            allocations(&source);
+            return;
+        }
+        Some(subcommand) => {
+            eprintln!(
+                "Unknown subcommand `{}`, expected `fetch`, `allocations` or `compare`",
+                subcommand
+            );
+            process::exit(1);
+        }
+        None => {}
+    }
+
+    if quick && save_baseline.is_some() {
+        eprintln!("`--quick` results are too noisy for `--save-baseline`");
+        process::exit(1);
+    }
+
+    let lib_codes = lib_codes(&source);
+
+    let mut criterion = Criterion::default().without_plots();
+    criterion = if quick {
+        criterion
+            .measurement_time(QUICK_MEASUREMENT_TIME)
+            .warm_up_time(QUICK_MEASUREMENT_TIME / 2)
+            .sample_size(QUICK_SAMPLE_SIZE)
+    } else {
+        criterion.measurement_time(MEASUREMENT_TIME)
+    };
+
+    match (&save_baseline, &baseline) {
+        (Some(_), Some(_)) => {
+            eprintln!("`--save-baseline` and `--baseline` cannot be used together");
+            process::exit(1);
+        }
+        (Some(save_baseline), None) => {
+            criterion = criterion.save_baseline(save_baseline.to_string());
+        }
+        // compare without overwriting the baseline
+        (None, Some(baseline)) => criterion = criterion.retain_baseline(baseline.to_string()),
+        (None, None) => {}
+    }
 
-    let mut criterion = Criterion::default()
-        .without_plots()
-        .measurement_time(Duration::new(10, 0));
+    let codes = lib_codes
+        .iter()
+        .cloned()
+        .chain(std::iter::once(punctuator_code()))
+        .filter(|(id, _)| matches_filter(id, filter.as_deref()))
+        .collect::<Vec<_>>();
+    let statistics = codes
+        .iter()
+        .map(|(id, code)| (id.clone(), SourceStats::new(code)))
+        .collect::<Vec<_>>();
+    if !statistics.is_empty() {
+        print!("{}", render_source_stats(&statistics));
+    }
+
+    let mut groups = vec![];
+    if !codes.is_empty() {
+        groups.push(("lexer", bench_group(&mut criterion, "lexer", codes)));
+    }
+
+    let lib_only = lib_codes
+        .iter()
+        .filter(|(id, _)| matches_filter(id, filter.as_deref()))
+        .map(|(_, code)| code.clone())
+        .collect::<Vec<_>>();
+    let scaling = (!lib_only.is_empty()).then(|| bench_scaling(&mut criterion, &lib_only));
+
+    // a group per workload, for attributing a change to a reader
+    for name in WORKLOADS {
+        if !matches_filter(name, filter.as_deref()) {
+            continue;
+        }
+        let code = workload(name).unwrap();
+        let codes = std::iter::once(("lexer".to_string(), code));
+        groups.push((name, bench_group(&mut criterion, name, codes)));
+    }
+
+    // checked before criterion, which would hang on a lexer that never finishes
+    let codes = PATHOLOGICAL
+        .into_iter()
+        .filter(|name| matches_filter(name, filter.as_deref()))
+        .map(|name| {
+            let code = pathological(name).unwrap();
+            or_exit(lex_within(name, code.clone(), PATHOLOGICAL_TIMEOUT));
+            (name.to_string(), code)
+        })
+        .collect::<Vec<_>>();
+    if !codes.is_empty() {
+        groups.push((
+            "pathological",
+            bench_group(&mut criterion, "pathological", codes),
+        ));
+    }
 
-    if let Some(ref baseline) = baseline {
-        criterion = criterion.save_baseline(baseline.to_string());
+    #[cfg(feature = "compare")]
+    compare_ress(&mut criterion, &lib_codes, save_baseline.as_deref());
+
+    let samples = groups
+        .iter()
+        .map(|(group, sizes)| (*group, read_samples(group, sizes, save_baseline.as_deref())))
+        .collect::<Vec<_>>();
+    if quick {
+        println!("Quick run, the results are low-confidence");
+    }
+    let (tables, workloads): (Vec<_>, Vec<_>) = samples
+        .iter()
+        .partition(|(group, _)| matches!(*group, "lexer" | "pathological"));
+    for (_, samples) in tables {
+        print!("{}", render_samples(samples));
+    }
+    let workloads = workloads
+        .into_iter()
+        .flat_map(|(group, samples)| {
+            samples
+                .iter()
+                .map(move |(_, sample)| (group.to_string(), *sample))
+        })
+        .collect::<Vec<_>>();
+    if !workloads.is_empty() {
+        print!("{}", render_samples(&workloads));
+    }
+
+    let scaling = scaling.map(|sizes| read_samples("parallel", &sizes, save_baseline.as_deref()));
+    if let Some(samples) = &scaling {
+        let speedups = thread_counts(rayon::current_num_threads())
+            .into_iter()
+            .zip(samples.iter().map(|(_, sample)| *sample))
+            .collect::<Vec<_>>();
+        print!("{}", render_speedups(&speedups));
     }
 
-    let mut group = criterion.benchmark_group("lexer");
+    if let Some(path) = json {
+        let groups = samples
+            .iter()
+            .cloned()
+            .chain(scaling.map(|samples| ("parallel", samples)))
+            .collect::<Vec<_>>();
+        let mut records = to_records(&groups, git_commit().as_deref());
+        for record in &mut records {
+            record.statistics = statistics
+                .iter()
+                .find(|(id, _)| record.name == format!("lexer/{}", id))
+                .map(|(_, statistics)| statistics.clone());
+        }
+        or_exit(fs::write(&path, render_records(&records)));
+    }
 
-    let libs = include_str!("./libs.txt").lines();
+    if let Some(baseline) = baseline {
+        let regressions = samples
+            .iter()
+            .map(|(group, samples)| check_regressions(group, samples, &baseline, metric, threshold))
+            .sum::<usize>();
+        if regressions > 0 {
+            eprintln!(
+                "{} benchmarks regressed by more than {}% in {} per second from the `{}` baseline",
+                regressions, threshold, metric, baseline
+            );
+            process::exit(1);
+        }
+    }
+}
 
-    for lib in libs {
-        let (id, code) = get_code(lib).unwrap();
+/// Benchmark lexing each code in the criterion group `name`, returning the input sizes
+fn bench_group(
+    criterion: &mut Criterion,
+    name: &str,
+    codes: impl IntoIterator<Item = (String, String)>,
+) -> Vec<(String, InputSize)> {
+    let mut group = criterion.benchmark_group(name);
+    let mut sizes = vec![];
+    for (id, code) in codes {
         let code = code.as_str();
+        let size = InputSize {
+            bytes: code.len(),
+            tokens: Lexer::new(code).count(),
+        };
 
         group.throughput(Throughput::Bytes(code.len() as u64));
         group.bench_function(&id, |b| {
-            b.iter(|| {
-                black_box(Lexer::new(code).into_iter().count());
-            });
+            // the count is returned so that the tokens are not optimized away
+            b.iter(|| Lexer::new(code).count());
         });
+        sizes.push((id, size));
     }
+    group.finish();
+    sizes
+}
 
+/// Benchmark lexing all the libraries in parallel with each of `thread_counts`,
+/// returning the input sizes of `threads/<n>`
+fn bench_scaling(criterion: &mut Criterion, codes: &[String]) -> Vec<(String, InputSize)> {
+    let size = InputSize {
+        bytes: codes.iter().map(String::len).sum(),
+        tokens: lex_many(codes),
+    };
+    let mut group = criterion.benchmark_group("parallel");
+    group.throughput(Throughput::Bytes(size.bytes as u64));
+    let mut sizes = vec![];
+    for threads in thread_counts(rayon::current_num_threads()) {
+        let pool = or_exit(ThreadPoolBuilder::new().num_threads(threads).build());
+        group.bench_with_input(BenchmarkId::new("threads", threads), codes, |b, codes| {
+            b.iter(|| pool.install(|| lex_many(codes)));
+        });
+        sizes.push((format!("threads/{}", threads), size));
+    }
     group.finish();
+    sizes
+}
+
+/// Lex each library with this lexer and ress side by side,
+/// logging the libraries where the token counts are far apart
+#[cfg(feature = "compare")]
+fn compare_ress(
+    criterion: &mut Criterion,
+    lib_codes: &[(String, String)],
+    save_baseline: Option<&str>,
+) {
+    use benchmark::{
+        comparable_token_count, count_mismatch, render_side_by_side, ress_token_count,
+    };
+
+    let mut rows = vec![];
+    let mut mismatches = vec![];
+    for (id, code) in lib_codes {
+        let group_name = format!("compare-{}", id);
+        let mut group = criterion.benchmark_group(&group_name);
+        group.throughput(Throughput::Bytes(code.len() as u64));
+        group.bench_function("lexer", |b| b.iter(|| Lexer::new(code).count()));
+        group.bench_function("ress", |b| b.iter(|| ress_token_count(code)));
+        group.finish();
+
+        let size = InputSize {
+            bytes: code.len(),
+            tokens: Lexer::new(code).count(),
+        };
+        let sizes = [("lexer".to_string(), size), ("ress".to_string(), size)];
+        let samples = read_samples(&group_name, &sizes, save_baseline);
+        rows.push((id.to_string(), samples[0].1, samples[1].1));
+        mismatches.extend(count_mismatch(
+            id,
+            comparable_token_count(code),
+            "ress",
+            ress_token_count(code),
+        ));
+    }
+    print!("{}", render_side_by_side("ress", &rows));
+    for mismatch in mismatches {
+        println!("{}", mismatch);
+    }
+}
+
+/// Samples of the run, storing the input sizes with it and with the saved baseline
+fn read_samples(
+    group: &str,
+    sizes: &[(String, InputSize)],
+    save_baseline: Option<&str>,
+) -> Vec<(String, Sample)> {
+    let dir = criterion_dir();
+    sizes
+        .iter()
+        .map(|(id, size)| {
+            for baseline in std::iter::once("new").chain(save_baseline) {
+                or_exit(write_input_size(&dir, group, id, baseline, *size));
+            }
+            let sample = or_exit(Sample::read(&dir, group, id, "new", *size));
+            (id.to_string(), sample)
+        })
+        .collect()
+}
+
+/// Print the comparison of the group with `baseline`,
+/// returning the number of benchmarks regressed by more than `threshold` percent
+fn check_regressions(
+    group: &str,
+    samples: &[(String, Sample)],
+    baseline: &str,
+    metric: Metric,
+    threshold: f64,
+) -> usize {
+    let comparisons = or_exit(compare(&criterion_dir(), group, samples, baseline));
+    println!("[{}]", group);
+    print!("{}", render_comparisons(&comparisons, metric, threshold));
+    comparisons
+        .iter()
+        .filter(|comparison| comparison.regressed(metric, threshold))
+        .count()
 }
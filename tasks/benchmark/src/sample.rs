@@ -0,0 +1,185 @@
+//! Throughput of a benchmark in bytes and tokens per second
+
+use std::{
+    fmt::{self, Write},
+    fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::read_median;
+
+/// Throughput to compare with `--metric`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// Bytes per second, what criterion reports
+    Bytes,
+    /// Tokens per second, minified code has far more tokens per byte than formatted code
+    Tokens,
+}
+
+impl FromStr for Metric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bytes" => Ok(Self::Bytes),
+            "tokens" => Ok(Self::Tokens),
+            _ => Err(format!(
+                "invalid metric `{}`, expected `bytes` or `tokens`",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Bytes => "bytes",
+            Self::Tokens => "tokens",
+        })
+    }
+}
+
+/// Size of the input of a benchmark
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputSize {
+    pub bytes: usize,
+    /// Every token of the lexer, including trivia and `EOF`
+    pub tokens: usize,
+}
+
+impl InputSize {
+    #[must_use]
+    pub const fn of(&self, metric: Metric) -> usize {
+        match metric {
+            Metric::Bytes => self.bytes,
+            Metric::Tokens => self.tokens,
+        }
+    }
+}
+
+fn input_size_path(dir: &Path, group: &str, benchmark: &str, baseline: &str) -> PathBuf {
+    dir.join(group)
+        .join(benchmark)
+        .join(baseline)
+        .join("input.json")
+}
+
+/// Store the input size next to criterion's estimates of `baseline`,
+/// as criterion only keeps the throughput in bytes
+/// # Errors
+/// IO error
+pub fn write_input_size(
+    dir: &Path,
+    group: &str,
+    benchmark: &str,
+    baseline: &str,
+    size: InputSize,
+) -> io::Result<()> {
+    let path = input_size_path(dir, group, benchmark, baseline);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::json!({ "bytes": size.bytes, "tokens": size.tokens });
+    fs::write(path, serde_json::to_string(&json)?)
+}
+
+/// The input size stored with `baseline`, `None` for a baseline saved without it
+/// # Errors
+/// The input size is invalid
+pub fn read_input_size(
+    dir: &Path,
+    group: &str,
+    benchmark: &str,
+    baseline: &str,
+) -> Result<Option<InputSize>, String> {
+    let path = input_size_path(dir, group, benchmark, baseline);
+    let json = match fs::read_to_string(&path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("{}: {}", path.display(), e)),
+    };
+    let invalid = || format!("{}: invalid input size", path.display());
+    let size = serde_json::from_str::<serde_json::Value>(&json).map_err(|_| invalid())?;
+    let field = |name: &str| {
+        size[name]
+            .as_u64()
+            .and_then(|n| usize::try_from(n).ok())
+            .ok_or_else(invalid)
+    };
+    Ok(Some(InputSize {
+        bytes: field("bytes")?,
+        tokens: field("tokens")?,
+    }))
+}
+
+/// Median time of a benchmark with the size of its input
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    /// Nanoseconds per iteration
+    pub median: f64,
+    pub size: InputSize,
+}
+
+impl Sample {
+    /// Read the median time and input size of `baseline`,
+    /// with `size` for a baseline saved without its input size
+    /// # Errors
+    /// The estimates are missing or invalid
+    pub fn read(
+        dir: &Path,
+        group: &str,
+        benchmark: &str,
+        baseline: &str,
+        size: InputSize,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            median: read_median(dir, group, benchmark, baseline)?,
+            size: read_input_size(dir, group, benchmark, baseline)?.unwrap_or(size),
+        })
+    }
+
+    /// Bytes or tokens per second
+    #[must_use]
+    pub fn per_second(&self, metric: Metric) -> f64 {
+        #[allow(clippy::cast_precision_loss)]
+        let units = self.size.of(metric) as f64;
+        units / self.median * 1e9
+    }
+}
+
+/// Table of the throughput of every benchmark in MB/s and millions of tokens per second
+#[must_use]
+pub fn render_samples(samples: &[(String, Sample)]) -> String {
+    let width = samples
+        .iter()
+        .map(|(benchmark, _)| benchmark.len())
+        .chain(std::iter::once("benchmark".len()))
+        .max()
+        .unwrap_or_default();
+    let mut s = format!(
+        "{:width$}  {:>10}  {:>10}  {:>10}  {:>10}\n",
+        "benchmark",
+        "bytes",
+        "tokens",
+        "MB/s",
+        "Mtokens/s",
+        width = width
+    );
+    for (benchmark, sample) in samples {
+        writeln!(
+            s,
+            "{:width$}  {:>10}  {:>10}  {:>10.1}  {:>10.1}",
+            benchmark,
+            sample.size.bytes,
+            sample.size.tokens,
+            sample.per_second(Metric::Bytes) / 1e6,
+            sample.per_second(Metric::Tokens) / 1e6,
+            width = width
+        )
+        .unwrap();
+    }
+    s
+}
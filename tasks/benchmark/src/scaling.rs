@@ -0,0 +1,46 @@
+//! Throughput of lexing the libraries in parallel, to see whether it scales with the threads
+
+use std::fmt::Write;
+
+use lexer::Lexer;
+use rayon::prelude::*;
+
+use crate::{Metric, Sample};
+
+/// The thread counts benchmarked in the `parallel` group: 1, 2, 4 and `max`, up to `max`
+#[must_use]
+pub fn thread_counts(max: usize) -> Vec<usize> {
+    let mut counts = [1, 2, 4]
+        .into_iter()
+        .filter(|n| *n < max)
+        .collect::<Vec<_>>();
+    counts.push(max.max(1));
+    counts
+}
+
+/// Lex every code on the current rayon thread pool, returning the number of tokens
+#[must_use]
+pub fn lex_many(codes: &[String]) -> usize {
+    codes.par_iter().map(|code| Lexer::new(code).count()).sum()
+}
+
+/// Table of the throughput for each thread count, with the speedup over the first one
+#[must_use]
+pub fn render_speedups(samples: &[(usize, Sample)]) -> String {
+    let base = samples
+        .first()
+        .map_or(0.0, |(_, sample)| sample.per_second(Metric::Bytes));
+    let mut s = format!("{:>7}  {:>10}  {:>7}\n", "threads", "MB/s", "speedup");
+    for (threads, sample) in samples {
+        let per_second = sample.per_second(Metric::Bytes);
+        writeln!(
+            s,
+            "{:>7}  {:>10.1}  {:>6.2}x",
+            threads,
+            per_second / 1e6,
+            per_second / base
+        )
+        .unwrap();
+    }
+    s
+}
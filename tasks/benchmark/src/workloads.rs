@@ -0,0 +1,259 @@
+//! Synthetic inputs dominated by a single kind of token,
+//! to attribute a change in throughput to the reader of that token
+
+use std::fmt::Write;
+
+/// Names of the workloads, each benchmarked in its own criterion group
+pub const WORKLOADS: [&str; 8] = [
+    "strings",
+    "comments",
+    "identifiers",
+    "long_identifiers",
+    "unicode_identifiers",
+    "numbers",
+    "operators",
+    "templates",
+];
+
+/// Size of a benchmarked workload in bytes
+pub const WORKLOAD_SIZE: usize = 1_000_000;
+
+/// Seed of the benchmarked workloads, fixed so that every run lexes the same input
+pub const WORKLOAD_SEED: u64 = 0x5eed;
+
+const WORDS: [&str; 24] = [
+    "the",
+    "software",
+    "is",
+    "provided",
+    "as",
+    "without",
+    "warranty",
+    "of",
+    "any",
+    "kind",
+    "express",
+    "or",
+    "implied",
+    "copyright",
+    "license",
+    "permission",
+    "hereby",
+    "granted",
+    "free",
+    "charge",
+    "to",
+    "person",
+    "obtaining",
+    "copy",
+];
+
+const OPERATORS: [&str; 32] = [
+    "+", "-", "*", "/", "%", "**", "==", "===", "!=", "!==", "<", ">", "<=", ">=", "<<", ">>",
+    ">>>", "&", "|", "^", "&&", "||", "??", "=", "+=", "-=", "*=", "**=", "&&=", "||=", "??=",
+    ">>>=",
+];
+
+/// `ID_Start` code points beyond ASCII: Latin, Greek, Cyrillic, CJK, Hangul,
+/// then of the astral planes, mathematical letters, Deseret and a CJK extension
+const UNICODE_START: [char; 16] = [
+    'é', 'ß', 'ñ', 'ø', 'λ', 'Ω', 'ж', 'Я', '中', '文', '字', '변', '𝐀', '𝑥', '𐐀', '𠀀',
+];
+
+/// `ID_Continue` but not `ID_Start` code points: a combining acute accent, an Arabic-Indic
+/// and a fullwidth digit, and the middle dot
+const UNICODE_CONTINUE: [char; 4] = ['\u{301}', '٣', '１', '·'];
+
+/// xorshift64*, so the workloads are the same on every platform and Rust version
+struct Rng(u64);
+
+impl Rng {
+    const fn new(seed: u64) -> Self {
+        // xorshift is stuck at 0
+        Self(seed | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// In `0..n`
+    #[allow(clippy::cast_possible_truncation)]
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    /// In `min..=max`
+    fn between(&mut self, min: usize, max: usize) -> usize {
+        min + self.below(max - min + 1)
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.below(items.len())]
+    }
+
+    /// `min..=max` words
+    fn words(&mut self, min: usize, max: usize) -> String {
+        (0..self.between(min, max))
+            .map(|_| self.pick(&WORDS))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn identifier(&mut self) -> String {
+        const START: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ_$";
+        const PART: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ_$0123456789";
+        let len = self.between(1, 12);
+        let mut s = String::with_capacity(len + 2);
+        s.push(char::from(START[self.below(START.len())]));
+        for _ in 1..len {
+            s.push(char::from(PART[self.below(PART.len())]));
+        }
+        if self.below(16) == 0 {
+            s.push('μ');
+        }
+        s
+    }
+
+    /// `camelCase` of `min..=max` words, an ASCII run from start to end
+    fn long_identifier(&mut self, min: usize, max: usize) -> String {
+        let mut s = String::from(self.pick(&WORDS));
+        for _ in 1..self.between(min, max) {
+            let word = self.pick(&WORDS);
+            s.push(word.as_bytes()[0].to_ascii_uppercase().into());
+            s.push_str(&word[1..]);
+        }
+        s
+    }
+
+    /// An identifier of [`UNICODE_START`] and [`UNICODE_CONTINUE`] only, which the lexer
+    /// reads with `UnicodeID` rather than its ASCII tables, compared with `identifier`
+    fn unicode_identifier(&mut self) -> String {
+        let len = self.between(1, 8);
+        let mut s = String::with_capacity(len * 4);
+        s.push(UNICODE_START[self.below(UNICODE_START.len())]);
+        for _ in 1..len {
+            if self.below(4) == 0 {
+                s.push(UNICODE_CONTINUE[self.below(UNICODE_CONTINUE.len())]);
+            } else {
+                s.push(UNICODE_START[self.below(UNICODE_START.len())]);
+            }
+        }
+        s
+    }
+
+    fn number(&mut self) -> String {
+        let n = self.next() % 1_000_000;
+        match self.below(7) {
+            0 => format!("{}", n),
+            1 => format!("{}.{}", n, self.below(1000)),
+            2 => format!("{}e-{}", n, self.below(20)),
+            3 => format!("0x{:X}", n),
+            4 => format!("0b{:b}", n % 256),
+            5 => format!("0o{:o}", n),
+            _ => format!("{}n", n),
+        }
+    }
+
+    fn string_content(&mut self) -> String {
+        let mut s = self.words(2, 9);
+        match self.below(4) {
+            0 => s.push_str("\\n"),
+            1 => s.push_str("\\u00e9"),
+            2 => s.push_str("\\\\"),
+            _ => {}
+        }
+        s
+    }
+}
+
+/// One line of the workload `name`, `None` for an unknown workload
+fn line(name: &str, rng: &mut Rng) -> Option<String> {
+    let line = match name {
+        "strings" => {
+            let quote = if rng.below(2) == 0 { '"' } else { '\'' };
+            let content = rng.string_content();
+            format!("s = {quote}{content}{quote};\n")
+        }
+        "comments" => {
+            if rng.below(4) == 0 {
+                format!("// {}\n", rng.words(4, 11))
+            } else {
+                let mut s = String::from("/*!\n");
+                for _ in 0..rng.between(1, 6) {
+                    writeln!(s, " * {}", rng.words(4, 11)).unwrap();
+                }
+                s.push_str(" */\n");
+                s
+            }
+        }
+        "identifiers" => {
+            let ids = [(); 5].map(|_| rng.identifier());
+            format!(
+                "{} = {}.{}({}, {});\n",
+                ids[0], ids[1], ids[2], ids[3], ids[4]
+            )
+        }
+        // the same lines as `identifiers`
+        "long_identifiers" => {
+            let ids = [(); 5].map(|_| rng.long_identifier(3, 6));
+            format!(
+                "{} = {}.{}({}, {});\n",
+                ids[0], ids[1], ids[2], ids[3], ids[4]
+            )
+        }
+        "unicode_identifiers" => {
+            let ids = [(); 5].map(|_| rng.unicode_identifier());
+            format!(
+                "{} = {}.{}({}, {});\n",
+                ids[0], ids[1], ids[2], ids[3], ids[4]
+            )
+        }
+        "numbers" => {
+            let numbers = (0..rng.between(4, 7))
+                .map(|_| rng.number())
+                .collect::<Vec<_>>();
+            format!("n = [{}];\n", numbers.join(", "))
+        }
+        "operators" => {
+            let mut s = String::from("a");
+            for _ in 0..rng.between(4, 11) {
+                write!(s, " {} b", rng.pick(&OPERATORS)).unwrap();
+            }
+            s.push_str(";\n");
+            s
+        }
+        "templates" => {
+            let head = rng.words(1, 4);
+            let tail = rng.string_content();
+            let expression = rng.identifier();
+            format!("t = `{head} ${{{expression}}} {tail}`;\n")
+        }
+        _ => return None,
+    };
+    Some(line)
+}
+
+/// The workload `name` generated from `seed`, whole lines up to at least `size` bytes.
+/// `None` for an unknown workload.
+#[must_use]
+pub fn generate_workload(name: &str, seed: u64, size: usize) -> Option<String> {
+    if !WORKLOADS.contains(&name) {
+        return None;
+    }
+    let mut rng = Rng::new(seed);
+    let mut code = String::with_capacity(size + 256);
+    while code.len() < size {
+        code.push_str(&line(name, &mut rng)?);
+    }
+    Some(code)
+}
+
+/// The benchmarked workload `name`, see [`WORKLOAD_SIZE`] and [`WORKLOAD_SEED`]
+#[must_use]
+pub fn workload(name: &str) -> Option<String> {
+    generate_workload(name, WORKLOAD_SEED, WORKLOAD_SIZE)
+}
@@ -0,0 +1,735 @@
+use std::fs;
+
+use benchmark::{
+    check_offline, comparable_token_count, compare, compare_records, count_allocations,
+    count_mismatch, download, filter_libs, generate_workload, get_code, get_codes, lex_allocations,
+    lex_many, lex_within, lib_filename, libs, matches_filter, parse_libs, parse_records,
+    pathological, punctuator_code, read_cached, read_input_size, render_allocations,
+    render_comparisons, render_records, render_samples, render_side_by_side, render_source_stats,
+    render_speedups, select_libs, thread_counts, to_records, validate_libs, write_cached,
+    write_input_size, Allocations, CountingAllocator, DownloadOptions, InputSize, Metric, Sample,
+    SourceStats, ALLOCATION_BUDGET, DEFAULT_CACHE_DIR, PATHOLOGICAL, PATHOLOGICAL_TIMEOUT,
+    USER_AGENT, WORKLOADS,
+};
+use lexer::{estimate_token_count, Kind, Lexer};
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    path::Path,
+    thread,
+    time::Duration,
+};
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[test]
+fn cached_download() {
+    let dir = std::env::temp_dir().join(format!("benchmark-cache-{}", std::process::id()));
+    assert_eq!(read_cached(&dir, "lib.js"), None);
+
+    write_cached(&dir, "lib.js", "let a = 1;").unwrap();
+    assert_eq!(read_cached(&dir, "lib.js").as_deref(), Some("let a = 1;"));
+
+    // truncated by an interrupted download
+    fs::write(dir.join("lib.js"), "let a").unwrap();
+    assert_eq!(read_cached(&dir, "lib.js"), None);
+
+    // same length, different content
+    fs::write(dir.join("lib.js"), "let b = 1;").unwrap();
+    assert_eq!(read_cached(&dir, "lib.js"), None);
+
+    // downloaded before checksums were recorded
+    write_cached(&dir, "lib.js", "let a = 1;").unwrap();
+    fs::remove_file(dir.join("lib.js.checksum")).unwrap();
+    assert_eq!(read_cached(&dir, "lib.js"), None);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn offline() {
+    let dir = std::env::temp_dir().join(format!("benchmark-offline-{}", std::process::id()));
+    let libs = [
+        "https://cdn.jsdelivr.net/npm/a@1.0.0/a.js",
+        "https://cdn.jsdelivr.net/npm/b@1.0.0/dist/b.js",
+    ];
+    write_cached(&dir, "a.js", "let a = 1;").unwrap();
+
+    let error = check_offline(&dir, libs).unwrap_err();
+    assert!(!error.contains("a.js"), "{}", error);
+    assert!(
+        error.contains(&format!("{} from {}", dir.join("b.js").display(), libs[1])),
+        "{}",
+        error
+    );
+    assert!(
+        error.contains("cargo benchmark fetch --cache-dir"),
+        "{}",
+        error
+    );
+
+    let error = get_code(libs[1], &dir, true, &DownloadOptions::default()).unwrap_err();
+    assert!(error.contains("b.js"), "{}", error);
+    assert_eq!(
+        get_code(libs[0], &dir, true, &DownloadOptions::default()).unwrap(),
+        ("a.js".to_string(), "let a = 1;".to_string())
+    );
+
+    write_cached(&dir, "b.js", "let b = 1;").unwrap();
+    assert_eq!(check_offline(&dir, libs), Ok(()));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn lib_list() {
+    assert!(validate_libs(libs()).is_ok());
+    assert!(libs().any(|lib| lib.ends_with(".min.js")));
+    assert!(libs().any(|lib| !lib.ends_with(".min.js")));
+
+    let list = "# formatted\nhttps://a.com/a.js\n\n  https://b.com/b.js  \n# minified\n";
+    assert_eq!(
+        parse_libs(list).collect::<Vec<_>>(),
+        ["https://a.com/a.js", "https://b.com/b.js"]
+    );
+
+    let dir = std::env::temp_dir().join(format!("benchmark-libs-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("libs.txt");
+    fs::write(&path, list).unwrap();
+    let url = "https://c.com/c.js".to_string();
+    assert_eq!(
+        select_libs(None, vec![]).unwrap(),
+        libs().map(ToString::to_string).collect::<Vec<_>>()
+    );
+    assert_eq!(select_libs(None, vec![url.clone()]).unwrap(), [url.clone()]);
+    assert_eq!(
+        select_libs(Some(&path), vec![url.clone()]).unwrap(),
+        ["https://a.com/a.js", "https://b.com/b.js", &url]
+    );
+    let error = select_libs(Some(&dir.join("missing.txt")), vec![]).unwrap_err();
+    assert!(error.contains("missing.txt"), "{}", error);
+
+    let error = validate_libs([
+        "https://a.com/a.js",
+        "a.js",
+        "ftp://b.com/b.js",
+        "https://c.com/",
+        "https://d.com/a.js",
+    ])
+    .unwrap_err();
+    assert!(error.starts_with("4 invalid benchmark urls:"), "{}", error);
+    for lib in ["  a.js:", "  ftp://b.com/b.js:", "  https://c.com/:"] {
+        assert!(error.contains(lib), "{}", error);
+    }
+    assert!(
+        error.contains("  https://d.com/a.js: same file name as https://a.com/a.js"),
+        "{}",
+        error
+    );
+
+    // every failure is reported, not only the first
+    write_cached(&dir, "b.js", "let b = 1;").unwrap();
+    let libs = ["https://a.com/a.js", "https://b.com/b.js", &url];
+    let error = get_codes(libs, &dir, true, &DownloadOptions::default()).unwrap_err();
+    assert!(
+        error.starts_with("Failed to get 2 benchmark files:"),
+        "{}",
+        error
+    );
+    assert!(
+        error.contains("a.js") && error.contains("c.js"),
+        "{}",
+        error
+    );
+    assert_eq!(
+        get_codes(
+            ["https://b.com/b.js"],
+            &dir,
+            true,
+            &DownloadOptions::default()
+        )
+        .unwrap(),
+        [("b.js".to_string(), "let b = 1;".to_string())]
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Serve each of `responses` to one connection on a local port, returning the url
+/// and the `User-Agent` of each request
+fn serve(responses: Vec<&'static str>) -> (String, thread::JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/lib.js", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+        let mut user_agents = vec![];
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                if let Some(user_agent) = line.strip_prefix("User-Agent: ") {
+                    user_agents.push(user_agent.trim_end().to_string());
+                }
+                line.clear();
+            }
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+        user_agents
+    });
+    (url, handle)
+}
+
+const OK: &str = "HTTP/1.1 200 OK\r\nContent-Length: 10\r\nConnection: close\r\n\r\nlet a = 1;";
+const ERROR: &str =
+    "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const NOT_FOUND: &str = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const TRUNCATED: &str = "HTTP/1.1 200 OK\r\nContent-Length: 10\r\nConnection: close\r\n\r\nlet";
+
+#[test]
+fn download_retries() {
+    let options = DownloadOptions {
+        retries: 2,
+        timeout: Duration::from_secs(10),
+        backoff: Duration::from_millis(1),
+    };
+
+    let (url, server) = serve(vec![ERROR, TRUNCATED, OK]);
+    assert_eq!(download(&url, &options).unwrap(), "let a = 1;");
+    assert_eq!(server.join().unwrap(), [USER_AGENT; 3]);
+
+    let (url, server) = serve(vec![ERROR, ERROR, ERROR]);
+    assert_eq!(
+        download(&url, &options).unwrap_err(),
+        "HTTP 500 Internal Server Error after 3 attempts"
+    );
+    server.join().unwrap();
+
+    // not retried
+    let (url, server) = serve(vec![NOT_FOUND]);
+    assert_eq!(download(&url, &options).unwrap_err(), "HTTP 404 Not Found");
+    server.join().unwrap();
+
+    // nothing is cached after a failure
+    let dir = std::env::temp_dir().join(format!("benchmark-download-{}", std::process::id()));
+    let (url, server) = serve(vec![TRUNCATED, TRUNCATED, TRUNCATED]);
+    let error = get_code(&url, &dir, false, &options).unwrap_err();
+    server.join().unwrap();
+    assert!(error.contains(&url), "{}", error);
+    assert!(
+        error.contains(&dir.join("lib.js").display().to_string()),
+        "{}",
+        error
+    );
+    // a read error or a length mismatch, depending on when the end of the body is noticed
+    assert!(error.ends_with("after 3 attempts"), "{}", error);
+    assert_eq!(read_cached(&dir, "lib.js"), None);
+    assert!(!dir.join("lib.js").exists());
+
+    let (url, server) = serve(vec![ERROR, OK]);
+    assert_eq!(
+        get_code(&url, &dir, false, &options).unwrap(),
+        ("lib.js".to_string(), "let a = 1;".to_string())
+    );
+    server.join().unwrap();
+    assert_eq!(read_cached(&dir, "lib.js").as_deref(), Some("let a = 1;"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Criterion's `estimates.json` with only the fields read
+fn write_estimates(dir: &Path, benchmark: &str, baseline: &str, median: f64) {
+    let dir = dir.join("lexer").join(benchmark).join(baseline);
+    fs::create_dir_all(&dir).unwrap();
+    let json = format!(
+        r#"{{"mean":{{"point_estimate":1.0,"standard_error":0.1}},"median":{{"confidence_interval":{{"confidence_level":0.95,"lower_bound":{0},"upper_bound":{0}}},"point_estimate":{0},"standard_error":0.5}}}}"#,
+        median
+    );
+    fs::write(dir.join("estimates.json"), json).unwrap();
+}
+
+#[test]
+fn baseline_comparison() {
+    let dir = std::env::temp_dir().join(format!("benchmark-criterion-{}", std::process::id()));
+    let size = |bytes, tokens| InputSize { bytes, tokens };
+    let sample = |median, size| Sample { median, size };
+    write_estimates(&dir, "a.js", "main", 1_000_000.0);
+    write_input_size(&dir, "lexer", "a.js", "main", size(1_000_000, 100_000)).unwrap();
+    // saved without its input size
+    write_estimates(&dir, "b.js", "main", 2_000_000.0);
+    // more tokens in the new run
+    write_estimates(&dir, "c.js", "main", 1_000_000.0);
+    write_input_size(&dir, "lexer", "c.js", "main", size(1_000_000, 100_000)).unwrap();
+
+    assert_eq!(
+        read_input_size(&dir, "lexer", "a.js", "main"),
+        Ok(Some(size(1_000_000, 100_000)))
+    );
+    assert_eq!(read_input_size(&dir, "lexer", "b.js", "main"), Ok(None));
+
+    let new = [
+        (
+            "a.js".to_string(),
+            sample(1_100_000.0, size(1_000_000, 100_000)),
+        ),
+        (
+            "b.js".to_string(),
+            sample(1_900_000.0, size(2_000_000, 300_000)),
+        ),
+        (
+            "c.js".to_string(),
+            sample(1_200_000.0, size(1_000_000, 120_000)),
+        ),
+    ];
+    let comparisons = compare(&dir, "lexer", &new, "main").unwrap();
+    let baselines = comparisons
+        .iter()
+        .map(|comparison| comparison.baseline)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        baselines,
+        [
+            sample(1_000_000.0, size(1_000_000, 100_000)),
+            sample(2_000_000.0, size(2_000_000, 300_000)),
+            sample(1_000_000.0, size(1_000_000, 100_000)),
+        ]
+    );
+    let deltas = |metric| {
+        comparisons
+            .iter()
+            .map(|comparison| (comparison.delta(metric) * 100.0).round() / 100.0)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(deltas(Metric::Bytes), [10.0, -5.0, 20.0]);
+    assert_eq!(deltas(Metric::Tokens), [10.0, -5.0, 0.0]);
+    let regressed = |metric, threshold| {
+        comparisons
+            .iter()
+            .filter(|comparison| comparison.regressed(metric, threshold))
+            .map(|comparison| comparison.benchmark.as_str())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(regressed(Metric::Bytes, 5.0), ["a.js", "c.js"]);
+    assert_eq!(regressed(Metric::Tokens, 5.0), ["a.js"]);
+    assert_eq!(regressed(Metric::Tokens, 10.5), Vec::<&str>::new());
+
+    assert_eq!(
+        render_comparisons(&comparisons, Metric::Tokens, 5.0),
+        "benchmark              baseline                   new     delta\n\
+         a.js            100.0 Mtokens/s        90.9 Mtokens/s   +10.00%  regressed\n\
+         b.js            150.0 Mtokens/s       157.9 Mtokens/s    -5.00%\n\
+         c.js            100.0 Mtokens/s       100.0 Mtokens/s    +0.00%\n"
+    );
+    assert_eq!(
+        render_samples(&new),
+        "benchmark       bytes      tokens        MB/s   Mtokens/s\n\
+         a.js          1000000      100000       909.1        90.9\n\
+         b.js          2000000      300000      1052.6       157.9\n\
+         c.js          1000000      120000       833.3       100.0\n"
+    );
+
+    let error = compare(&dir, "lexer", &new, "missing").unwrap_err();
+    assert!(error.contains("missing"), "{}", error);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn workloads() {
+    // the tokens each workload is made of
+    let dominant: [fn(Kind) -> bool; 8] = [
+        |kind| matches!(kind, Kind::Str(_)),
+        |kind| matches!(kind, Kind::Comment | Kind::MultilineComment),
+        |kind| kind == Kind::Ident,
+        |kind| kind == Kind::Ident,
+        |kind| kind == Kind::Ident,
+        |kind| matches!(kind, Kind::Number(_)),
+        |kind| !matches!(kind, Kind::Ident | Kind::Semicolon),
+        |kind| kind == Kind::Template,
+    ];
+    for (name, dominant) in WORKLOADS.into_iter().zip(dominant) {
+        let code = generate_workload(name, 1, 10_000).unwrap();
+        assert_eq!(generate_workload(name, 1, 10_000).as_ref(), Some(&code));
+        assert_ne!(generate_workload(name, 2, 10_000).as_ref(), Some(&code));
+        assert!(code.len() >= 10_000 && code.len() < 11_000, "{}", name);
+        assert!(code.ends_with('\n'), "{}", name);
+
+        let mut lexer = Lexer::new(&code);
+        let tokens = lexer
+            .by_ref()
+            .filter(|token| {
+                !matches!(
+                    token.kind(),
+                    Kind::WhiteSpace | Kind::LineTerminator | Kind::EOF
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(lexer.errors(), [], "{}", name);
+        let bytes = tokens.iter().map(lexer::Token::len).sum::<usize>();
+        let dominant_bytes = tokens
+            .iter()
+            .filter(|token| dominant(*token.kind()))
+            .map(lexer::Token::len)
+            .sum::<usize>();
+        assert!(
+            dominant_bytes * 2 > bytes,
+            "{} {}/{}",
+            name,
+            dominant_bytes,
+            bytes
+        );
+    }
+    assert_eq!(generate_workload("regexes", 1, 10_000), None);
+}
+
+#[test]
+fn unicode_identifiers() {
+    let code = generate_workload("unicode_identifiers", 1, 100_000).unwrap();
+    assert!(code.chars().any(|c| c > '\u{ffff}'));
+    let kinds = Lexer::new(&code)
+        .map(|token| *token.kind())
+        .collect::<Vec<_>>();
+    assert!(!kinds.contains(&Kind::Unknown));
+    // 5 per line, as in `identifiers`
+    let identifiers = kinds.iter().filter(|kind| **kind == Kind::Ident).count();
+    assert_eq!(identifiers, 5 * code.lines().count());
+}
+
+#[test]
+fn long_identifiers() {
+    let code = generate_workload("long_identifiers", 1, 100_000).unwrap();
+    assert!(code.is_ascii());
+    let identifiers = Lexer::new(&code)
+        .filter(|token| *token.kind() == Kind::Ident)
+        .map(|token| token.len())
+        .collect::<Vec<_>>();
+    assert_eq!(identifiers.len(), 5 * code.lines().count());
+    // at least 3 words of 2 letters
+    assert!(identifiers.iter().all(|len| *len >= 6));
+}
+
+#[test]
+fn token_count_estimate() {
+    // the libraries downloaded by `cargo benchmark` or `cargo benchmark fetch`, if any
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../..")
+        .join(DEFAULT_CACHE_DIR);
+    let libs = libs()
+        .map(|lib| lib_filename(lib).unwrap())
+        .filter_map(|filename| read_cached(&dir, &filename).map(|code| (filename, code)));
+    let workloads = WORKLOADS.into_iter().map(|name| {
+        (
+            name.to_string(),
+            generate_workload(name, 1, 100_000).unwrap(),
+        )
+    });
+    for (name, code) in libs
+        .chain(workloads)
+        .chain(std::iter::once(punctuator_code()))
+    {
+        let count = Lexer::new(&code).count();
+        let estimate = estimate_token_count(&code);
+        assert!(
+            count <= estimate && estimate <= 2 * count,
+            "{} {} {}",
+            name,
+            count,
+            estimate
+        );
+    }
+}
+
+#[test]
+fn allocations() {
+    let (_, allocations) = count_allocations(|| Vec::<u64>::with_capacity(100));
+    assert_eq!(
+        allocations,
+        Allocations {
+            count: 1,
+            bytes: 800
+        }
+    );
+
+    let code = generate_workload("identifiers", 1, 100_000).unwrap();
+    let (_, allocations) = count_allocations(|| Lexer::new(&code).count());
+    assert_eq!(allocations, Allocations::default());
+
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../..")
+        .join(DEFAULT_CACHE_DIR);
+    let libs = libs()
+        .map(|lib| lib_filename(lib).unwrap())
+        .filter_map(|filename| read_cached(&dir, &filename).map(|code| (filename, code)));
+    let workloads = WORKLOADS.into_iter().map(|name| {
+        (
+            name.to_string(),
+            generate_workload(name, 1, 100_000).unwrap(),
+        )
+    });
+    // giant escapes and unicode identifiers
+    let unusual = [
+        format!("a{} = 1;", "\\u{0062}".repeat(50_000)),
+        "μέτρο + ".repeat(50_000),
+    ];
+    let unusual = unusual
+        .into_iter()
+        .enumerate()
+        .map(|(i, code)| (format!("unusual{}", i), code));
+    for (name, code) in libs
+        .chain(workloads)
+        .chain(std::iter::once(punctuator_code()))
+        .chain(unusual)
+    {
+        let allocations = lex_allocations(&code);
+        assert!(
+            allocations.count <= ALLOCATION_BUDGET,
+            "{} {:?}",
+            name,
+            allocations
+        );
+    }
+
+    let allocations = [
+        (
+            "a".to_string(),
+            Allocations {
+                count: 3,
+                bytes: 96,
+            },
+        ),
+        (
+            "b".to_string(),
+            Allocations {
+                count: 65,
+                bytes: 1 << 20,
+            },
+        ),
+    ];
+    assert_eq!(
+        render_allocations(&allocations),
+        "\
+benchmark  allocations         bytes
+a                    3            96
+b                   65       1048576  over budget
+"
+    );
+}
+
+#[test]
+fn differential() {
+    assert_eq!(
+        comparable_token_count("let a = 1; // comment\n/* b */ f(a);\n"),
+        12
+    );
+
+    assert_eq!(count_mismatch("a.js", 1000, "ress", 1090), None);
+    assert_eq!(count_mismatch("a.js", 1000, "ress", 910), None);
+    assert_eq!(
+        count_mismatch("a.js", 1000, "ress", 1200).as_deref(),
+        Some("[a.js] - 1000 tokens but 1200 with ress (+20.0%), one of the lexers likely mis-lexes something")
+    );
+    assert!(count_mismatch("b.js", 0, "ress", 5).is_some());
+
+    let size = InputSize {
+        bytes: 1_000_000,
+        tokens: 100_000,
+    };
+    let sample = |median| Sample { median, size };
+    let rows = [
+        ("a.js".to_string(), sample(1_000_000.0), sample(2_000_000.0)),
+        ("b.js".to_string(), sample(4_000_000.0), sample(2_000_000.0)),
+    ];
+    assert_eq!(
+        render_side_by_side("ress", &rows),
+        "benchmark  lexer MB/s   ress MB/s   speedup\n\
+         a.js           1000.0       500.0     2.00x\n\
+         b.js            250.0       500.0     0.50x\n"
+    );
+}
+
+#[test]
+fn filter() {
+    assert!(matches_filter("d3.js", None));
+    assert!(matches_filter("d3.js", Some("d3")));
+    assert!(!matches_filter("strings", Some("d3")));
+
+    let libs = [
+        "https://a.com/react-dom.development.js",
+        "https://a.com/d3.js",
+        "https://a.com/react-dom.production.min.js",
+    ]
+    .map(ToString::to_string);
+    assert_eq!(filter_libs(libs.to_vec(), None, None), libs);
+    assert_eq!(filter_libs(libs.to_vec(), None, Some(2)), libs[..2]);
+    assert_eq!(
+        filter_libs(libs.to_vec(), Some("react"), None),
+        [libs[0].clone(), libs[2].clone()]
+    );
+    assert_eq!(
+        filter_libs(libs.to_vec(), Some("react"), Some(1)),
+        libs[..1]
+    );
+    // the host is not part of the name
+    assert_eq!(
+        filter_libs(libs.to_vec(), Some("a.com"), None),
+        [] as [String; 0]
+    );
+}
+
+#[test]
+fn pathological_inputs() {
+    for name in PATHOLOGICAL {
+        let code = pathological(name).unwrap();
+        assert_eq!(pathological(name).as_ref(), Some(&code), "{}", name);
+        assert!(code.len() >= 300_000, "{} {}", name, code.len());
+        let tokens = lex_within(name, code, PATHOLOGICAL_TIMEOUT).unwrap();
+        assert!(tokens > 1, "{}", name);
+    }
+    assert_eq!(pathological("regexes"), None);
+
+    let count = |name| lex_within(name, pathological(name).unwrap(), PATHOLOGICAL_TIMEOUT).unwrap();
+    // a token and EOF
+    assert_eq!(count("long_string"), 2);
+    assert_eq!(count("file_comment"), 2);
+    // `x`, then a space, `/`, a space and a regex per ambiguity
+    assert_eq!(count("regex_or_divide"), 2 + 50_000 * 4);
+
+    assert_eq!(
+        lex_within("ok", "a = 1".to_string(), PATHOLOGICAL_TIMEOUT),
+        Ok(6)
+    );
+    assert_eq!(
+        lex_within("hang", "a".repeat(10_000_000), Duration::from_nanos(1)),
+        Err("Lexing `hang` took longer than 1ns".to_string())
+    );
+}
+
+#[test]
+fn scaling() {
+    assert_eq!(thread_counts(1), [1]);
+    assert_eq!(thread_counts(2), [1, 2]);
+    assert_eq!(thread_counts(3), [1, 2, 3]);
+    assert_eq!(thread_counts(16), [1, 2, 4, 16]);
+    assert_eq!(thread_counts(0), [1]);
+
+    let codes = vec!["a = 1".to_string(), "b".to_string()];
+    assert_eq!(lex_many(&codes), 6 + 2);
+    assert_eq!(lex_many(&[]), 0);
+
+    let sample = |median| Sample {
+        median,
+        size: InputSize {
+            bytes: 1_000_000,
+            tokens: 100_000,
+        },
+    };
+    assert_eq!(
+        render_speedups(&[(1, sample(4e6)), (2, sample(2.5e6)), (4, sample(1e6))]),
+        "threads        MB/s  speedup
+      1       250.0    1.00x
+      2       400.0    1.60x
+      4      1000.0    4.00x
+"
+    );
+}
+
+#[test]
+fn history() {
+    let sample = |median| Sample {
+        median,
+        size: InputSize {
+            bytes: 1_000_000,
+            tokens: 100_000,
+        },
+    };
+    let groups = [
+        (
+            "lexer",
+            vec![
+                ("react.js".to_string(), sample(2e6)),
+                ("vue.js".to_string(), sample(4e6)),
+            ],
+        ),
+        ("strings", vec![("lexer".to_string(), sample(1e6))]),
+    ];
+    let old = to_records(&groups, Some("abc123"));
+    assert_eq!(
+        old.iter()
+            .map(|record| record.name.as_str())
+            .collect::<Vec<_>>(),
+        ["lexer/react.js", "lexer/vue.js", "strings/lexer"]
+    );
+
+    let json = render_records(&old);
+    let value = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+    let first = &value.as_array().unwrap()[0];
+    assert_eq!(first["name"].as_str(), Some("lexer/react.js"));
+    assert_eq!(first["bytes"].as_u64(), Some(1_000_000));
+    assert_eq!(first["tokens"].as_u64(), Some(100_000));
+    assert_eq!(first["median_ns"].as_f64(), Some(2e6));
+    assert_eq!(first["megabytes_per_second"].as_f64(), Some(500.0));
+    assert_eq!(first["tokens_per_second"].as_f64(), Some(5e7));
+    assert_eq!(first["commit"].as_str(), Some("abc123"));
+    assert_eq!(parse_records(&json), Ok(old.clone()));
+    let mut with_statistics = old.clone();
+    with_statistics[0].statistics = Some(SourceStats::new("a = b;"));
+    assert_eq!(
+        parse_records(&render_records(&with_statistics)),
+        Ok(with_statistics)
+    );
+    let without_commit = to_records(&groups, None);
+    assert_eq!(
+        parse_records(&render_records(&without_commit)),
+        Ok(without_commit)
+    );
+
+    let new = to_records(
+        &[(
+            "lexer",
+            vec![
+                ("vue.js".to_string(), sample(5e6)),
+                ("jquery.js".to_string(), sample(1e6)),
+            ],
+        )],
+        Some("def456"),
+    );
+    let comparisons = compare_records(&old, &new);
+    assert_eq!(comparisons.len(), 1);
+    assert_eq!(comparisons[0].benchmark, "lexer/vue.js");
+    assert_eq!(comparisons[0].delta(Metric::Bytes), 25.0);
+
+    assert_eq!(
+        parse_records("{}"),
+        Err("expected an array of benchmarks".to_string())
+    );
+    assert_eq!(
+        parse_records(r#"[{"name": "lexer/a.js", "bytes": 1, "tokens": 1}]"#),
+        Err("`lexer/a.js` has no valid `median_ns`".to_string())
+    );
+}
+
+#[test]
+fn source_stats() {
+    let stats = SourceStats::new("a = b; // c\nd = a + b;");
+    assert_eq!(stats.bytes, 22);
+    assert_eq!(stats.tokens, 19);
+    assert_eq!(
+        stats.top_kinds,
+        [
+            ("WhiteSpace".to_string(), 7),
+            ("Ident".to_string(), 5),
+            ("Eq".to_string(), 2)
+        ]
+    );
+    assert_eq!(
+        render_source_stats(&[
+            ("a.js".to_string(), stats),
+            ("empty.js".to_string(), SourceStats::new("")),
+        ]),
+        "benchmark       bytes      tokens   trivia  top kinds
+a.js               22          19    47.4%  WhiteSpace 36.8%, Ident 26.3%, Eq 10.5%
+empty.js            0           0     0.0%
+"
+    );
+}
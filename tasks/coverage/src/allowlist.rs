@@ -0,0 +1,176 @@
+//! Expected failures allowlist
+//!
+//! One path or glob per line, relative to the suite root, with an optional `# reason`:
+//!
+//! ```text
+//! # lines starting with `#` are comments
+//! language/comments/hashbang/**  # hashbang is not implemented
+//! built-ins/RegExp/*-v-flag*.js
+//! ```
+//!
+//! `*` and `?` match within a path segment, `**` matches any number of segments.
+
+use std::{fmt, fs, io, path::Path};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectedFailure {
+    /// Path or glob relative to the suite root
+    pub pattern: String,
+    /// Text after `#`
+    pub reason: Option<String>,
+    /// Line number in the allowlist file, starting from 1
+    pub line: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct ExpectedFailures {
+    entries: Vec<ExpectedFailure>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl ExpectedFailures {
+    /// Read the allowlist file, a missing file is an empty list
+    /// # Errors
+    /// IO error or invalid lines
+    pub fn read(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).map_err(|errors| {
+                let errors = errors
+                    .iter()
+                    .map(|e| format!("  {}:{e}", path.display()))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid expected failures file\n{errors}"),
+                )
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// # Errors
+    /// Every invalid line
+    pub fn parse(text: &str) -> Result<Self, Vec<ParseError>> {
+        let mut entries: Vec<ExpectedFailure> = vec![];
+        let mut errors = vec![];
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let (pattern, reason) = match line.split_once('#') {
+                Some((pattern, reason)) => (pattern.trim(), Some(reason.trim())),
+                None => (line.trim(), None),
+            };
+            if pattern.is_empty() {
+                continue;
+            }
+            if let Err(message) = validate_pattern(pattern) {
+                errors.push(ParseError {
+                    line: line_number,
+                    message: format!("`{pattern}` {message}"),
+                });
+                continue;
+            }
+            if let Some(first) = entries.iter().find(|e| e.pattern == pattern) {
+                errors.push(ParseError {
+                    line: line_number,
+                    message: format!("`{pattern}` is a duplicate of line {}", first.line),
+                });
+                continue;
+            }
+            entries.push(ExpectedFailure {
+                pattern: pattern.to_string(),
+                reason: reason.filter(|r| !r.is_empty()).map(ToString::to_string),
+                line: line_number,
+            });
+        }
+        if errors.is_empty() {
+            Ok(Self { entries })
+        } else {
+            Err(errors)
+        }
+    }
+
+    #[must_use]
+    pub fn entries(&self) -> &[ExpectedFailure] {
+        &self.entries
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Find the entry matching a path relative to the suite root
+    #[must_use]
+    pub fn find(&self, path: &str) -> Option<&ExpectedFailure> {
+        self.entries.iter().find(|e| glob_match(&e.pattern, path))
+    }
+
+    #[must_use]
+    pub fn is_expected(&self, path: &str) -> bool {
+        self.find(path).is_some()
+    }
+}
+
+fn validate_pattern(pattern: &str) -> Result<(), &'static str> {
+    if pattern.contains(char::is_whitespace) {
+        return Err("contains whitespace, use `# reason` for comments");
+    }
+    if pattern.contains('\\') {
+        return Err("contains `\\`, use `/` as the path separator");
+    }
+    if pattern.starts_with('/') {
+        return Err("is absolute, paths are relative to the suite root");
+    }
+    for segment in pattern.split('/') {
+        if segment.is_empty() {
+            return Err("contains an empty path segment");
+        }
+        if segment == "." || segment == ".." {
+            return Err("contains a `.` or `..` path segment");
+        }
+        if segment != "**" && segment.contains("**") {
+            return Err("uses `**` inside a path segment, `**` must be a whole segment");
+        }
+    }
+    Ok(())
+}
+
+/// Match a path against a glob where `*` and `?` stay within a segment and `**` spans segments
+#[must_use]
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.split('/').collect::<Vec<_>>();
+    let path = path.split('/').collect::<Vec<_>>();
+    match_segments(&pattern, &path)
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|i| match_segments(rest, &path[i..])),
+        Some((segment, rest)) => path.split_first().map_or(false, |(name, path)| {
+            match_segment(segment.as_bytes(), name.as_bytes()) && match_segments(rest, path)
+        }),
+    }
+}
+
+fn match_segment(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0..=name.len()).any(|i| match_segment(rest, &name[i..])),
+        Some((b'?', rest)) => !name.is_empty() && match_segment(rest, &name[1..]),
+        Some((b, rest)) => name.first() == Some(b) && match_segment(rest, &name[1..]),
+    }
+}
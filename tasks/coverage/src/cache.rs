@@ -0,0 +1,176 @@
+//! Results of previous runs, keyed by the test content and the lexer sources
+//!
+//! ```text
+//! lexer 5f1c0e6a2b9d7c34
+//! 0a1b2c3d4e5f6071 pass
+//! 8192a3b4c5d6e7f8 fail 10 11
+//! 90a1b2c3d4e5f607 fail 3 4 strict
+//! ```
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    fmt, fs,
+    hash::{Hash, Hasher},
+    io,
+    ops::Range,
+    path::Path,
+};
+use walkdir::WalkDir;
+
+use crate::{project_root, Phase, TestCase};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedResult {
+    /// Range of the first unknown token
+    pub unknown: Option<Range<usize>>,
+
+    /// The unknown token was only found in strict mode
+    pub strict: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct Cache {
+    lexer_id: u64,
+    results: HashMap<u64, CachedResult>,
+}
+
+/// Hash of every file of the lexer, ast and parser crates and of `Cargo.lock`,
+/// any change to the phases or their dependencies busts the cache
+/// # Errors
+/// IO error
+pub fn lexer_id(root: &Path) -> io::Result<u64> {
+    let mut paths = ["lexer", "ast", "parser"]
+        .iter()
+        .flat_map(|name| {
+            let krate = root.join("crates").join(name);
+            WalkDir::new(&krate)
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter(|e| e.file_type().is_file())
+                .map(walkdir::DirEntry::into_path)
+                .filter(move |path| {
+                    !path.starts_with(krate.join("fuzz")) && !path.starts_with(krate.join("target"))
+                })
+        })
+        .collect::<Vec<_>>();
+    paths.push(root.join("Cargo.lock"));
+    paths.sort();
+    let mut hasher = DefaultHasher::new();
+    for path in paths {
+        path.strip_prefix(root).unwrap_or(&path).hash(&mut hasher);
+        fs::read(&path)?.hash(&mut hasher);
+    }
+    Ok(hasher.finish())
+}
+
+impl Cache {
+    /// Key of a test case in a phase, its code and the modes it is checked in
+    #[must_use]
+    pub fn key(phase: &dyn Phase, case: &TestCase) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        phase.name().hash(&mut hasher);
+        case.code.hash(&mut hasher);
+        case.strictness.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[must_use]
+    pub fn new(lexer_id: u64) -> Self {
+        Self {
+            lexer_id,
+            results: HashMap::new(),
+        }
+    }
+
+    /// Cache of the current lexer sources at `path`,
+    /// empty if it is missing, invalid or written by another lexer
+    /// # Errors
+    /// The lexer sources are not readable
+    pub fn read(path: &Path) -> io::Result<Self> {
+        let lexer_id = lexer_id(&project_root())?;
+        let cache = fs::read_to_string(path)
+            .ok()
+            .and_then(|text| Self::parse(&text, lexer_id))
+            .unwrap_or_else(|| Self::new(lexer_id));
+        Ok(cache)
+    }
+
+    /// `None` if the text is invalid or written by another lexer
+    #[must_use]
+    pub fn parse(text: &str, lexer_id: u64) -> Option<Self> {
+        let mut lines = text.lines();
+        let id = lines.next()?.strip_prefix("lexer ")?;
+        if u64::from_str_radix(id, 16).ok()? != lexer_id {
+            return None;
+        }
+        let mut cache = Self::new(lexer_id);
+        for line in lines {
+            let mut parts = line.split(' ');
+            let key = u64::from_str_radix(parts.next()?, 16).ok()?;
+            let result = match parts.next()? {
+                "pass" => CachedResult {
+                    unknown: None,
+                    strict: false,
+                },
+                "fail" => {
+                    let start = parts.next()?.parse().ok()?;
+                    let end = parts.next()?.parse().ok()?;
+                    CachedResult {
+                        unknown: Some(start..end),
+                        strict: parts.next() == Some("strict"),
+                    }
+                }
+                _ => return None,
+            };
+            cache.results.insert(key, result);
+        }
+        Some(cache)
+    }
+
+    #[must_use]
+    pub fn get(&self, key: u64) -> Option<&CachedResult> {
+        self.results.get(&key)
+    }
+
+    pub fn insert(&mut self, key: u64, result: CachedResult) {
+        self.results.insert(key, result);
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// # Errors
+    /// IO error
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+}
+
+/// Sorted by key, so unchanged results write the same file
+impl fmt::Display for Cache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "lexer {:016x}", self.lexer_id)?;
+        let mut results = self.results.iter().collect::<Vec<_>>();
+        results.sort_unstable_by_key(|(key, _)| **key);
+        for (key, result) in results {
+            match &result.unknown {
+                None => writeln!(f, "{key:016x} pass")?,
+                Some(range) => {
+                    write!(f, "{key:016x} fail {} {}", range.start, range.end)?;
+                    if result.strict {
+                        write!(f, " strict")?;
+                    }
+                    writeln!(f)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
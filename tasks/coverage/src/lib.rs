@@ -1,5 +1,25 @@
 #![warn(clippy::all, clippy::pedantic, clippy::nursery)]
 
+pub mod allowlist;
+pub mod cache;
+pub mod memory;
+pub mod phases;
+pub mod progress;
+pub mod report;
+pub mod runner;
+pub mod shard;
+pub mod snapshot;
+pub mod suite;
 pub mod test262;
 
+pub use crate::allowlist::*;
+pub use crate::cache::*;
+pub use crate::memory::*;
+pub use crate::phases::*;
+pub use crate::progress::*;
+pub use crate::report::*;
+pub use crate::runner::*;
+pub use crate::shard::*;
+pub use crate::snapshot::*;
+pub use crate::suite::*;
 pub use crate::test262::*;
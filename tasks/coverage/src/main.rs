@@ -1,103 +1,238 @@
 #![warn(clippy::all, clippy::pedantic, clippy::nursery)]
 
-use codespan_reporting::diagnostic::{Diagnostic, Label};
-use codespan_reporting::files::SimpleFiles;
-use codespan_reporting::term;
-use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
-use rayon::prelude::*;
-use std::{
-    env, fs,
-    path::{Path, PathBuf},
-    result::Result,
-    time::Instant,
+use pico_args::Arguments;
+use std::{convert::Infallible, fmt, fs, path::PathBuf, process};
+
+use coverage::{
+    lexer_id, merge_shards, parse_summaries, phase_by_name, project_root, read_skip_features,
+    render_json, render_markdown, render_summaries, run_suite, run_suite_cached,
+    skip_features_path, suites_by_name, utc_date, Cache, Phase, RunOptions, Shard, Snapshot, Suite,
+    SuiteReport, PHASE_NAMES, SUITE_NAMES,
 };
-use walkdir::WalkDir;
-
-use coverage::read_metadata;
-use lexer::{Lexer, Token};
-
-/// # Panics
-/// Invalid Project Root
-#[must_use]
-pub fn project_root() -> PathBuf {
-    Path::new(
-        &env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| env!("CARGO_MANIFEST_DIR").to_owned()),
-    )
-    .ancestors()
-    .nth(2)
-    .unwrap()
-    .to_path_buf()
+
+/// Unexpected failures with `--check`
+const EXIT_FAILURES: i32 = 1;
+/// Invalid arguments or configuration
+const EXIT_ERROR: i32 = 2;
+/// The test suite is not checked out
+const EXIT_SUITE_MISSING: i32 = 3;
+
+/// Exit with `EXIT_ERROR` on invalid arguments or configuration
+fn or_exit<T, E: fmt::Display>(result: Result<T, E>) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("{e}");
+        process::exit(EXIT_ERROR);
+    })
 }
 
-fn main() {
-    let root = project_root().join("tasks/coverage/test262/test/");
-    let entries = WalkDir::new(&root)
-        .into_iter()
-        .filter_map(Result::ok)
-        .filter(|e| e.file_type().is_file())
-        .map(|e| e.path().to_string_lossy().to_string())
-        .filter(|path| !path.contains("_FIXTURE"))
-        .collect::<Vec<_>>();
+/// `a,b,c`, an empty value is an empty list
+#[allow(clippy::unnecessary_wraps)]
+fn parse_list(s: &str) -> Result<Vec<String>, Infallible> {
+    let list = s
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(ToString::to_string)
+        .collect();
+    Ok(list)
+}
 
-    println!("Reading {} files.", entries.len());
+/// Exit with the instructions to check out any missing suite,
+/// `init` checks them out instead
+fn check_out(suites: &[Box<dyn Suite>], init: bool) {
+    for suite in suites {
+        if suite.is_checked_out() {
+            continue;
+        }
+        let repository = suite.repository();
+        if init {
+            println!("Checking out {} with `{repository}` ...", suite.name());
+            if let Err(e) = repository.init() {
+                eprintln!("{e}");
+                process::exit(EXIT_SUITE_MISSING);
+            }
+        }
+        if !suite.is_checked_out() {
+            eprintln!(
+                "The {} suite is missing, expected test files in {}",
+                suite.name(),
+                suite.root().display()
+            );
+            eprintln!("Check it out from the project root with `{repository}`,");
+            eprintln!("or run again with `--init` to do it automatically.");
+            process::exit(EXIT_SUITE_MISSING);
+        }
+    }
+}
 
-    let codes = entries
-        .par_iter()
+/// Merge the `--json` reports of every shard of a run,
+/// written to `json` or printed
+fn merge(paths: &[PathBuf], json: Option<PathBuf>, check: bool) {
+    if paths.is_empty() {
+        eprintln!("`--merge` expects the JSON reports of every shard");
+        process::exit(EXIT_ERROR);
+    }
+    let reports = paths
+        .iter()
         .map(|path| {
-            let code = fs::read_to_string(path).unwrap();
-            (
-                path.strip_prefix(root.clone().as_path().to_str().unwrap())
-                    .unwrap(),
-                code,
-            )
+            let text = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()));
+            parse_summaries(&or_exit(text)).map_err(|e| format!("{}: {e}", path.display()))
         })
-        .collect::<Vec<_>>();
+        .map(or_exit)
+        .collect();
+    let summaries = or_exit(merge_shards(reports));
+    let mut failed = 0;
+    for summary in &summaries {
+        println!("[{} {}]", summary.name, summary.phase);
+        println!(
+            "Passed: {}/{} ({:.2}%)",
+            summary.passed, summary.total, summary.pass_rate
+        );
+        println!("Known Failures: {}", summary.known_failures);
+        failed += summary.failures;
+    }
+    let json_summaries = render_summaries(&summaries);
+    match json {
+        Some(path) => fs::write(path, json_summaries).unwrap(),
+        None => println!("{json_summaries}"),
+    }
+    if check && failed > 0 {
+        eprintln!("{failed} unexpected failures");
+        process::exit(EXIT_FAILURES);
+    }
+}
 
-    let mut files = SimpleFiles::new();
-    let mut lexers = Vec::with_capacity(codes.len());
-    for (path, code) in &codes {
-        let (code, meta) = read_metadata(code.as_str()).unwrap();
-        // TODO: re-enable negative tests
-        if meta.negative.is_none() {
-            let file_id = files.add(path, code);
-            lexers.push((file_id, Lexer::new(code)));
-        }
+/// Run a phase on a suite, print the report and update the snapshot of a full run.
+/// The previous snapshot is returned along with the report, to compare with in the markdown report.
+fn run(
+    suite: &dyn Suite,
+    phase: &dyn Phase,
+    options: &RunOptions,
+    cache: Option<&mut Cache>,
+) -> (SuiteReport, Option<Snapshot>) {
+    let report = match cache {
+        Some(cache) => run_suite_cached(suite, phase, options, cache),
+        None => run_suite(suite, phase, options),
+    };
+    let report = or_exit(report);
+    report.print();
+    if let Some(path) = &report.stopped_by {
+        eprintln!(
+            "Reproduce with `cargo coverage -- {} {path} --phase {}`",
+            report.name, report.phase
+        );
+        process::exit(EXIT_FAILURES);
+    }
+    let path = suite.snapshot_path(phase.name());
+    let previous = Snapshot::read(&path);
+    // partial runs would drop failures from the snapshot
+    if options.filter.is_none() && options.shard.is_none() && report.cancelled == 0 {
+        fs::write(path, Snapshot::new(&report).to_string()).unwrap();
     }
+    (report, previous)
+}
 
-    println!("Running Lexer ...");
+fn main() {
+    let mut args = Arguments::from_env();
+    let check = args.contains("--check");
+    let init = args.contains("--init");
+    let quiet = args.contains("--quiet");
+    let fail_fast = args.contains("--fail-fast");
+    let date = args.contains("--date");
+    let no_cache = args.contains("--no-cache");
+    let memory = args.contains("--memory");
+    let merge_paths = args.contains("--merge");
+    let markdown = or_exit(args.opt_value_from_str::<_, PathBuf>("--markdown"));
+    let cache_path = or_exit(args.opt_value_from_str::<_, PathBuf>("--cache"));
+    let json = or_exit(args.opt_value_from_str::<_, PathBuf>("--json"));
+    let skip_features = or_exit(args.opt_value_from_fn("--skip-features", parse_list));
+    let shard = or_exit(args.opt_value_from_str::<_, Shard>("--shard"));
+    let phases = or_exit(args.opt_value_from_fn("--phase", parse_list))
+        .unwrap_or_else(|| vec!["lexer".to_string()]);
+    if merge_paths {
+        let paths = args
+            .finish()
+            .into_iter()
+            .map(PathBuf::from)
+            .collect::<Vec<_>>();
+        merge(&paths, json, check);
+        return;
+    }
+    let name = args
+        .opt_free_from_str::<String>()
+        .unwrap()
+        .unwrap_or_else(|| "test262".to_string());
+    let options = RunOptions {
+        quiet,
+        filter: args.opt_free_from_str().unwrap(),
+        fail_fast,
+        skip_features: skip_features.unwrap_or_else(|| {
+            read_skip_features(&skip_features_path()).unwrap_or_else(|e| {
+                eprintln!("{}: {e}", skip_features_path().display());
+                process::exit(EXIT_ERROR);
+            })
+        }),
+        shard,
+        memory,
+    };
 
-    let now = Instant::now();
+    let suites = suites_by_name(&name).unwrap_or_else(|| {
+        eprintln!(
+            "Unknown suite `{name}`, expected one of: {}",
+            SUITE_NAMES.join(", ")
+        );
+        process::exit(EXIT_ERROR);
+    });
 
-    let failed = lexers
-        .into_par_iter()
-        .filter_map(|(file_id, lexer)| {
-            lexer
-                .into_iter()
-                .find(Token::is_unknown)
-                .map(|token| (file_id, token))
+    let phases = phases
+        .iter()
+        .map(|name| {
+            phase_by_name(name).ok_or_else(|| {
+                format!(
+                    "Unknown phase `{name}`, expected one of: {}",
+                    PHASE_NAMES.join(", ")
+                )
+            })
         })
+        .map(or_exit)
         .collect::<Vec<_>>();
 
-    let duration = now.elapsed();
+    check_out(&suites, init);
 
-    let writer = StandardStream::stderr(ColorChoice::Always);
-    let config = codespan_reporting::term::Config::default();
-
-    failed.iter().take(5).for_each(|(file_id, token)| {
-        let diagnostic = Diagnostic::error()
-            .with_message("Unknown Token")
-            .with_labels(vec![Label::primary(*file_id, token.range())]);
-        term::emit(&mut writer.lock(), &config, &files, &diagnostic).ok();
+    let mut cache = cache_path.as_ref().map(|path| {
+        let cache = if no_cache {
+            lexer_id(&project_root()).map(Cache::new)
+        } else {
+            Cache::read(path)
+        };
+        or_exit(cache)
     });
 
-    let passed = codes.len() - failed.len();
-    #[allow(clippy::cast_precision_loss)]
-    let diff = (passed as f64 / codes.len() as f64) * 100.0;
+    let mut failed = 0;
+    let mut reports = vec![];
+    for suite in &suites {
+        for phase in &phases {
+            let (report, previous) = run(suite.as_ref(), phase.as_ref(), &options, cache.as_mut());
+            failed += report.failures.len();
+            reports.push((report, previous));
+        }
+    }
+
+    if let (Some(cache), Some(path)) = (cache, cache_path) {
+        cache.write(&path).unwrap();
+    }
+
+    if let Some(path) = json {
+        fs::write(path, render_json(reports.iter().map(|(report, _)| report))).unwrap();
+    }
+
+    if let Some(path) = markdown {
+        let date = date.then(utc_date);
+        fs::write(path, render_markdown(&reports, date.as_deref())).unwrap();
+    }
 
-    println!("Lexing Passed: {}/{} ({:.2}%)", passed, codes.len(), diff);
-    println!(
-        "Time Elapased: {}.{}s",
-        duration.as_secs(),
-        duration.subsec_millis()
-    );
+    if check && failed > 0 {
+        eprintln!("{failed} unexpected failures");
+        process::exit(EXIT_FAILURES);
+    }
 }
@@ -0,0 +1,164 @@
+//! Memory usage with `--memory`
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, mem};
+
+use lexer::Token;
+
+/// Peak resident set size of the process in bytes, `VmHWM` of `/proc/self/status`
+#[cfg(target_os = "linux")]
+#[must_use]
+pub fn peak_rss() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    let kilobytes = line
+        .trim_start_matches("VmHWM:")
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kilobytes * 1024)
+}
+
+/// Peak resident set size of the process in bytes, `resident_size_max` of `task_info`
+#[cfg(target_os = "macos")]
+#[must_use]
+pub fn peak_rss() -> Option<u64> {
+    mach::peak_rss()
+}
+
+/// `None` on other platforms than Linux and macOS
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+#[must_use]
+pub const fn peak_rss() -> Option<u64> {
+    None
+}
+
+/// The part of `<mach/task_info.h>` to read `mach_task_basic_info`
+#[cfg(target_os = "macos")]
+#[allow(non_camel_case_types, non_upper_case_globals)]
+mod mach {
+    use std::mem;
+
+    const MACH_TASK_BASIC_INFO: u32 = 20;
+    const KERN_SUCCESS: i32 = 0;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct mach_task_basic_info {
+        virtual_size: u64,
+        resident_size: u64,
+        resident_size_max: u64,
+        user_time: [i32; 2],
+        system_time: [i32; 2],
+        policy: i32,
+        suspend_count: i32,
+    }
+
+    extern "C" {
+        /// `mach_task_self()` is a macro reading it
+        static mach_task_self_: u32;
+
+        fn task_info(
+            target_task: u32,
+            flavor: u32,
+            task_info_out: *mut i32,
+            task_info_out_count: *mut u32,
+        ) -> i32;
+    }
+
+    pub fn peak_rss() -> Option<u64> {
+        let mut info = mach_task_basic_info::default();
+        // `MACH_TASK_BASIC_INFO_COUNT`, the size in `natural_t`s
+        #[allow(clippy::cast_possible_truncation)]
+        let mut count = (mem::size_of::<mach_task_basic_info>() / mem::size_of::<u32>()) as u32;
+        // SAFETY: `info` is the struct of the flavor, and `count` its size
+        let result = unsafe {
+            task_info(
+                mach_task_self_,
+                MACH_TASK_BASIC_INFO,
+                (&mut info as *mut mach_task_basic_info).cast(),
+                &mut count,
+            )
+        };
+        (result == KERN_SUCCESS).then(|| info.resident_size_max)
+    }
+}
+
+/// Size of the `Vec<Token>` of `len` tokens a parser would hold
+#[must_use]
+pub const fn token_vec_bytes(len: usize) -> usize {
+    len * mem::size_of::<Token>()
+}
+
+/// Largest token `Vec` of a single file
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenVec {
+    /// Path relative to the suite root
+    pub path: String,
+
+    /// Number of tokens, including trivia and `EOF`
+    pub tokens: usize,
+
+    /// See [`token_vec_bytes`]
+    pub bytes: usize,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Memory {
+    /// Peak RSS before reading the files of the suite.
+    /// The peak never decreases, so this includes the suites run before.
+    pub peak_rss_before: Option<u64>,
+
+    /// Peak RSS after lexing the suite
+    pub peak_rss_after: Option<u64>,
+
+    /// `None` when every file is cached
+    pub largest_token_vec: Option<TokenVec>,
+}
+
+impl Memory {
+    /// Largest numbers of both, e.g. of two shards
+    #[must_use]
+    pub fn max(self, other: Self) -> Self {
+        let largest_token_vec = match (self.largest_token_vec, other.largest_token_vec) {
+            (Some(a), Some(b)) => Some(if b.bytes > a.bytes { b } else { a }),
+            (a, b) => a.or(b),
+        };
+        Self {
+            peak_rss_before: self.peak_rss_before.max(other.peak_rss_before),
+            peak_rss_after: self.peak_rss_after.max(other.peak_rss_after),
+            largest_token_vec,
+        }
+    }
+}
+
+/// Megabytes, `n/a` for `None`
+fn megabytes(bytes: Option<u64>) -> String {
+    #[allow(clippy::cast_precision_loss)]
+    bytes.map_or_else(
+        || "n/a".to_string(),
+        |bytes| format!("{:.1} MB", bytes as f64 / 1_000_000.0),
+    )
+}
+
+impl fmt::Display for Memory {
+    /// `Peak RSS: 12.0 MB before, 80.5 MB after`, then the largest token `Vec`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Peak RSS: {} before, {} after",
+            megabytes(self.peak_rss_before),
+            megabytes(self.peak_rss_after)
+        )?;
+        if let Some(vec) = &self.largest_token_vec {
+            write!(
+                f,
+                "\nLargest Token Vec: {} tokens ({} bytes) in {}",
+                vec.tokens, vec.bytes, vec.path
+            )?;
+        }
+        Ok(())
+    }
+}
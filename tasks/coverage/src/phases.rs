@@ -0,0 +1,106 @@
+//! Pipeline stages the test files are run through, e.g. the lexer or the parser
+
+use std::ops::Range;
+
+use lexer::{Lexer, LexerOptions, Token};
+use parser::Parser;
+
+use crate::RunOptions;
+
+/// Result of a phase on a single source
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CaseResult {
+    /// Range of the first error
+    pub error: Option<Range<usize>>,
+
+    /// Number of tokens kept in memory with [`RunOptions::memory`], 0 otherwise
+    pub tokens: usize,
+}
+
+pub trait Phase: Sync {
+    /// Name used on the command line, in the output and in the snapshot and expected failures files
+    fn name(&self) -> &'static str;
+
+    /// Run the phase on `source`, the runner takes care of the timing and of the strict mode
+    /// variant, which is `strict` with a `"use strict";` prologue
+    fn check(&self, source: &str, strict: bool, options: &RunOptions) -> CaseResult;
+}
+
+/// Lex the source, the first unknown token is the error
+pub struct LexerPhase;
+
+impl Phase for LexerPhase {
+    fn name(&self) -> &'static str {
+        "lexer"
+    }
+
+    /// With [`RunOptions::memory`] every token is kept in a `Vec` like a parser would,
+    /// otherwise lexing stops at the first unknown token
+    fn check(&self, source: &str, strict: bool, options: &RunOptions) -> CaseResult {
+        let lexer_options = LexerOptions {
+            strict,
+            ..LexerOptions::new()
+        };
+        let lexer = Lexer::with_options(source, lexer_options);
+        if options.memory {
+            let tokens = lexer.collect::<Vec<_>>();
+            let error = tokens.iter().find(|token| token.is_unknown());
+            return CaseResult {
+                error: error.map(Token::range),
+                tokens: tokens.len(),
+            };
+        }
+        let error = lexer.into_iter().find(Token::is_unknown);
+        CaseResult {
+            error: error.map(|token| token.range()),
+            tokens: 0,
+        }
+    }
+}
+
+/// Parse each line of the source as an expression, the parser parsing expressions only,
+/// e.g. the files of the `expressions` suite. The first diagnostic is the error.
+pub struct ParserPhase;
+
+impl Phase for ParserPhase {
+    fn name(&self) -> &'static str {
+        "parser"
+    }
+
+    fn check(&self, source: &str, strict: bool, _options: &RunOptions) -> CaseResult {
+        let lexer_options = LexerOptions {
+            strict,
+            ..LexerOptions::new()
+        };
+        let mut start = 0;
+        for line in source.split_inclusive('\n') {
+            let offset = start;
+            start += line.len();
+            let line = line.trim_end_matches(['\n', '\r']);
+            if line.trim().is_empty() {
+                continue;
+            }
+            let ret = Parser::with_options(line, lexer_options).parse_expression();
+            if let Some(diagnostic) = ret.diagnostics.first() {
+                let span = diagnostic.span();
+                return CaseResult {
+                    error: Some(offset + span.start..offset + span.end),
+                    tokens: 0,
+                };
+            }
+        }
+        CaseResult::default()
+    }
+}
+
+pub const PHASE_NAMES: [&str; 2] = ["lexer", "parser"];
+
+/// Phase selected by a command line name
+#[must_use]
+pub fn phase_by_name(name: &str) -> Option<Box<dyn Phase>> {
+    match name {
+        "lexer" => Some(Box::new(LexerPhase)),
+        "parser" => Some(Box::new(ParserPhase)),
+        _ => None,
+    }
+}
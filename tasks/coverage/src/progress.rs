@@ -0,0 +1,91 @@
+//! Progress reporting for long runs
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+/// Counters updated from the rayon workers
+#[derive(Debug, Default)]
+pub struct Progress {
+    total: usize,
+    done: AtomicUsize,
+    failed: AtomicUsize,
+    finished: AtomicBool,
+}
+
+impl Progress {
+    #[must_use]
+    pub fn new(total: usize) -> Self {
+        Self {
+            total,
+            ..Self::default()
+        }
+    }
+
+    pub fn inc(&self, failed: bool) {
+        self.done.fetch_add(1, Ordering::Relaxed);
+        if failed {
+            self.failed.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// `12000/39000 (31%) failed=87`
+    #[must_use]
+    pub fn line(&self) -> String {
+        let done = self.done.load(Ordering::Relaxed);
+        let percent = if self.total == 0 {
+            100
+        } else {
+            done * 100 / self.total
+        };
+        format!(
+            "{done}/{} ({percent}%) failed={}",
+            self.total,
+            self.failed.load(Ordering::Relaxed)
+        )
+    }
+}
+
+/// Prints the progress to stderr every `interval` until dropped
+#[allow(clippy::module_name_repetitions)]
+pub struct ProgressReporter {
+    progress: Arc<Progress>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl ProgressReporter {
+    #[must_use]
+    pub fn spawn(progress: Arc<Progress>, interval: Duration) -> Self {
+        let handle = {
+            let progress = Arc::clone(&progress);
+            thread::spawn(move || {
+                let mut last = Instant::now();
+                while !progress.finished.load(Ordering::Relaxed) {
+                    thread::sleep(Duration::from_millis(50));
+                    if last.elapsed() >= interval {
+                        eprintln!("{}", progress.line());
+                        last = Instant::now();
+                    }
+                }
+            })
+        };
+        Self {
+            progress,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for ProgressReporter {
+    fn drop(&mut self) {
+        self.progress.finished.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            handle.join().ok();
+        }
+    }
+}
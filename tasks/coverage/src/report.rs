@@ -0,0 +1,220 @@
+//! Markdown and JSON conformance reports
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{DirectoryReport, Memory, Shard, Snapshot, SuiteReport, Throughput, SIZE_BUCKETS};
+
+/// Newly failing files listed per suite
+const MAX_NEWLY_FAILING: usize = 20;
+
+/// Render the reports as markdown, comparing each with its previous snapshot.
+/// The output only depends on its arguments.
+#[must_use]
+pub fn render_markdown(reports: &[(SuiteReport, Option<Snapshot>)], date: Option<&str>) -> String {
+    let mut s = String::from("# Conformance Report\n");
+    if let Some(date) = date {
+        writeln!(s, "\nDate: {date}").unwrap();
+    }
+    for (report, previous) in reports {
+        writeln!(s, "\n## {} ({})\n", report.name, report.phase).unwrap();
+        s.push_str("| Passed | Total | Rate | Delta |\n");
+        s.push_str("| ---: | ---: | ---: | ---: |\n");
+        let delta = previous.as_ref().map_or_else(
+            || "n/a".to_string(),
+            |previous| format!("{:+.2}%", report.pass_rate() - previous.pass_rate()),
+        );
+        writeln!(
+            s,
+            "| {} | {} | {:.2}% | {delta} |",
+            report.passed,
+            report.total,
+            report.pass_rate()
+        )
+        .unwrap();
+
+        s.push_str("\n| Directory | Passed | Total | Rate |\n");
+        s.push_str("| --- | ---: | ---: | ---: |\n");
+        for (name, directory) in &report.directories {
+            writeln!(
+                s,
+                "| {name} | {} | {} | {:.2}% |",
+                directory.passed,
+                directory.total,
+                directory.pass_rate()
+            )
+            .unwrap();
+        }
+
+        let previous = match previous {
+            Some(previous) => previous,
+            None => continue,
+        };
+        let newly_failing = report
+            .failures
+            .iter()
+            .filter(|failure| previous.failures.binary_search(&failure.path).is_err())
+            .collect::<Vec<_>>();
+        if newly_failing.is_empty() {
+            continue;
+        }
+        writeln!(s, "\n### Newly Failing ({})\n", newly_failing.len()).unwrap();
+        for failure in newly_failing.iter().take(MAX_NEWLY_FAILING) {
+            writeln!(s, "- `{}`", failure.path).unwrap();
+        }
+        if newly_failing.len() > MAX_NEWLY_FAILING {
+            writeln!(s, "- and {} more", newly_failing.len() - MAX_NEWLY_FAILING).unwrap();
+        }
+    }
+    s
+}
+
+/// JSON of a [`SuiteReport`], kept stable for historical comparisons
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SuiteSummary {
+    pub name: String,
+    pub phase: String,
+    /// `None` for a whole run, including one merged from its shards
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shard: Option<Shard>,
+    /// Files of this shard, [`SuiteSummary::discovered`] without shards
+    pub files: usize,
+    /// Files of every shard
+    pub discovered: usize,
+    pub total: usize,
+    pub skipped: usize,
+    pub skipped_features: usize,
+    pub passed: usize,
+    pub known_failures: usize,
+    pub failures: usize,
+    pub strict_failures: usize,
+    pub pass_rate: f64,
+    pub directories: BTreeMap<String, DirectoryReport>,
+    pub throughput: ThroughputSummary,
+    /// Keyed by the names of [`SIZE_BUCKETS`]
+    pub throughput_by_size: BTreeMap<String, ThroughputSummary>,
+    /// With `--memory`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<Memory>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ThroughputSummary {
+    pub files: usize,
+    pub bytes: usize,
+    pub seconds: f64,
+    pub megabytes_per_second: f64,
+}
+
+impl ThroughputSummary {
+    /// Sum of both throughputs, e.g. of two shards
+    pub fn add(&mut self, other: &Self) {
+        self.files += other.files;
+        self.bytes += other.bytes;
+        self.seconds += other.seconds;
+        self.megabytes_per_second = if self.seconds == 0.0 {
+            0.0
+        } else {
+            #[allow(clippy::cast_precision_loss)]
+            let megabytes = self.bytes as f64 / 1_000_000.0;
+            megabytes / self.seconds
+        };
+    }
+}
+
+impl From<&Throughput> for ThroughputSummary {
+    fn from(throughput: &Throughput) -> Self {
+        Self {
+            files: throughput.files,
+            bytes: throughput.bytes,
+            seconds: throughput.duration.as_secs_f64(),
+            megabytes_per_second: throughput.megabytes_per_second(),
+        }
+    }
+}
+
+impl From<&SuiteReport> for SuiteSummary {
+    fn from(report: &SuiteReport) -> Self {
+        Self {
+            name: report.name.to_string(),
+            phase: report.phase.to_string(),
+            shard: report.shard,
+            files: report.files,
+            discovered: report.discovered,
+            total: report.total,
+            skipped: report.skipped,
+            skipped_features: report.skipped_features,
+            passed: report.passed,
+            known_failures: report.known_failures,
+            failures: report.failures.len(),
+            strict_failures: report.strict_failures,
+            pass_rate: report.pass_rate(),
+            directories: report.directories.clone(),
+            throughput: ThroughputSummary::from(&report.throughput),
+            throughput_by_size: SIZE_BUCKETS
+                .iter()
+                .zip(&report.throughput_by_size)
+                .map(|((bucket, _), throughput)| ((*bucket).to_string(), throughput.into()))
+                .collect(),
+            memory: report.memory.clone(),
+        }
+    }
+}
+
+/// Render the reports as a JSON array of [`SuiteSummary`]
+#[must_use]
+pub fn render_json<'a>(reports: impl IntoIterator<Item = &'a SuiteReport>) -> String {
+    let summaries = reports
+        .into_iter()
+        .map(SuiteSummary::from)
+        .collect::<Vec<_>>();
+    render_summaries(&summaries)
+}
+
+/// Render summaries as a JSON array, e.g. merged from shards
+/// # Panics
+/// Serialization failed
+#[must_use]
+pub fn render_summaries(summaries: &[SuiteSummary]) -> String {
+    serde_json::to_string_pretty(summaries).unwrap()
+}
+
+/// Read a JSON array of [`SuiteSummary`] written by `--json`
+/// # Errors
+/// Invalid JSON
+pub fn parse_summaries(json: &str) -> Result<Vec<SuiteSummary>, serde_json::Error> {
+    serde_json::from_str(json)
+}
+
+/// Current UTC date as `YYYY-MM-DD`
+/// # Panics
+/// System time is before the unix epoch
+#[must_use]
+pub fn utc_date() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let (year, month, day) = civil_from_days(secs / 86400);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Days since 1970-01-01 to a (year, month, day) date,
+/// from <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+const fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + (month <= 2) as u64;
+    (year, month, day)
+}
@@ -0,0 +1,607 @@
+//! Run a phase over a test suite
+
+use codespan_reporting::diagnostic::{Diagnostic, Label};
+use codespan_reporting::files::SimpleFiles;
+use codespan_reporting::term;
+use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt, io,
+    ops::Range,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{
+    peak_rss, token_vec_bytes, Cache, CachedResult, ExpectedFailures, Memory, Phase, Progress,
+    ProgressReporter, Shard, Strictness, Suite, TestCase, TokenVec,
+};
+
+/// Prologue of the strict mode variant of a test
+const USE_STRICT: &str = "\"use strict\";\n";
+
+#[derive(Debug, Default, Clone)]
+pub struct RunOptions {
+    /// Do not report progress
+    pub quiet: bool,
+
+    /// Only run files whose path contains this string
+    pub filter: Option<String>,
+
+    /// Stop at the first unexpected failure
+    pub fail_fast: bool,
+
+    /// Skip tests using any of these test262 features
+    pub skip_features: Vec<String>,
+
+    /// Only run the files of this shard
+    pub shard: Option<Shard>,
+
+    /// Measure the peak memory usage and keep the tokens of each file in a `Vec`
+    pub memory: bool,
+}
+
+#[derive(Debug)]
+pub struct Failure {
+    /// Path relative to the suite root
+    pub path: String,
+
+    /// Range of the first error
+    pub range: Range<usize>,
+
+    /// Only fails in strict mode, i.e. with a `"use strict"` prologue
+    pub strict: bool,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectoryReport {
+    pub total: usize,
+    pub passed: usize,
+}
+
+impl DirectoryReport {
+    #[must_use]
+    pub fn pass_rate(&self) -> f64 {
+        percent(self.passed, self.total)
+    }
+}
+
+/// Upper bounds (exclusive) of the file size buckets of [`SuiteReport::throughput_by_size`]
+pub const SIZE_BUCKETS: [(&str, usize); 3] = [
+    ("<1 KB", 1024),
+    ("1-10 KB", 10 * 1024),
+    (">10 KB", usize::MAX),
+];
+
+/// Index of the [`SIZE_BUCKETS`] of a file of `len` bytes
+fn size_bucket(len: usize) -> usize {
+    SIZE_BUCKETS
+        .iter()
+        .position(|(_, limit)| len < *limit)
+        .unwrap_or(SIZE_BUCKETS.len() - 1)
+}
+
+/// Bytes checked and the time spent in the phase, excluding IO and metadata parsing.
+/// The time is summed over the rayon workers, so this is the throughput of a single thread.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Throughput {
+    pub files: usize,
+    pub bytes: usize,
+    pub duration: Duration,
+}
+
+impl Throughput {
+    pub fn add(&mut self, bytes: usize, duration: Duration) {
+        self.files += 1;
+        self.bytes += bytes;
+        self.duration += duration;
+    }
+
+    #[must_use]
+    pub fn megabytes_per_second(&self) -> f64 {
+        let seconds = self.duration.as_secs_f64();
+        if seconds == 0.0 {
+            return 0.0;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let megabytes = self.bytes as f64 / 1_000_000.0;
+        megabytes / seconds
+    }
+
+    /// Mean time per file, zero without files
+    #[must_use]
+    pub fn mean_duration(&self) -> Duration {
+        u32::try_from(self.files)
+            .ok()
+            .and_then(|files| self.duration.checked_div(files))
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for Throughput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.2} MB/s ({} files, {} bytes in {:.3}s)",
+            self.megabytes_per_second(),
+            self.files,
+            self.bytes,
+            self.duration.as_secs_f64()
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct SuiteReport {
+    pub name: &'static str,
+
+    /// Name of the [`Phase`]
+    pub phase: &'static str,
+
+    pub shard: Option<Shard>,
+
+    /// Number of files in this shard, including skipped and cancelled files
+    pub files: usize,
+
+    /// Number of files matching the filter in every shard
+    pub discovered: usize,
+
+    /// Number of checked files
+    pub total: usize,
+
+    /// Number of files skipped by the suite, e.g. negative tests
+    pub skipped: usize,
+
+    /// Number of files skipped for using one of [`RunOptions::skip_features`]
+    pub skipped_features: usize,
+
+    /// Number of files not checked because of `--fail-fast`
+    pub cancelled: usize,
+
+    /// Path of the unexpected failure that stopped the run with `--fail-fast`
+    pub stopped_by: Option<String>,
+
+    /// Number of files not checked because their result is cached
+    pub cached: usize,
+
+    pub passed: usize,
+
+    /// Failures listed in the expected failures file
+    pub known_failures: usize,
+
+    /// Failures not listed in the expected failures file, sorted by path
+    pub failures: Vec<Failure>,
+
+    /// Number of `failures` only failing in strict mode
+    pub strict_failures: usize,
+
+    /// Files listed in the expected failures file that passed
+    pub unexpectedly_passing: Vec<String>,
+
+    /// Results grouped by the first two segments of the path
+    pub directories: BTreeMap<String, DirectoryReport>,
+
+    pub throughput: Throughput,
+
+    /// Throughput of the files in each of [`SIZE_BUCKETS`]
+    pub throughput_by_size: [Throughput; SIZE_BUCKETS.len()],
+
+    /// Every read file over the elapsed time, with all the rayon workers
+    pub aggregate: Throughput,
+
+    pub duration: Duration,
+
+    /// With [`RunOptions::memory`]
+    pub memory: Option<Memory>,
+}
+
+/// `passed / total` in percent, 0 for no files
+#[must_use]
+pub fn percent(passed: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let rate = (passed as f64 / total as f64) * 100.0;
+    rate
+}
+
+/// `language/expressions/addition/foo.js` -> `language/expressions`
+fn directory(path: &str) -> &str {
+    let mut slashes = path.match_indices('/').map(|(i, _)| i);
+    let end = match (slashes.next(), slashes.next()) {
+        (Some(_), Some(i)) | (Some(i), None) => i,
+        (None, _) => return ".",
+    };
+    &path[..end]
+}
+
+impl SuiteReport {
+    #[must_use]
+    pub fn pass_rate(&self) -> f64 {
+        percent(self.passed, self.total)
+    }
+
+    pub fn print(&self) {
+        match self.shard {
+            Some(shard) => println!("[{} {} shard {shard}]", self.name, self.phase),
+            None => println!("[{} {}]", self.name, self.phase),
+        }
+        println!(
+            "Passed: {}/{} ({:.2}%)",
+            self.passed,
+            self.total,
+            self.pass_rate()
+        );
+        println!("Known Failures: {}", self.known_failures);
+        if self.strict_failures > 0 {
+            println!("Strict Mode Failures: {}", self.strict_failures);
+        }
+        if self.skipped_features > 0 {
+            println!("Skipped Features: {}", self.skipped_features);
+        }
+        if !self.unexpectedly_passing.is_empty() {
+            println!(
+                "Unexpectedly Passing: {} (remove from the expected failures)",
+                self.unexpectedly_passing.len(),
+            );
+            for path in &self.unexpectedly_passing {
+                println!("  {path}");
+            }
+        }
+        println!(
+            "Time Elapased: {}.{}s",
+            self.duration.as_secs(),
+            self.duration.subsec_millis()
+        );
+        if self.cached > 0 {
+            println!("Cached: {} (not included in the throughput)", self.cached);
+        }
+        println!("Aggregate Throughput: {}", self.aggregate);
+        println!("Throughput: {}", self.throughput);
+        println!(
+            "Mean File Time: {:.3}ms",
+            self.throughput.mean_duration().as_secs_f64() * 1000.0
+        );
+        for ((bucket, _), throughput) in SIZE_BUCKETS.iter().zip(&self.throughput_by_size) {
+            println!("  {bucket}: {throughput}");
+        }
+        if let Some(memory) = &self.memory {
+            println!("{memory}");
+        }
+    }
+}
+
+/// Result of a phase on a test case in each of its modes
+struct Checked {
+    /// Range of the first error in the test code
+    error: Option<Range<usize>>,
+
+    /// The error was only found in the strict mode variant
+    strict: bool,
+
+    /// Bytes checked over all modes
+    bytes: usize,
+
+    /// Time spent in the phase over all modes
+    elapsed: Duration,
+
+    /// From the cache, not checked in this run
+    cached: bool,
+
+    /// Tokens of the largest mode with [`RunOptions::memory`], 0 otherwise
+    tokens: usize,
+}
+
+impl From<&CachedResult> for Checked {
+    fn from(result: &CachedResult) -> Self {
+        Self {
+            error: result.unknown.clone(),
+            strict: result.strict,
+            bytes: 0,
+            elapsed: Duration::ZERO,
+            cached: true,
+            tokens: 0,
+        }
+    }
+}
+
+/// Run the phase on the test as is and with a `"use strict"` prologue,
+/// as required by its `strictness`
+fn check_case(phase: &dyn Phase, case: &TestCase, options: &RunOptions) -> Checked {
+    let mut checked = Checked {
+        error: None,
+        strict: false,
+        bytes: 0,
+        elapsed: Duration::ZERO,
+        cached: false,
+        tokens: 0,
+    };
+    if case.strictness != Strictness::Strict {
+        let start = Instant::now();
+        let result = phase.check(&case.code, false, options);
+        checked.elapsed += start.elapsed();
+        checked.error = result.error;
+        checked.bytes += case.code.len();
+        checked.tokens = result.tokens;
+    }
+    if checked.error.is_none() && case.strictness != Strictness::Sloppy {
+        let code = format!("{USE_STRICT}{}", case.code);
+        let start = Instant::now();
+        let result = phase.check(&code, true, options);
+        checked.elapsed += start.elapsed();
+        // back to offsets in the test code
+        checked.error = result.error.map(|range| {
+            range.start.saturating_sub(USE_STRICT.len())..range.end.saturating_sub(USE_STRICT.len())
+        });
+        checked.strict = checked.error.is_some() && case.strictness == Strictness::Both;
+        checked.bytes += code.len();
+        checked.tokens = checked.tokens.max(result.tokens);
+    }
+    checked
+}
+
+/// Print the error of the first few failures, starting with the one that stopped the run
+/// with `--fail-fast`, which cases failing in parallel could push out of the first few
+fn print_failures(
+    phase: &dyn Phase,
+    cases: &[&TestCase],
+    failures: &[Failure],
+    stopped_by: Option<&str>,
+) {
+    let writer = StandardStream::stderr(ColorChoice::Always);
+    let config = codespan_reporting::term::Config::default();
+    let mut files = SimpleFiles::new();
+    let mut failed = cases.iter().zip(failures).collect::<Vec<_>>();
+    // stable, the other failures keep their order
+    failed.sort_by_key(|(case, _)| Some(case.path.as_str()) != stopped_by);
+    for (case, failure) in failed.into_iter().take(5) {
+        let file_id = files.add(case.path.as_str(), case.code.as_str());
+        let diagnostic = Diagnostic::error()
+            .with_message(format!("{} error", phase.name()))
+            .with_labels(vec![Label::primary(file_id, failure.range.clone())]);
+        term::emit(&mut writer.lock(), &config, &files, &diagnostic).ok();
+    }
+}
+
+/// The result of a case with its cache key, `None` when cancelled by `--fail-fast`
+type CheckedCase = Option<(Option<u64>, Checked)>;
+
+/// Check the cases in parallel, with their cache keys when there is a `cache`.
+/// Cancelled cases are `None`, along with the path of the case that stopped the run.
+fn check_cases(
+    phase: &dyn Phase,
+    cases: &[TestCase],
+    options: &RunOptions,
+    expected_failures: &ExpectedFailures,
+    progress: &Progress,
+    cache: Option<&Cache>,
+) -> (Vec<CheckedCase>, Option<String>) {
+    let stop = AtomicBool::new(false);
+    let stopped_by = Mutex::new(None);
+    let results = cases
+        .par_iter()
+        .map(|case| {
+            if stop.load(Ordering::Relaxed) {
+                return None;
+            }
+            let key = cache.map(|_| Cache::key(phase, case));
+            let cached = cache.zip(key).and_then(|(cache, key)| cache.get(key));
+            let checked = cached.map_or_else(|| check_case(phase, case, options), Checked::from);
+            let failed = checked.error.is_some();
+            progress.inc(failed);
+            // the first failure to set the flag stopped the run, others may race with it
+            if options.fail_fast
+                && failed
+                && !expected_failures.is_expected(&case.path)
+                && !stop.swap(true, Ordering::Relaxed)
+            {
+                *stopped_by.lock().unwrap() = Some(case.path.clone());
+            }
+            Some((key, checked))
+        })
+        .collect();
+    (results, stopped_by.into_inner().unwrap())
+}
+
+/// Cases checked in this run, i.e. neither cancelled nor cached
+fn checked_cases<'a>(
+    cases: &'a [TestCase],
+    results: &'a [CheckedCase],
+) -> impl Iterator<Item = (&'a TestCase, &'a Checked)> {
+    cases
+        .iter()
+        .zip(results)
+        .filter_map(|(case, result)| Some((case, &result.as_ref()?.1)))
+        .filter(|(_, checked)| !checked.cached)
+}
+
+/// Throughput of the checked cases, overall and by [`SIZE_BUCKETS`]
+fn sum_throughput<'a>(
+    checked: impl Iterator<Item = (&'a TestCase, &'a Checked)>,
+) -> (Throughput, [Throughput; SIZE_BUCKETS.len()]) {
+    let mut throughput = Throughput::default();
+    let mut throughput_by_size = [Throughput::default(); SIZE_BUCKETS.len()];
+    for (case, checked) in checked {
+        throughput.add(checked.bytes, checked.elapsed);
+        throughput_by_size[size_bucket(case.code.len())].add(checked.bytes, checked.elapsed);
+    }
+    (throughput, throughput_by_size)
+}
+
+/// Peak RSS now and the largest token `Vec` of the checked cases
+fn measure_memory<'a>(
+    peak_rss_before: Option<u64>,
+    checked: impl Iterator<Item = (&'a TestCase, &'a Checked)>,
+) -> Memory {
+    let largest_token_vec =
+        checked
+            .max_by_key(|(_, checked)| checked.tokens)
+            .map(|(case, checked)| TokenVec {
+                path: case.path.clone(),
+                tokens: checked.tokens,
+                bytes: token_vec_bytes(checked.tokens),
+            });
+    Memory {
+        peak_rss_before,
+        peak_rss_after: peak_rss(),
+        largest_token_vec,
+    }
+}
+
+/// Run the phase on every file of the suite and print the first few unexpected failures
+/// # Errors
+/// Invalid expected failures file
+pub fn run_suite(
+    suite: &dyn Suite,
+    phase: &dyn Phase,
+    options: &RunOptions,
+) -> io::Result<SuiteReport> {
+    run(suite, phase, options, None)
+}
+
+/// Same as [`run_suite`], reusing the results of unchanged tests from `cache`
+/// and adding the results of the checked tests to it
+/// # Errors
+/// Invalid expected failures file
+pub fn run_suite_cached(
+    suite: &dyn Suite,
+    phase: &dyn Phase,
+    options: &RunOptions,
+    cache: &mut Cache,
+) -> io::Result<SuiteReport> {
+    run(suite, phase, options, Some(cache))
+}
+
+#[allow(clippy::too_many_lines)]
+fn run(
+    suite: &dyn Suite,
+    phase: &dyn Phase,
+    options: &RunOptions,
+    mut cache: Option<&mut Cache>,
+) -> io::Result<SuiteReport> {
+    let expected_failures = ExpectedFailures::read(&suite.expected_failures_path(phase.name()))?;
+    let peak_rss_before = options.memory.then(peak_rss).flatten();
+
+    let read = suite.read_test_cases(options.filter.as_deref(), options.shard);
+    let (mut cases, skipped, discovered) = (read.cases, read.skipped, read.discovered);
+    let files = cases.len() + skipped;
+    cases.retain(|case| !case.uses_any_feature(&options.skip_features));
+    let skipped_features = files - skipped - cases.len();
+
+    let shard = options.shard.map_or_else(String::new, |shard| {
+        format!(" (shard {shard} of {discovered} files)")
+    });
+    println!("Reading {files} files{shard}.");
+
+    println!("Running {} ...", phase.name());
+
+    let progress = Arc::new(Progress::new(cases.len()));
+    let reporter = (!options.quiet)
+        .then(|| ProgressReporter::spawn(Arc::clone(&progress), Duration::from_secs(1)));
+
+    let now = Instant::now();
+
+    let (results, stopped_by) = check_cases(
+        phase,
+        &cases,
+        options,
+        &expected_failures,
+        &progress,
+        cache.as_deref(),
+    );
+
+    let duration = now.elapsed();
+    drop(reporter);
+
+    let (throughput, throughput_by_size) = sum_throughput(checked_cases(&cases, &results));
+    let aggregate = Throughput {
+        files: cases.len(),
+        bytes: cases.iter().map(|case| case.code.len()).sum(),
+        duration,
+    };
+    let memory = options
+        .memory
+        .then(|| measure_memory(peak_rss_before, checked_cases(&cases, &results)));
+
+    let mut total = 0;
+    let mut failures = vec![];
+    let mut failed_cases = vec![];
+    let mut known_failures = 0;
+    let mut unexpectedly_passing = vec![];
+    let mut directories = BTreeMap::<String, DirectoryReport>::new();
+    let mut cached = 0;
+    for (case, result) in cases.iter().zip(results) {
+        let (key, checked) = match result {
+            Some(result) => result,
+            None => continue,
+        };
+        total += 1;
+        if checked.cached {
+            cached += 1;
+        } else if let (Some(cache), Some(key)) = (&mut cache, key) {
+            let result = CachedResult {
+                unknown: checked.error.clone(),
+                strict: checked.strict,
+            };
+            cache.insert(key, result);
+        }
+        let unknown = checked.error;
+        let directory = directories
+            .entry(directory(&case.path).to_string())
+            .or_default();
+        directory.total += 1;
+        if unknown.is_none() {
+            directory.passed += 1;
+        }
+        let expected = expected_failures.is_expected(&case.path);
+        match unknown {
+            Some(_) if expected => known_failures += 1,
+            Some(range) => {
+                failed_cases.push(case);
+                failures.push(Failure {
+                    path: case.path.clone(),
+                    range,
+                    strict: checked.strict,
+                });
+            }
+            None if expected => unexpectedly_passing.push(case.path.clone()),
+            None => {}
+        }
+    }
+
+    print_failures(phase, &failed_cases, &failures, stopped_by.as_deref());
+
+    failures.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+    unexpectedly_passing.sort_unstable();
+
+    Ok(SuiteReport {
+        name: suite.name(),
+        phase: phase.name(),
+        shard: options.shard,
+        files,
+        discovered,
+        total,
+        skipped,
+        skipped_features,
+        cancelled: cases.len() - total,
+        stopped_by,
+        cached,
+        passed: total - failures.len() - known_failures,
+        known_failures,
+        strict_failures: failures.iter().filter(|failure| failure.strict).count(),
+        failures,
+        unexpectedly_passing,
+        directories,
+        throughput,
+        throughput_by_size,
+        aggregate,
+        duration,
+        memory,
+    })
+}
@@ -0,0 +1,173 @@
+//! Split a run across CI jobs with `--shard K/N` and merge their JSON reports with `--merge`
+
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use crate::{percent, SuiteSummary, ThroughputSummary};
+
+/// Shard `index` of `count`, 1-based like on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shard {
+    pub index: usize,
+    pub count: usize,
+}
+
+impl Shard {
+    /// Whether the file at `path` (relative to the suite root) belongs to this shard.
+    /// The hash is FNV-1a so the partition is the same across platforms and Rust versions.
+    #[must_use]
+    pub fn contains(&self, path: &str) -> bool {
+        let hash = path.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+        #[allow(clippy::cast_possible_truncation)]
+        let shard = (hash % self.count as u64) as usize;
+        shard == self.index - 1
+    }
+}
+
+impl FromStr for Shard {
+    type Err = String;
+
+    /// `K/N` with `1 <= K <= N`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid shard `{s}`, expected `K/N` with 1 <= K <= N");
+        let (index, count) = s.split_once('/').ok_or_else(invalid)?;
+        let index = index.trim().parse::<usize>().map_err(|_| invalid())?;
+        let count = count.trim().parse::<usize>().map_err(|_| invalid())?;
+        if index == 0 || index > count {
+            return Err(invalid());
+        }
+        Ok(Self { index, count })
+    }
+}
+
+impl fmt::Display for Shard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.index, self.count)
+    }
+}
+
+/// Merge the JSON reports of every shard of a run into the report of the whole run
+/// # Errors
+/// The shards are not all shards of the same run, or a file is missing or counted twice
+pub fn merge_shards(reports: Vec<Vec<SuiteSummary>>) -> Result<Vec<SuiteSummary>, String> {
+    // by suite and phase
+    let mut suites = BTreeMap::<String, Vec<SuiteSummary>>::new();
+    let mut names = vec![];
+    for summary in reports.into_iter().flatten() {
+        let name = format!("{} {}", summary.name, summary.phase);
+        if summary.shard.is_none() {
+            return Err(format!("the {name} report is not a shard"));
+        }
+        if !suites.contains_key(&name) {
+            names.push(name.clone());
+        }
+        suites.entry(name).or_default().push(summary);
+    }
+    let count = suites
+        .values()
+        .flatten()
+        .filter_map(|summary| summary.shard)
+        .map(|shard| shard.count)
+        .max()
+        .unwrap_or_default();
+    names
+        .into_iter()
+        .map(|name| {
+            let shards = suites.remove(&name).unwrap_or_default();
+            check_shards(&name, &shards, count)?;
+            Ok(merge_suite(shards))
+        })
+        .collect()
+}
+
+/// Every shard of `count` exactly once, adding up to the discovered files
+fn check_shards(name: &str, shards: &[SuiteSummary], count: usize) -> Result<(), String> {
+    let mut seen = vec![false; count];
+    for shard in shards.iter().filter_map(|summary| summary.shard) {
+        // a deserialized shard skips the check of `FromStr`
+        if shard.count != count || !(1..=count).contains(&shard.index) {
+            return Err(format!(
+                "{name}: shard {shard} is not one of {count} shards"
+            ));
+        }
+        if std::mem::replace(&mut seen[shard.index - 1], true) {
+            return Err(format!("{name}: shard {shard} is merged twice"));
+        }
+    }
+    let missing = (1..=count)
+        .filter(|index| !seen[index - 1])
+        .map(|index| format!("{index}/{count}"))
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        return Err(format!("{name}: missing shards {}", missing.join(", ")));
+    }
+    let discovered = shards[0].discovered;
+    if shards
+        .iter()
+        .any(|summary| summary.discovered != discovered)
+    {
+        return Err(format!(
+            "{name}: the shards discovered different files, were they run with the same filter?"
+        ));
+    }
+    let files = shards.iter().map(|summary| summary.files).sum::<usize>();
+    if files != discovered {
+        return Err(format!(
+            "{name}: the shards ran {files} of {discovered} files"
+        ));
+    }
+    Ok(())
+}
+
+fn merge_suite(shards: Vec<SuiteSummary>) -> SuiteSummary {
+    let mut merged = SuiteSummary {
+        name: shards[0].name.clone(),
+        phase: shards[0].phase.clone(),
+        shard: None,
+        files: 0,
+        discovered: shards[0].discovered,
+        total: 0,
+        skipped: 0,
+        skipped_features: 0,
+        passed: 0,
+        known_failures: 0,
+        failures: 0,
+        strict_failures: 0,
+        pass_rate: 0.0,
+        directories: BTreeMap::new(),
+        throughput: ThroughputSummary::default(),
+        throughput_by_size: BTreeMap::new(),
+        memory: None,
+    };
+    for shard in shards {
+        merged.files += shard.files;
+        merged.total += shard.total;
+        merged.skipped += shard.skipped;
+        merged.skipped_features += shard.skipped_features;
+        merged.passed += shard.passed;
+        merged.known_failures += shard.known_failures;
+        merged.failures += shard.failures;
+        merged.strict_failures += shard.strict_failures;
+        for (path, directory) in shard.directories {
+            let merged = merged.directories.entry(path).or_default();
+            merged.total += directory.total;
+            merged.passed += directory.passed;
+        }
+        merged.throughput.add(&shard.throughput);
+        for (bucket, throughput) in shard.throughput_by_size {
+            merged
+                .throughput_by_size
+                .entry(bucket)
+                .or_default()
+                .add(&throughput);
+        }
+        merged.memory = match (merged.memory, shard.memory) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+    }
+    merged.pass_rate = percent(merged.passed, merged.total);
+    merged
+}
@@ -0,0 +1,78 @@
+//! Snapshot of a suite run, `<suite>-<phase>.snap`
+
+use std::{fmt, fs, path::Path};
+
+use crate::SuiteReport;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub passed: usize,
+    pub total: usize,
+    pub known_failures: usize,
+    /// Paths of the unexpected failures, sorted
+    pub failures: Vec<String>,
+}
+
+impl Snapshot {
+    #[must_use]
+    pub fn new(report: &SuiteReport) -> Self {
+        Self {
+            passed: report.passed,
+            total: report.total,
+            known_failures: report.known_failures,
+            failures: report.failures.iter().map(|f| f.path.clone()).collect(),
+        }
+    }
+
+    /// Read a previous snapshot, `None` if it is missing or invalid
+    #[must_use]
+    pub fn read(path: &Path) -> Option<Self> {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|text| Self::parse(&text))
+    }
+
+    #[must_use]
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut lines = text.lines();
+        let (passed, total) = lines
+            .next()?
+            .strip_prefix("Passed: ")?
+            .split_once(' ')?
+            .0
+            .split_once('/')?;
+        let known_failures = lines.next()?.strip_prefix("Known Failures: ")?;
+        let failures = lines.next()?.strip_prefix("Failures: ")?;
+        let snapshot = Self {
+            passed: passed.parse().ok()?,
+            total: total.parse().ok()?,
+            known_failures: known_failures.parse().ok()?,
+            failures: lines.map(ToString::to_string).collect(),
+        };
+        (failures.parse() == Ok(snapshot.failures.len())).then(|| snapshot)
+    }
+
+    #[must_use]
+    pub fn pass_rate(&self) -> f64 {
+        crate::percent(self.passed, self.total)
+    }
+}
+
+/// Stable across runs with the same results
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Passed: {}/{} ({:.2}%)",
+            self.passed,
+            self.total,
+            self.pass_rate()
+        )?;
+        writeln!(f, "Known Failures: {}", self.known_failures)?;
+        writeln!(f, "Failures: {}", self.failures.len())?;
+        for path in &self.failures {
+            writeln!(f, "{path}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,401 @@
+//! Test Suites
+
+use rayon::prelude::*;
+use std::{
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+use walkdir::WalkDir;
+
+use crate::{read_metadata, Shard, TestFlag};
+
+/// # Panics
+/// Invalid Project Root
+#[must_use]
+pub fn project_root() -> PathBuf {
+    Path::new(
+        &env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| env!("CARGO_MANIFEST_DIR").to_owned()),
+    )
+    .ancestors()
+    .nth(2)
+    .unwrap()
+    .to_path_buf()
+}
+
+#[must_use]
+pub fn coverage_root() -> PathBuf {
+    project_root().join("tasks/coverage")
+}
+
+/// Git repository of a suite
+pub struct Repository {
+    pub url: &'static str,
+
+    /// Checkout directory relative to the project root
+    pub path: &'static str,
+
+    /// Whether the checkout is a submodule of this repository
+    pub submodule: bool,
+}
+
+impl Repository {
+    fn args(&self) -> Vec<&'static str> {
+        if self.submodule {
+            vec!["submodule", "update", "--init", "--depth", "1", self.path]
+        } else {
+            vec!["clone", "--depth", "1", self.url, self.path]
+        }
+    }
+
+    /// Shallow clone the repository
+    /// # Errors
+    /// `git` is not available or failed
+    pub fn init(&self) -> io::Result<()> {
+        let status = Command::new("git")
+            .args(self.args())
+            .current_dir(project_root())
+            .status()?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("`{self}` failed with {status}"),
+            ))
+        }
+    }
+}
+
+impl fmt::Display for Repository {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "git {}", self.args().join(" "))
+    }
+}
+
+/// Checked in list of test262 features to skip
+#[must_use]
+pub fn skip_features_path() -> PathBuf {
+    coverage_root().join("skip-features.txt")
+}
+
+/// Read the features to skip, one per line with an optional `# reason`
+/// # Errors
+/// IO error
+pub fn read_skip_features(path: &Path) -> io::Result<Vec<String>> {
+    let text = fs::read_to_string(path)?;
+    let features = text
+        .lines()
+        .map(|line| {
+            line.split_once('#')
+                .map_or(line, |(feature, _)| feature)
+                .trim()
+        })
+        .filter(|feature| !feature.is_empty())
+        .map(ToString::to_string)
+        .collect();
+    Ok(features)
+}
+
+/// Modes a test is lexed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Strictness {
+    /// As is, e.g. test262 `noStrict` and `raw` tests or other suites
+    Sloppy,
+    /// Only with a `"use strict"` prologue, test262 `onlyStrict` tests
+    Strict,
+    /// Both as is and with a `"use strict"` prologue, both must pass
+    Both,
+}
+
+/// Content of a test file to lex
+#[derive(Debug)]
+pub struct Source {
+    /// Source code without the test harness metadata
+    pub code: String,
+
+    /// test262 `features` of the test
+    pub features: Box<[Box<str>]>,
+
+    pub strictness: Strictness,
+}
+
+impl From<String> for Source {
+    fn from(code: String) -> Self {
+        Self {
+            code,
+            features: Box::new([]),
+            strictness: Strictness::Sloppy,
+        }
+    }
+}
+
+/// A source file to lex
+#[derive(Debug)]
+pub struct TestCase {
+    /// Path relative to the suite root, separated by `/`
+    pub path: String,
+
+    /// Source code without the test harness metadata
+    pub code: String,
+
+    /// test262 `features` of the test
+    pub features: Box<[Box<str>]>,
+
+    pub strictness: Strictness,
+}
+
+impl TestCase {
+    #[must_use]
+    pub fn uses_any_feature(&self, features: &[String]) -> bool {
+        self.features
+            .iter()
+            .any(|feature| features.iter().any(|f| f.as_str() == &**feature))
+    }
+}
+
+/// Test files read by [`Suite::read_test_cases`]
+#[derive(Debug)]
+pub struct TestCases {
+    pub cases: Vec<TestCase>,
+
+    /// Number of files skipped by [`Suite::load`]
+    pub skipped: usize,
+
+    /// Number of files matching the filter in every shard
+    pub discovered: usize,
+}
+
+pub trait Suite: Sync {
+    /// Name used on the command line, in the output and for the snapshot file
+    fn name(&self) -> &'static str;
+
+    /// Directory containing the test files
+    fn root(&self) -> PathBuf;
+
+    fn repository(&self) -> Repository;
+
+    /// Whether the test files are checked out, i.e. `root` is a non-empty directory
+    fn is_checked_out(&self) -> bool {
+        fs::read_dir(self.root()).map_or(false, |mut entries| entries.next().is_some())
+    }
+
+    /// Whether a file under `root` is a test file
+    fn is_test_file(&self, path: &Path) -> bool;
+
+    /// Source to lex from the file content, `None` skips the file
+    fn load(&self, path: &Path, code: &str) -> Option<Source>;
+
+    /// Known failures of a phase
+    fn expected_failures_path(&self, phase: &str) -> PathBuf {
+        coverage_root().join(format!("expected-failures-{}-{phase}.txt", self.name()))
+    }
+
+    /// Failures of the last full run of a phase
+    fn snapshot_path(&self, phase: &str) -> PathBuf {
+        coverage_root().join(format!("{}-{phase}.snap", self.name()))
+    }
+
+    /// Read all test files of `shard` whose path contains `filter`
+    /// # Panics
+    /// File is not readable
+    fn read_test_cases(&self, filter: Option<&str>, shard: Option<Shard>) -> TestCases {
+        let root = self.root();
+        let paths = WalkDir::new(&root)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_file() && self.is_test_file(e.path()))
+            .map(|e| {
+                let path = e.path().strip_prefix(&root).unwrap().to_string_lossy();
+                (path.replace('\\', "/"), e.into_path())
+            })
+            .filter(|(path, _)| filter.map_or(true, |filter| path.contains(filter)))
+            .collect::<Vec<_>>();
+        let discovered = paths.len();
+        let paths = paths
+            .into_iter()
+            .filter(|(path, _)| shard.map_or(true, |shard| shard.contains(path)))
+            .collect::<Vec<_>>();
+        let cases = paths
+            .par_iter()
+            .filter_map(|(path, full_path)| {
+                let code = fs::read_to_string(full_path).unwrap();
+                let source = self.load(full_path, &code)?;
+                Some(TestCase {
+                    path: path.clone(),
+                    code: source.code,
+                    features: source.features,
+                    strictness: source.strictness,
+                })
+            })
+            .collect::<Vec<_>>();
+        let skipped = paths.len() - cases.len();
+        TestCases {
+            cases,
+            skipped,
+            discovered,
+        }
+    }
+}
+
+pub struct Test262;
+
+impl Suite for Test262 {
+    fn name(&self) -> &'static str {
+        "test262"
+    }
+
+    fn root(&self) -> PathBuf {
+        coverage_root().join("test262/test")
+    }
+
+    fn repository(&self) -> Repository {
+        Repository {
+            url: "https://github.com/tc39/test262",
+            path: "tasks/coverage/test262",
+            submodule: true,
+        }
+    }
+
+    fn is_test_file(&self, path: &Path) -> bool {
+        !path.to_string_lossy().contains("_FIXTURE")
+    }
+
+    fn load(&self, path: &Path, code: &str) -> Option<Source> {
+        let (code, meta) = match read_metadata(code) {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                eprintln!("Skipping {}: {e}", path.display());
+                return None;
+            }
+        };
+        // TODO: re-enable negative tests
+        let strictness = if meta.flags.contains(&TestFlag::OnlyStrict) {
+            Strictness::Strict
+        } else if meta.flags.contains(&TestFlag::NoStrict) || meta.flags.contains(&TestFlag::Raw) {
+            Strictness::Sloppy
+        } else {
+            Strictness::Both
+        };
+        meta.negative.is_none().then(|| Source {
+            code: code.to_string(),
+            features: meta.features,
+            strictness,
+        })
+    }
+
+    fn expected_failures_path(&self, phase: &str) -> PathBuf {
+        coverage_root().join(format!("expected-failures-{phase}.txt"))
+    }
+}
+
+pub struct Babel;
+
+impl Suite for Babel {
+    fn name(&self) -> &'static str {
+        "babel"
+    }
+
+    fn root(&self) -> PathBuf {
+        coverage_root().join("babel/packages/babel-parser/test/fixtures")
+    }
+
+    fn repository(&self) -> Repository {
+        Repository {
+            url: "https://github.com/babel/babel",
+            path: "tasks/coverage/babel",
+            submodule: false,
+        }
+    }
+
+    fn is_test_file(&self, path: &Path) -> bool {
+        path.file_name()
+            .map_or(false, |name| name == "input.js" || name == "input.mjs")
+    }
+
+    fn load(&self, path: &Path, code: &str) -> Option<Source> {
+        // fixtures expecting an error have a `throws` field in `options.json`
+        let options = path.with_file_name("options.json");
+        let throws = fs::read_to_string(options).map_or(false, |o| o.contains("\"throws\""));
+        (!throws).then(|| code.to_string().into())
+    }
+}
+
+pub struct TypeScript;
+
+impl Suite for TypeScript {
+    fn name(&self) -> &'static str {
+        "typescript"
+    }
+
+    fn root(&self) -> PathBuf {
+        coverage_root().join("typescript/tests/cases/conformance")
+    }
+
+    fn repository(&self) -> Repository {
+        Repository {
+            url: "https://github.com/microsoft/TypeScript",
+            path: "tasks/coverage/typescript",
+            submodule: false,
+        }
+    }
+
+    fn is_test_file(&self, path: &Path) -> bool {
+        path.extension().map_or(false, |ext| ext == "ts")
+    }
+
+    fn load(&self, _path: &Path, code: &str) -> Option<Source> {
+        Some(code.to_string().into())
+    }
+}
+
+/// The expression fixtures of the parser, an expression per line for [`crate::ParserPhase`]
+pub struct Expressions;
+
+impl Suite for Expressions {
+    fn name(&self) -> &'static str {
+        "expressions"
+    }
+
+    fn root(&self) -> PathBuf {
+        project_root().join("crates/parser/tests/fixtures")
+    }
+
+    /// In this repository
+    fn repository(&self) -> Repository {
+        Repository {
+            url: "",
+            path: "crates/parser/tests/fixtures",
+            submodule: false,
+        }
+    }
+
+    fn is_test_file(&self, path: &Path) -> bool {
+        path.extension().map_or(false, |ext| ext == "js")
+    }
+
+    fn load(&self, _path: &Path, code: &str) -> Option<Source> {
+        Some(code.to_string().into())
+    }
+}
+
+pub const SUITE_NAMES: [&str; 5] = ["test262", "babel", "typescript", "expressions", "all"];
+
+/// Suites selected by a command line name, `all` selects every suite
+#[must_use]
+pub fn suites_by_name(name: &str) -> Option<Vec<Box<dyn Suite>>> {
+    let suites: Vec<Box<dyn Suite>> = match name {
+        "test262" => vec![Box::new(Test262)],
+        "babel" => vec![Box::new(Babel)],
+        "typescript" => vec![Box::new(TypeScript)],
+        "expressions" => vec![Box::new(Expressions)],
+        "all" => vec![
+            Box::new(Test262),
+            Box::new(Babel),
+            Box::new(TypeScript),
+            Box::new(Expressions),
+        ],
+        _ => return None,
+    };
+    Some(suites)
+}
@@ -24,7 +24,7 @@ pub struct MetaData {
 /// Negative test information structure.
 #[derive(Debug, Clone, Deserialize)]
 pub struct Negative {
-    pub phase: Phase,
+    pub phase: NegativePhase,
     #[serde(rename = "type")]
     pub error_type: String,
 }
@@ -47,23 +47,50 @@ pub enum TestFlag {
     NonDeterministic,
 }
 
+/// Phase of a negative test, when the error is expected
 #[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
-pub enum Phase {
+pub enum NegativePhase {
     Parse,
     Early,
     Resolution,
     Runtime,
 }
 
-/// # Panics
-/// Invalid metadata
+const FRONTMATTER_START: &str = "/*---";
+const FRONTMATTER_END: &str = "---*/";
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Split a test into its YAML frontmatter and the code after it.
+/// CRLF line endings of the YAML are normalized, the code is left untouched.
+/// # Errors
+/// Missing or duplicated frontmatter markers
+pub fn split_frontmatter(code: &str) -> io::Result<(String, &str)> {
+    let code = code.strip_prefix('\u{feff}').unwrap_or(code);
+    let start = code
+        .find(FRONTMATTER_START)
+        .ok_or_else(|| invalid_data("missing `/*---` frontmatter start"))?
+        + FRONTMATTER_START.len();
+    let end = code[start..]
+        .find(FRONTMATTER_END)
+        .ok_or_else(|| invalid_data("missing `---*/` frontmatter end"))?
+        + start;
+    let yaml = &code[start..end];
+    if yaml.contains(FRONTMATTER_START) {
+        return Err(invalid_data("duplicated `/*---` frontmatter start"));
+    }
+    let code = &code[end + FRONTMATTER_END.len()..];
+    Ok((yaml.replace("\r\n", "\n"), code))
+}
+
 /// # Errors
-/// Invalid metadata
+/// Missing frontmatter or invalid metadata
 pub fn read_metadata(code: &str) -> io::Result<(&str, MetaData)> {
-    let (start, end) = (code.find("/*---").unwrap(), code.find("---*/").unwrap());
-    let yaml = &code[start + 5..end].replace("\r", "\n");
-    serde_yaml::from_str(yaml)
-        .map(|meta| ((&code[end + 5..]).trim(), meta))
+    let (yaml, code) = split_frontmatter(code)?;
+    serde_yaml::from_str(&yaml)
+        .map(|meta| (code.trim(), meta))
         .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
 }
@@ -0,0 +1 @@
+f(`t`, /re/g);
@@ -0,0 +1,494 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use coverage::{
+    glob_match, lexer_id, merge_shards, phase_by_name, read_metadata, render_markdown, run_suite,
+    run_suite_cached, split_frontmatter, token_vec_bytes, Cache, CachedResult, CaseResult,
+    ExpectedFailures, Expressions, LexerPhase, ParserPhase, Phase, Progress, Repository,
+    RunOptions, Shard, Snapshot, Source, Strictness, Suite, SuiteReport, SuiteSummary, TestCase,
+    Throughput,
+};
+
+fn fixtures() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+/// Suite of `tests/fixtures/suite`, lexed in the given modes
+struct Fixture(Strictness);
+
+impl Suite for Fixture {
+    fn name(&self) -> &'static str {
+        "fixture"
+    }
+
+    fn root(&self) -> PathBuf {
+        fixtures().join("suite")
+    }
+
+    fn repository(&self) -> Repository {
+        Repository {
+            url: "",
+            path: "tasks/coverage/tests/fixtures/suite",
+            submodule: false,
+        }
+    }
+
+    fn is_test_file(&self, path: &Path) -> bool {
+        path.extension().map_or(false, |ext| ext == "js")
+    }
+
+    fn load(&self, path: &Path, code: &str) -> Option<Source> {
+        // the failing fixtures use a made up feature
+        let features = if path.ends_with("fail.js") {
+            vec!["unknown-token".into()].into_boxed_slice()
+        } else {
+            Box::new([])
+        };
+        Some(Source {
+            code: code.to_string(),
+            features,
+            strictness: self.0,
+        })
+    }
+
+    fn expected_failures_path(&self, _phase: &str) -> PathBuf {
+        fixtures().join("expected-failures.txt")
+    }
+}
+
+#[test]
+fn glob() {
+    assert!(glob_match("a/b.js", "a/b.js"));
+    assert!(!glob_match("a/b.js", "a/c.js"));
+    assert!(glob_match("a/*.js", "a/b.js"));
+    assert!(!glob_match("a/*.js", "a/b/c.js"));
+    assert!(glob_match("a/?.js", "a/b.js"));
+    assert!(!glob_match("a/?.js", "a/bc.js"));
+    assert!(glob_match("a/**", "a/b/c.js"));
+    assert!(glob_match("a/**/c.js", "a/c.js"));
+    assert!(glob_match("a/**/c.js", "a/b/b/c.js"));
+    assert!(glob_match(
+        "**/*-v-flag*.js",
+        "built-ins/RegExp/unicode-v-flag-1.js"
+    ));
+}
+
+#[test]
+fn expected_failures() {
+    let list = ExpectedFailures::parse(
+        "# comment\n\nlanguage/comments/hashbang/**  # hashbang\nbuilt-ins/RegExp/*.js\n",
+    )
+    .unwrap();
+    assert_eq!(list.entries().len(), 2);
+    let entry = list.find("language/comments/hashbang/eval.js").unwrap();
+    assert_eq!(entry.reason.as_deref(), Some("hashbang"));
+    assert_eq!(entry.line, 3);
+    assert!(list.is_expected("built-ins/RegExp/S15.10.2_A1_T1.js"));
+    assert!(!list.is_expected("built-ins/RegExp/property-escapes/a.js"));
+}
+
+#[test]
+fn expected_failures_errors() {
+    let errors = ExpectedFailures::parse(
+        "a b.js\n/a.js\na\\b.js\na//b.js\n../a.js\na/b**/c.js\nx.js\nx.js\n",
+    )
+    .unwrap_err();
+    assert_eq!(
+        errors.iter().map(|e| e.line).collect::<Vec<_>>(),
+        [1, 2, 3, 4, 5, 6, 8]
+    );
+    assert_eq!(
+        errors[6].to_string(),
+        "line 8: `x.js` is a duplicate of line 7"
+    );
+}
+
+#[test]
+fn progress() {
+    let progress = Progress::new(4);
+    progress.inc(false);
+    progress.inc(true);
+    assert_eq!(progress.line(), "2/4 (50%) failed=1");
+    assert_eq!(Progress::new(0).line(), "0/0 (100%) failed=0");
+}
+
+#[test]
+fn snapshot() {
+    let text = "Passed: 2/3 (66.67%)\nKnown Failures: 0\nFailures: 1\na/fail.js\n";
+    let snapshot = Snapshot::parse(text).unwrap();
+    assert_eq!(snapshot.failures, ["a/fail.js"]);
+    assert_eq!(snapshot.to_string(), text);
+    assert_eq!(
+        Snapshot::parse("Passed: 2/3 (66.67%)\nKnown Failures: 0\nFailures: 2\n"),
+        None
+    );
+}
+
+#[test]
+fn markdown() {
+    let options = RunOptions {
+        quiet: true,
+        ..RunOptions::default()
+    };
+    let report = run_suite(&Fixture(Strictness::Sloppy), &LexerPhase, &options).unwrap();
+    let previous =
+        Snapshot::parse("Passed: 2/3 (66.67%)\nKnown Failures: 0\nFailures: 1\na/fail.js\n");
+    let markdown = render_markdown(&[(report, previous)], Some("2022-01-01"));
+    let golden = std::fs::read_to_string(fixtures().join("report.md")).unwrap();
+    assert_eq!(markdown, golden);
+}
+
+#[test]
+fn throughput() {
+    let options = RunOptions {
+        quiet: true,
+        ..RunOptions::default()
+    };
+    let report = run_suite(&Fixture(Strictness::Sloppy), &LexerPhase, &options).unwrap();
+    assert_eq!(report.throughput.files, 4);
+    assert_eq!(report.throughput.bytes, 45);
+    assert_eq!(report.throughput_by_size[0], report.throughput);
+    assert_eq!(report.throughput_by_size[1], Throughput::default());
+    assert_eq!(report.aggregate.files, 4);
+    assert_eq!(report.aggregate.bytes, 45);
+    assert_eq!(report.aggregate.duration, report.duration);
+
+    let none = Throughput::default();
+    assert_eq!(none.megabytes_per_second(), 0.0);
+    assert_eq!(none.mean_duration(), Duration::ZERO);
+    let two = Throughput {
+        files: 2,
+        bytes: 3_000_000,
+        duration: Duration::from_secs(3),
+    };
+    assert_eq!(two.megabytes_per_second(), 1.0);
+    assert_eq!(two.mean_duration(), Duration::from_millis(1500));
+}
+
+#[test]
+fn skip_features() {
+    let options = RunOptions {
+        quiet: true,
+        skip_features: vec!["unknown-token".to_string()],
+        ..RunOptions::default()
+    };
+    let report = run_suite(&Fixture(Strictness::Sloppy), &LexerPhase, &options).unwrap();
+    assert_eq!(report.skipped_features, 2);
+    assert_eq!(report.total, 2);
+    assert_eq!(report.passed, 2);
+}
+
+#[test]
+fn strict_mode() {
+    let options = RunOptions {
+        quiet: true,
+        ..RunOptions::default()
+    };
+    let sloppy = run_suite(&Fixture(Strictness::Sloppy), &LexerPhase, &options).unwrap();
+    let strict = run_suite(&Fixture(Strictness::Strict), &LexerPhase, &options).unwrap();
+    let both = run_suite(&Fixture(Strictness::Both), &LexerPhase, &options).unwrap();
+    let ranges = |report: &SuiteReport| {
+        report
+            .failures
+            .iter()
+            .map(|failure| failure.range.clone())
+            .collect::<Vec<_>>()
+    };
+    // offsets are relative to the test code without the prologue
+    assert_eq!(ranges(&sloppy), [8..9, 2..3]);
+    assert_eq!(ranges(&strict), ranges(&sloppy));
+    assert_eq!(ranges(&both), ranges(&sloppy));
+    assert_eq!(both.strict_failures, 0);
+    // the passing files are lexed twice
+    let prologue = "\"use strict\";\n".len();
+    assert_eq!(both.throughput.bytes, 45 + 11 + 15 + 2 * prologue);
+}
+
+#[test]
+fn frontmatter() {
+    let code = "// Copyright\n/*---\ndescription: a\n---*/\nvar a;\n";
+    let (yaml, body) = split_frontmatter(code).unwrap();
+    assert_eq!(yaml, "\ndescription: a\n");
+    assert_eq!(body, "\nvar a;\n");
+
+    let code = "\u{feff}/*---\r\ndescription: a\r\n---*/\r\nvar a;";
+    let (yaml, body) = split_frontmatter(code).unwrap();
+    assert_eq!(yaml, "\ndescription: a\n");
+    assert_eq!(body, "\r\nvar a;");
+
+    let code = "/*---\ndescription: a\n---*/\nvar s = '/*--- ---*/';";
+    let (_, body) = split_frontmatter(code).unwrap();
+    assert_eq!(body, "\nvar s = '/*--- ---*/';");
+
+    assert!(split_frontmatter("var a;").is_err());
+    assert!(split_frontmatter("/*---\ndescription: a\n").is_err());
+    assert!(split_frontmatter("/*---\n/*---\n---*/").is_err());
+    assert!(read_metadata("var a;").is_err());
+}
+
+#[test]
+fn cache_key() {
+    let case = |code: &str, strictness| TestCase {
+        path: "a.js".to_string(),
+        code: code.to_string(),
+        features: Box::new([]),
+        strictness,
+    };
+    let key = Cache::key(&LexerPhase, &case("a;", Strictness::Both));
+    assert_eq!(key, Cache::key(&LexerPhase, &case("a;", Strictness::Both)));
+    assert_ne!(key, Cache::key(&LexerPhase, &case("b;", Strictness::Both)));
+    assert_ne!(
+        key,
+        Cache::key(&LexerPhase, &case("a;", Strictness::Sloppy))
+    );
+
+    let mut cache = Cache::new(1);
+    cache.insert(
+        key,
+        CachedResult {
+            unknown: Some(2..3),
+            strict: true,
+        },
+    );
+    let text = cache.to_string();
+    let parsed = Cache::parse(&text, 1).unwrap();
+    assert_eq!(parsed.get(key), cache.get(key));
+    assert_eq!(parsed.to_string(), text);
+    // written by another lexer
+    assert!(Cache::parse(&text, 2).is_none());
+}
+
+#[test]
+fn cache_lexer_id() {
+    let root = std::env::temp_dir().join(format!("coverage-lexer-id-{}", std::process::id()));
+    let src = root.join("crates/lexer/src");
+    let fuzz = root.join("crates/lexer/fuzz");
+    std::fs::create_dir_all(&src).unwrap();
+    std::fs::create_dir_all(&fuzz).unwrap();
+    std::fs::write(root.join("Cargo.lock"), "").unwrap();
+    std::fs::write(src.join("lib.rs"), "// a").unwrap();
+    let id = lexer_id(&root).unwrap();
+    assert_eq!(lexer_id(&root).unwrap(), id);
+    std::fs::write(fuzz.join("input"), "a").unwrap();
+    assert_eq!(lexer_id(&root).unwrap(), id);
+    std::fs::write(src.join("lib.rs"), "// b").unwrap();
+    let id_b = lexer_id(&root).unwrap();
+    assert_ne!(id_b, id);
+    // the parser phase depends on the parser
+    let parser = root.join("crates/parser/src");
+    std::fs::create_dir_all(&parser).unwrap();
+    std::fs::write(parser.join("lib.rs"), "// a").unwrap();
+    assert_ne!(lexer_id(&root).unwrap(), id_b);
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn cached_run() {
+    let options = RunOptions {
+        quiet: true,
+        ..RunOptions::default()
+    };
+    let suite = Fixture(Strictness::Sloppy);
+    let mut cache = Cache::new(0);
+    let first = run_suite_cached(&suite, &LexerPhase, &options, &mut cache).unwrap();
+    assert_eq!((first.cached, cache.len()), (0, 4));
+    let second = run_suite_cached(&suite, &LexerPhase, &options, &mut cache).unwrap();
+    assert_eq!(second.cached, 4);
+    assert_eq!(second.throughput.files, 0);
+    assert_eq!(second.passed, first.passed);
+    assert_eq!(second.failures.len(), first.failures.len());
+}
+
+#[test]
+fn fail_fast() {
+    let options = RunOptions {
+        quiet: true,
+        fail_fast: true,
+        ..RunOptions::default()
+    };
+    let report = run_suite(&Fixture(Strictness::Sloppy), &LexerPhase, &options).unwrap();
+    // the other failure may have been checked in parallel before the run stopped
+    let stopped_by = report.stopped_by.unwrap();
+    assert!(report
+        .failures
+        .iter()
+        .any(|failure| failure.path == stopped_by));
+    let options = RunOptions {
+        quiet: true,
+        ..RunOptions::default()
+    };
+    let report = run_suite(&Fixture(Strictness::Sloppy), &LexerPhase, &options).unwrap();
+    assert_eq!(report.stopped_by, None);
+}
+
+#[test]
+fn shards() {
+    assert_eq!("2/3".parse(), Ok(Shard { index: 2, count: 3 }));
+    assert!("0/3".parse::<Shard>().is_err());
+    assert!("4/3".parse::<Shard>().is_err());
+    assert!("3".parse::<Shard>().is_err());
+
+    let run = |shard| {
+        let options = RunOptions {
+            quiet: true,
+            shard,
+            ..RunOptions::default()
+        };
+        SuiteSummary::from(&run_suite(&Fixture(Strictness::Sloppy), &LexerPhase, &options).unwrap())
+    };
+    let whole = run(None);
+    let first = run(Some(Shard { index: 1, count: 2 }));
+    let second = run(Some(Shard { index: 2, count: 2 }));
+    assert_eq!(first.discovered, 4);
+    assert_eq!(first.files + second.files, 4);
+
+    let merged = merge_shards(vec![vec![second], vec![first]]).unwrap();
+    assert_eq!(merged.len(), 1);
+    let merged = &merged[0];
+    assert_eq!(merged.shard, None);
+    assert_eq!(merged.files, whole.files);
+    assert_eq!(merged.total, whole.total);
+    assert_eq!(merged.passed, whole.passed);
+    assert_eq!(merged.failures, whole.failures);
+    assert_eq!(merged.known_failures, whole.known_failures);
+    assert_eq!(merged.directories, whole.directories);
+    assert_eq!(merged.throughput.bytes, whole.throughput.bytes);
+    assert!((merged.pass_rate - whole.pass_rate).abs() < f64::EPSILON);
+
+    let shard = |index| vec![run(Some(Shard { index, count: 2 }))];
+    assert!(merge_shards(vec![shard(1)]).is_err());
+    assert!(merge_shards(vec![shard(1), shard(1), shard(2)]).is_err());
+    assert!(merge_shards(vec![shard(1), vec![whole]]).is_err());
+    // out of range, as in a hand-edited report
+    let out_of_range = |index| {
+        let mut summary = shard(1);
+        summary[0].shard = Some(Shard { index, count: 2 });
+        summary
+    };
+    assert!(merge_shards(vec![out_of_range(0), shard(2)]).is_err());
+    assert!(merge_shards(vec![shard(1), shard(2), out_of_range(3)]).is_err());
+}
+
+#[test]
+fn memory() {
+    let run = |memory| {
+        let options = RunOptions {
+            quiet: true,
+            memory,
+            ..RunOptions::default()
+        };
+        run_suite(&Fixture(Strictness::Sloppy), &LexerPhase, &options).unwrap()
+    };
+    assert_eq!(run(false).memory, None);
+    let memory = run(true).memory.unwrap();
+    if cfg!(any(target_os = "linux", target_os = "macos")) {
+        assert!(memory.peak_rss_before.unwrap() <= memory.peak_rss_after.unwrap());
+    }
+    let largest = memory.largest_token_vec.unwrap();
+    let tokens = |path: &str| {
+        let code = fs::read_to_string(fixtures().join("suite").join(path)).unwrap();
+        lexer::Lexer::new(&code).count()
+    };
+    for path in ["a/pass.js", "a/fail.js", "b/pass.js", "b/c/fail.js"] {
+        assert!(tokens(path) <= largest.tokens, "{path}");
+    }
+    assert_eq!(largest.tokens, tokens(&largest.path));
+    assert_eq!(largest.bytes, token_vec_bytes(largest.tokens));
+}
+
+/// Fails at the first `@`, the fixture failures are the same as with the lexer
+struct AtSign;
+
+impl Phase for AtSign {
+    fn name(&self) -> &'static str {
+        "at-sign"
+    }
+
+    fn check(&self, source: &str, _strict: bool, _options: &RunOptions) -> CaseResult {
+        CaseResult {
+            error: source.find('@').map(|i| i..i + 1),
+            tokens: 0,
+        }
+    }
+}
+
+/// Fails in the strict mode variant only
+struct StrictOnly;
+
+impl Phase for StrictOnly {
+    fn name(&self) -> &'static str {
+        "strict-only"
+    }
+
+    fn check(&self, _source: &str, strict: bool, _options: &RunOptions) -> CaseResult {
+        CaseResult {
+            error: strict.then(|| 0..1),
+            tokens: 0,
+        }
+    }
+}
+
+#[test]
+fn parser_phase() {
+    let options = RunOptions::default();
+    // each line is an expression, the error is at its offset in the source
+    let result = ParserPhase.check("a + b\n\n(c\n", false, &options);
+    assert_eq!(result.error, Some(9..10));
+    assert_eq!(
+        ParserPhase.check("a\r\nb ? c : d", false, &options).error,
+        None
+    );
+    // future reserved words are only identifiers in sloppy mode
+    assert_eq!(ParserPhase.check("public + 1", false, &options).error, None);
+    assert_eq!(
+        ParserPhase.check("public + 1", true, &options).error,
+        Some(0..6)
+    );
+
+    // the fixtures of the parser, its syntax errors are known failures
+    let options = RunOptions {
+        quiet: true,
+        ..RunOptions::default()
+    };
+    let report = run_suite(&Expressions, &ParserPhase, &options).unwrap();
+    assert!(report.total >= 5);
+    assert!(report.known_failures > 0);
+    assert_eq!(report.failures.len(), 0);
+    assert!(report.unexpectedly_passing.is_empty());
+}
+
+#[test]
+fn phases() {
+    assert_eq!(phase_by_name("lexer").unwrap().name(), "lexer");
+    assert_eq!(phase_by_name("parser").unwrap().name(), "parser");
+    assert!(phase_by_name("linter").is_none());
+
+    let options = RunOptions {
+        quiet: true,
+        ..RunOptions::default()
+    };
+    let suite = Fixture(Strictness::Sloppy);
+    let lexer = run_suite(&suite, &LexerPhase, &options).unwrap();
+    let at_sign = run_suite(&suite, &AtSign, &options).unwrap();
+    assert_eq!((lexer.phase, at_sign.phase), ("lexer", "at-sign"));
+    assert_eq!(at_sign.passed, lexer.passed);
+    assert_eq!(at_sign.failures.len(), lexer.failures.len());
+    assert!(suite
+        .snapshot_path("at-sign")
+        .ends_with("fixture-at-sign.snap"));
+
+    // results are cached per phase
+    let mut cache = Cache::new(0);
+    run_suite_cached(&suite, &LexerPhase, &options, &mut cache).unwrap();
+    let cached = run_suite_cached(&suite, &AtSign, &options, &mut cache).unwrap();
+    assert_eq!((cached.cached, cache.len()), (0, 8));
+
+    // the strict mode variant is lexed as strict mode code
+    let sloppy = run_suite(&suite, &StrictOnly, &options).unwrap();
+    assert_eq!(sloppy.failures.len(), 0);
+    let both = run_suite(&Fixture(Strictness::Both), &StrictOnly, &options).unwrap();
+    assert_eq!((both.failures.len(), both.strict_failures), (4, 4));
+}
@@ -0,0 +1,7 @@
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+pub mod report;
+pub mod scan;
+
+pub use crate::report::*;
+pub use crate::scan::*;
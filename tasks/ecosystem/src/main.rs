@@ -0,0 +1,55 @@
+//! Lex every `.js`, `.mjs` and `.cjs` file under a directory, e.g. the `node_modules` of a big
+//! project, and report the files with errors by error kind and by package.
+//!
+//! ```text
+//! cargo ecosystem path/to/node_modules [--json report.json] [--samples dir] [--top 10] [--check]
+//! ```
+//!
+//! `--samples` writes a fixture per error kind of each failing file, to copy into
+//! `crates/lexer/tests/fixtures`. `--check` exits with 1 when any file has errors.
+
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+use pico_args::Arguments;
+use std::{fmt, fs, path::PathBuf, process};
+
+use ecosystem::{render_json, render_summary, scan, write_samples};
+
+/// Files with errors with `--check`
+const EXIT_FAILURES: i32 = 1;
+/// Invalid arguments
+const EXIT_ERROR: i32 = 2;
+
+/// Exit with `EXIT_ERROR` on invalid arguments
+fn or_exit<T, E: fmt::Display>(result: Result<T, E>) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("{e}");
+        process::exit(EXIT_ERROR);
+    })
+}
+
+fn main() {
+    let mut args = Arguments::from_env();
+    let check = args.contains("--check");
+    let json = or_exit(args.opt_value_from_str::<_, PathBuf>("--json"));
+    let samples = or_exit(args.opt_value_from_str::<_, PathBuf>("--samples"));
+    let top = or_exit(args.opt_value_from_str::<_, usize>("--top")).unwrap_or(10);
+    let root = or_exit(args.free_from_str::<PathBuf>());
+    if !root.is_dir() {
+        eprintln!("{} is not a directory", root.display());
+        process::exit(EXIT_ERROR);
+    }
+
+    let report = scan(&root);
+    print!("{}", render_summary(&report, top));
+    if let Some(path) = json {
+        or_exit(fs::write(path, render_json(&report, top)));
+    }
+    if let Some(dir) = samples {
+        let written = or_exit(write_samples(&report, &dir));
+        println!("\nWrote {} samples to {}", written.len(), dir.display());
+    }
+    if check && !report.failures.is_empty() {
+        process::exit(EXIT_FAILURES);
+    }
+}
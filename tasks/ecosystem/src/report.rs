@@ -0,0 +1,184 @@
+//! Summaries of a scan, and the samples as lexer fixtures
+
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt::Write, fs, io, path::Path};
+
+use crate::{FileReport, ScanReport};
+
+/// Files with errors of a package
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PackageFailures {
+    pub package: String,
+    pub files: usize,
+    pub errors: usize,
+}
+
+/// The report of `--json`
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Serialize)]
+pub struct JsonReport<'a> {
+    pub files: usize,
+    pub bytes: usize,
+    pub unreadable: usize,
+    pub seconds: f64,
+    pub megabytes_per_second: f64,
+    /// Failing files per error kind
+    pub by_kind: BTreeMap<String, usize>,
+    pub top_packages: Vec<PackageFailures>,
+    pub failures: &'a [FileReport],
+}
+
+impl ScanReport {
+    /// Lexed megabytes per second of lexing, `0.0` for an empty scan
+    #[must_use]
+    pub fn megabytes_per_second(&self) -> f64 {
+        let seconds = self.duration.as_secs_f64();
+        if seconds == 0.0 {
+            return 0.0;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let megabytes = self.bytes as f64 / 1_000_000.0;
+        megabytes / seconds
+    }
+
+    /// Number of failing files per error kind, a file counted once per kind
+    #[must_use]
+    pub fn by_kind(&self) -> BTreeMap<String, usize> {
+        let mut kinds = BTreeMap::new();
+        for sample in self.failures.iter().flat_map(|file| &file.samples) {
+            *kinds.entry(sample.kind.clone()).or_default() += 1;
+        }
+        kinds
+    }
+
+    /// The `n` packages with the most failing files, then the most errors
+    #[must_use]
+    pub fn top_packages(&self, n: usize) -> Vec<PackageFailures> {
+        let mut packages = BTreeMap::<&str, PackageFailures>::new();
+        for file in &self.failures {
+            let package = packages
+                .entry(&file.package)
+                .or_insert_with(|| PackageFailures {
+                    package: file.package.clone(),
+                    files: 0,
+                    errors: 0,
+                });
+            package.files += 1;
+            package.errors += file
+                .samples
+                .iter()
+                .map(|sample| sample.count)
+                .sum::<usize>();
+        }
+        let mut packages = packages.into_values().collect::<Vec<_>>();
+        // stable, ties stay sorted by name
+        packages.sort_by(|a, b| (b.files, b.errors).cmp(&(a.files, a.errors)));
+        packages.truncate(n);
+        packages
+    }
+}
+
+/// The human summary, with the `top` packages
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn render_summary(report: &ScanReport, top: usize) -> String {
+    let mut s = String::new();
+    writeln!(
+        s,
+        "Files: {} ({:.2} MB), {} not UTF-8",
+        report.files,
+        report.bytes as f64 / 1_000_000.0,
+        report.unreadable
+    )
+    .unwrap();
+    writeln!(
+        s,
+        "Throughput: {:.2} MB/s lexing, {:.2}s elapsed",
+        report.megabytes_per_second(),
+        report.elapsed.as_secs_f64()
+    )
+    .unwrap();
+    writeln!(s, "Failing Files: {}", report.failures.len()).unwrap();
+    if report.failures.is_empty() {
+        return s;
+    }
+    s.push_str("\nBy Error Kind:\n");
+    for (kind, files) in report.by_kind() {
+        writeln!(s, "  {kind}: {files}").unwrap();
+        if let Some((file, sample)) = report.failures.iter().find_map(|file| {
+            let sample = file.samples.iter().find(|sample| sample.kind == kind)?;
+            Some((file, sample))
+        }) {
+            writeln!(s, "    {}:{}:{}", file.path, sample.line, sample.column).unwrap();
+            writeln!(s, "    {}", sample.text.trim()).unwrap();
+        }
+    }
+    s.push_str("\nTop Packages:\n");
+    for package in report.top_packages(top) {
+        writeln!(
+            s,
+            "  {}: {} files, {} errors",
+            package.package, package.files, package.errors
+        )
+        .unwrap();
+    }
+    s
+}
+
+/// Render the report as JSON, see [`JsonReport`]
+/// # Panics
+/// Serialization failed
+#[must_use]
+pub fn render_json(report: &ScanReport, top: usize) -> String {
+    let json = JsonReport {
+        files: report.files,
+        bytes: report.bytes,
+        unreadable: report.unreadable,
+        seconds: report.duration.as_secs_f64(),
+        megabytes_per_second: report.megabytes_per_second(),
+        by_kind: report.by_kind(),
+        top_packages: report.top_packages(top),
+        failures: &report.failures,
+    };
+    serde_json::to_string_pretty(&json).unwrap()
+}
+
+/// `@scope/name` as `scope-name`, and any other char of a file name as `-`
+fn file_stem(s: &str) -> String {
+    s.trim_start_matches('@')
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Write a fixture per error kind of each failing file to `dir`, as `<package>-<kind>-<n>.js`
+/// with the sample line under a comment of its location, to copy into
+/// `crates/lexer/tests/fixtures`. Returns the paths of the written fixtures.
+/// # Errors
+/// IO error
+pub fn write_samples(report: &ScanReport, dir: &Path) -> io::Result<Vec<String>> {
+    fs::create_dir_all(dir)?;
+    let mut written = vec![];
+    let mut counts = BTreeMap::<String, usize>::new();
+    for file in &report.failures {
+        for sample in &file.samples {
+            let stem = format!("{}-{}", file_stem(&file.package), sample.kind);
+            let n = counts.entry(stem.clone()).or_default();
+            *n += 1;
+            let name = format!("{stem}-{n}.js");
+            let text = format!(
+                "// {}:{}:{}\n{}\n",
+                file.path, sample.line, sample.column, sample.text
+            );
+            fs::write(dir.join(&name), text)?;
+            written.push(name);
+        }
+    }
+    Ok(written)
+}
@@ -0,0 +1,194 @@
+//! Lex every JavaScript file under a directory
+
+use rayon::prelude::*;
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+use walkdir::WalkDir;
+
+use lexer::Lexer;
+
+/// Extensions of the lexed files
+pub const EXTENSIONS: [&str; 3] = ["js", "mjs", "cjs"];
+
+/// Longest sample in chars, a minified file is a single line
+pub const MAX_SAMPLE_LEN: usize = 160;
+
+/// The first error of a kind in a file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Sample {
+    /// Name of the `ErrorKind`, e.g. `UnterminatedString`
+    pub kind: String,
+    /// Errors of this kind in the file
+    pub count: usize,
+    /// 1-based
+    pub line: usize,
+    /// 1-based, in chars
+    pub column: usize,
+    /// The line of the error, cut to [`MAX_SAMPLE_LEN`] chars around it
+    pub text: String,
+}
+
+/// A file with errors
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileReport {
+    /// Relative to the parent of the scanned directory, with `/` separators,
+    /// so that it starts with `node_modules/` for a scan of `node_modules`
+    pub path: String,
+    /// See [`package_name`], the path for a file outside of `node_modules`
+    pub package: String,
+    /// A sample per error kind, by kind name
+    pub samples: Vec<Sample>,
+}
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Default)]
+pub struct ScanReport {
+    pub files: usize,
+    pub bytes: usize,
+    /// Files which are not UTF-8, and not lexed
+    pub unreadable: usize,
+    /// Time spent lexing, summed over the rayon workers
+    pub duration: Duration,
+    /// Wall time of the scan, reading the files included
+    pub elapsed: Duration,
+    /// Sorted by path
+    pub failures: Vec<FileReport>,
+}
+
+/// Whether `path` has one of the [`EXTENSIONS`]
+#[must_use]
+pub fn is_javascript(path: &Path) -> bool {
+    path.extension()
+        .and_then(std::ffi::OsStr::to_str)
+        .map_or(false, |extension| EXTENSIONS.contains(&extension))
+}
+
+/// The package of a file from the directory after its last `node_modules`,
+/// `@scope/name` for a scoped package. `None` outside of `node_modules`.
+#[must_use]
+pub fn package_name(path: &str) -> Option<String> {
+    let (_, rest) = path.rsplit_once("node_modules/")?;
+    let mut parts = rest.split('/');
+    let name = parts.next()?;
+    // a file directly in `node_modules` is not in a package
+    parts.clone().next()?;
+    if name.starts_with('@') {
+        let scoped = parts.next()?;
+        parts.next()?;
+        return Some(format!("{name}/{scoped}"));
+    }
+    Some(name.to_string())
+}
+
+/// The start of the char at `offset` or before it, an error may split a char
+fn char_boundary(source: &str, mut offset: usize) -> usize {
+    offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+/// Line and column of `offset`, and the text of its line cut around it
+#[must_use]
+pub fn locate(source: &str, offset: usize) -> (usize, usize, String) {
+    let offset = char_boundary(source, offset);
+    let is_line_break = |c| matches!(c, '\n' | '\r' | '\u{2028}' | '\u{2029}');
+    let mut line = 1;
+    let mut line_start = 0;
+    let mut chars = source[..offset].char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        // `\r\n` is a single line break
+        if is_line_break(c) && !(c == '\r' && matches!(chars.peek(), Some((_, '\n')))) {
+            line += 1;
+            line_start = i + c.len_utf8();
+        }
+    }
+    let line_end = source[offset..]
+        .find(is_line_break)
+        .map_or(source.len(), |i| offset + i);
+    let column = source[line_start..offset].chars().count();
+    let text = source[line_start..line_end]
+        .chars()
+        .skip(column.saturating_sub(MAX_SAMPLE_LEN / 2))
+        .take(MAX_SAMPLE_LEN)
+        .collect();
+    (line, column + 1, text)
+}
+
+/// The samples of the errors of `source`, `None` without errors
+#[must_use]
+#[allow(clippy::module_name_repetitions)]
+pub fn scan_file(path: &str, source: &str) -> Option<FileReport> {
+    let mut lexer = Lexer::new(source);
+    lexer.by_ref().count();
+    let mut samples = BTreeMap::<String, Sample>::new();
+    for error in lexer.errors_sorted() {
+        let kind = format!("{:?}", error.kind());
+        if let Some(sample) = samples.get_mut(&kind) {
+            sample.count += 1;
+            continue;
+        }
+        let (line, column, text) = locate(source, error.span().start);
+        let sample = Sample {
+            kind: kind.clone(),
+            count: 1,
+            line,
+            column,
+            text,
+        };
+        samples.insert(kind, sample);
+    }
+    if samples.is_empty() {
+        return None;
+    }
+    Some(FileReport {
+        path: path.to_string(),
+        package: package_name(path).unwrap_or_else(|| path.to_string()),
+        samples: samples.into_values().collect(),
+    })
+}
+
+/// Lex every JavaScript file under `root` in parallel
+#[must_use]
+pub fn scan(root: &Path) -> ScanReport {
+    let start = Instant::now();
+    let base = root.parent().unwrap_or(root);
+    let paths = WalkDir::new(root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file() && is_javascript(entry.path()))
+        .map(walkdir::DirEntry::into_path)
+        .collect::<Vec<_>>();
+    let results = paths
+        .par_iter()
+        .map(|path| {
+            let relative = path.strip_prefix(base).unwrap_or(path);
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            let source = fs::read_to_string(path).ok()?;
+            let start = Instant::now();
+            let failure = scan_file(&relative, &source);
+            Some((source.len(), start.elapsed(), failure))
+        })
+        .collect::<Vec<_>>();
+    let mut report = ScanReport::default();
+    for result in results {
+        match result {
+            Some((bytes, duration, failure)) => {
+                report.files += 1;
+                report.bytes += bytes;
+                report.duration += duration;
+                report.failures.extend(failure);
+            }
+            None => report.unreadable += 1,
+        }
+    }
+    report.elapsed = start.elapsed();
+    report
+}
@@ -0,0 +1,147 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use ecosystem::{
+    is_javascript, locate, package_name, render_summary, scan, scan_file, write_samples,
+    PackageFailures,
+};
+
+/// A `node_modules` with a passing package, a scoped package with two failing files,
+/// and a package with an unterminated string
+fn node_modules(name: &str) -> PathBuf {
+    let root = std::env::temp_dir()
+        .join(format!("ecosystem-{name}-{}", std::process::id()))
+        .join("node_modules");
+    let files = [
+        ("ok/index.js", "export const a = 1;\n"),
+        ("ok/README.md", "a @ b"),
+        ("@scope/pkg/lib/a.mjs", "let a = 1;\nlet b = a @ b @ c;\n"),
+        ("@scope/pkg/lib/b.js", "a @ b"),
+        ("c/index.cjs", "module.exports = 'c\n"),
+    ];
+    for (path, source) in files {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, source).unwrap();
+    }
+    root
+}
+
+#[test]
+fn javascript_files() {
+    assert!(is_javascript(Path::new("a/b.js")));
+    assert!(is_javascript(Path::new("a/b.mjs")));
+    assert!(is_javascript(Path::new("a/b.cjs")));
+    assert!(!is_javascript(Path::new("a/b.json")));
+    assert!(!is_javascript(Path::new("a/js")));
+}
+
+#[test]
+fn packages() {
+    assert_eq!(
+        package_name("node_modules/a/index.js").as_deref(),
+        Some("a")
+    );
+    assert_eq!(
+        package_name("node_modules/@s/a/lib/index.js").as_deref(),
+        Some("@s/a")
+    );
+    // nested, in the innermost package
+    assert_eq!(
+        package_name("node_modules/a/node_modules/b/b.js").as_deref(),
+        Some("b")
+    );
+    assert_eq!(package_name("node_modules/a.js"), None);
+    assert_eq!(package_name("node_modules/@s/a.js"), None);
+    assert_eq!(package_name("src/a.js"), None);
+}
+
+#[test]
+fn locations() {
+    let source = "a\r\nb\nc€ d";
+    assert_eq!(locate(source, 0), (1, 1, "a".to_string()));
+    assert_eq!(locate(source, 3), (2, 1, "b".to_string()));
+    assert_eq!(locate(source, 10), (3, 4, "c€ d".to_string()));
+    // within the `€`
+    assert_eq!(locate(source, 7), (3, 2, "c€ d".to_string()));
+    let long = format!("{}@{}", "a".repeat(200), "b".repeat(200));
+    let (line, column, text) = locate(&long, 200);
+    assert_eq!((line, column), (1, 201));
+    assert_eq!(text.find('@'), Some(80));
+}
+
+#[test]
+fn file_samples() {
+    assert_eq!(scan_file("a.js", "let a = 1;"), None);
+    let report = scan_file("node_modules/a/a.js", "a @ b @\nc @").unwrap();
+    assert_eq!(report.package, "a");
+    assert_eq!(report.samples.len(), 1);
+    let sample = &report.samples[0];
+    assert_eq!(sample.count, 3);
+    assert_eq!((sample.line, sample.column), (1, 3));
+    assert_eq!(sample.text, "a @ b @");
+}
+
+#[test]
+fn scan_node_modules() {
+    let root = node_modules("scan");
+    let report = scan(&root);
+    assert_eq!(report.files, 4);
+    assert_eq!(report.unreadable, 0);
+    let paths = report
+        .failures
+        .iter()
+        .map(|file| file.path.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        [
+            "node_modules/@scope/pkg/lib/a.mjs",
+            "node_modules/@scope/pkg/lib/b.js",
+            "node_modules/c/index.cjs",
+        ]
+    );
+    let by_kind = report.by_kind();
+    assert_eq!(by_kind.values().sum::<usize>(), 3);
+    assert!(by_kind.contains_key("UnterminatedString"), "{by_kind:?}");
+    assert_eq!(
+        report.top_packages(1),
+        [PackageFailures {
+            package: "@scope/pkg".to_string(),
+            files: 2,
+            errors: 3,
+        }]
+    );
+    assert_eq!(report.top_packages(10).len(), 2);
+
+    let summary = render_summary(&report, 10);
+    assert!(summary.contains("Failing Files: 3"), "{summary}");
+    assert!(
+        summary.contains("@scope/pkg: 2 files, 3 errors"),
+        "{summary}"
+    );
+    assert!(
+        summary.contains("node_modules/c/index.cjs:1:18"),
+        "{summary}"
+    );
+}
+
+#[test]
+fn samples() {
+    let root = node_modules("samples");
+    let report = scan(&root);
+    let dir = root.with_file_name("samples");
+    let written = write_samples(&report, &dir).unwrap();
+    assert_eq!(written.len(), 3);
+    assert!(
+        written.contains(&"c-UnterminatedString-1.js".to_string()),
+        "{written:?}"
+    );
+    let fixture = fs::read_to_string(dir.join("c-UnterminatedString-1.js")).unwrap();
+    assert_eq!(
+        fixture,
+        "// node_modules/c/index.cjs:1:18\nmodule.exports = 'c\n"
+    );
+}